@@ -38,7 +38,7 @@
 //! ```
 
 use holochain_serialized_bytes::prelude::*;
-use holochain_types::dna::{wasm::DnaWasm, zome::Zome, DnaDef, DnaFile};
+use holochain_types::dna::{wasm::DnaWasm, zome::Zome, DnaDef, DnaFile, PublishRateLimits};
 use holochain_zome_types::zome::ZomeName;
 use std::{collections::BTreeMap, path::PathBuf};
 
@@ -193,6 +193,8 @@ struct DnaDefJson {
     pub uuid: String,
     pub properties: serde_json::Value,
     pub zomes: BTreeMap<ZomeName, ZomeJson>,
+    #[serde(default)]
+    pub rate_limits: PublishRateLimits,
 }
 
 impl DnaDefJson {
@@ -213,6 +215,7 @@ impl DnaDefJson {
             uuid: dna.uuid,
             properties: properties.0,
             zomes,
+            rate_limits: dna.rate_limits,
         })
     }
 
@@ -245,6 +248,7 @@ impl DnaDefJson {
             uuid: self.uuid.clone(),
             properties,
             zomes,
+            rate_limits: self.rate_limits.clone(),
         };
 
         Ok(DnaFile::new(dna, wasm_list).await?)
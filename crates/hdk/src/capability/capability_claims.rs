@@ -0,0 +1,29 @@
+use crate::prelude::*;
+
+/// List the CapClaims on the local source chain, newest first.
+///
+/// Claims can be filtered by the agent who granted them and by tag. Pass `None` for either to
+/// not filter on it.
+///
+/// This is how a zome finds the secret to pass to `call_remote` for a given agent:
+///
+/// ```ignore
+/// let secret = capability_claims(Some(bob.clone()), Some("friends".into()))?
+///   .first()
+///   .map(|claim| *claim.secret());
+/// call_remote(bob, zome_info()?.zome_name, "hello".into(), secret, &())?;
+/// ```
+///
+/// As with any claim, there is no guarantee the grantor has not since revoked the grant.
+///
+/// @see create_cap_claim
+pub fn capability_claims(
+    grantor: Option<AgentPubKey>,
+    tag: Option<String>,
+) -> HdkResult<Vec<CapClaim>> {
+    Ok(host_call::<CapabilityClaimsInput, CapabilityClaimsOutput>(
+        __capability_claims,
+        &CapabilityClaimsInput::new((grantor, tag)),
+    )?
+    .into_inner())
+}
@@ -0,0 +1,24 @@
+use crate::prelude::*;
+
+/// List the live CapGrants on the local source chain, newest first.
+///
+/// Pass a tag to only list the grants with that tag, or `None` to list them all.
+///
+/// Only grants that have not been updated or deleted are listed. Each one comes with the
+/// HeaderHash of the element that created it, which can be passed to `update_cap_grant` or
+/// `delete_cap_grant`.
+///
+/// ```ignore
+/// for (header_hash, grant) in capability_grants(Some("friends".into()))? {
+///   delete_cap_grant(header_hash)?;
+/// }
+/// ```
+///
+/// @see create_cap_grant
+pub fn capability_grants(tag: Option<String>) -> HdkResult<Vec<(HeaderHash, CapGrantEntry)>> {
+    Ok(host_call::<CapabilityGrantsInput, CapabilityGrantsOutput>(
+        __capability_grants,
+        &CapabilityGrantsInput::new(tag),
+    )?
+    .into_inner())
+}
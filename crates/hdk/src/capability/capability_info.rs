@@ -0,0 +1,18 @@
+use crate::prelude::*;
+
+/// Get the capability the current zome call is running under.
+/// There are no inputs to capability_info.
+///
+/// The CapInfo holds the agent who made the call and the grant that authorized it.
+/// The grant is `CapGrant::ChainAuthor` when the current agent called their own zome, otherwise
+/// it is the grant committed by this agent that matched the caller and their secret.
+///
+/// Callbacks such as `init` are run by the conductor on behalf of the current agent, so they
+/// always see the current agent as the caller.
+pub fn capability_info() -> HdkResult<CapInfo> {
+    Ok(host_call::<CapabilityInfoInput, CapabilityInfoOutput>(
+        __capability_info,
+        &CapabilityInfoInput::new(()),
+    )?
+    .into_inner())
+}
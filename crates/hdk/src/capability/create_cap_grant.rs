@@ -5,9 +5,19 @@ use crate::prelude::*;
 /// When an agent wants to expose externs to be called remotely by other agents they need to select
 /// a security model and probably generate a secret.
 ///
-/// The input needs to evalute to a `ZomeCallCapGrant` struct which defines the tag, access and
-/// granted zome/function pairs. The access is a `CapAccess` enum with variants `Unrestricted`,
-/// `Transferable`, and `Assigned`.
+/// The input needs to evalute to a `ZomeCallCapGrant` struct which defines the tag, access,
+/// granted zome/function pairs, curried payloads and limits. The access is a `CapAccess` enum with
+/// variants `Unrestricted`, `Transferable`, and `Assigned`.
+///
+/// The `CurryPayloads` fix arguments for any of the granted functions. When the grant is used the
+/// curried payload is merged into the payload the caller sent, overwriting any fields of the same
+/// name, so a grant to `read_channel` can be limited to a single channel without writing a
+/// dedicated extern for it. Use `CurryPayloads::default()` to curry nothing.
+///
+/// The `CapLimits` can make a grant expire at a `Timestamp` and/or only be usable for a number of
+/// zome calls. Uses are counted by the grantor's conductor, so expired and used up grants are
+/// rejected without the granted functions having to check for themselves. Use
+/// `CapLimits::default()` for a grant that lasts until it is deleted.
 ///
 /// The tag is an arbitrary `String` the developer or users can use to categorise and administer
 /// grants committed to the chain. The tag should also match the `CapClaim` tags committed on the
@@ -35,7 +45,7 @@ use crate::prelude::*;
 /// provided a valid transferable secret to a function that is currently unrestricted, the zome
 /// call will be executed with the stricter transferable access.
 ///
-/// @todo this is more relevant when partial application exists in the future
+/// This matters for curried payloads as only the payloads of the grant that is used are curried.
 /// @todo predictably disambiguate multiple CapGrants of the same specificity
 ///
 /// CapGrant entries can be updated and deleted in the same way as standard app entries.
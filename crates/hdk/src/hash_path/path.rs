@@ -262,7 +262,7 @@ impl Path {
 
     /// Does an entry exist at the hash we expect?
     pub fn exists(&self) -> Result<bool, HdkError> {
-        Ok(get(self.hash()?, GetOptions)?.is_some())
+        Ok(get(self.hash()?, GetOptions::default())?.is_some())
     }
 
     /// Recursively touch this and every parent that doesn't exist yet.
@@ -3,6 +3,7 @@ pub mod call;
 pub mod call_remote;
 pub mod create;
 pub mod create_link;
+pub mod create_x25519_keypair;
 pub mod debug;
 pub mod decrypt;
 pub mod delete;
@@ -0,0 +1,27 @@
+use crate::prelude::*;
+
+// Newer than the externs declared by `holochain_externs!`.
+extern "C" {
+    fn __create_x25519_keypair(guest_allocation_ptr: GuestPtr) -> Len;
+}
+
+/// Create a new x25519 keypair in the keystore and return the public key.
+///
+/// ```ignore
+/// let key = create_x25519_keypair()?;
+/// ```
+///
+/// The private key never leaves the keystore. The public key can be shared with other agents
+/// so that they can encrypt data for this agent.
+///
+/// @see encrypt
+/// @see decrypt
+pub fn create_x25519_keypair() -> HdkResult<X25519PubKey> {
+    Ok(
+        host_call::<CreateX25519KeypairInput, CreateX25519KeypairOutput>(
+            __create_x25519_keypair,
+            &CreateX25519KeypairInput::new(()),
+        )?
+        .into_inner(),
+    )
+}
@@ -1 +1,21 @@
-//! @todo
+use crate::prelude::*;
+
+/// Decrypt some data that was encrypted from one x25519 key to another.
+///
+/// ```ignore
+/// let decrypted = decrypt(Decrypt::new(bob, alice, encrypted))?;
+/// ```
+///
+/// The private key for the recipient must be in the keystore of the conductor running the zome.
+///
+/// Returns `None` if the data could not be decrypted and authenticated as being sent by the
+/// sender, i.e. it was tampered with, encrypted for someone else or sent by someone else.
+///
+/// @see encrypt
+pub fn decrypt(input: Decrypt) -> HdkResult<Option<Vec<u8>>> {
+    Ok(
+        host_call::<DecryptInput, DecryptOutput>(__decrypt, &DecryptInput::new(input))?
+            .into_inner()
+            .map(|bytes| bytes.into_vec()),
+    )
+}
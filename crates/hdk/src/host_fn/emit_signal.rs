@@ -5,18 +5,35 @@
 
 use crate::prelude::*;
 
-// TODO: we could consider adding a (optional?) "type" parameter, so that
-// statically typed languages can more easily get a hint of what type to
-// deserialize to. This of course requires a corresponding change to the
-// Signal type.
+/// Emit a signal with no type discriminator.
+///
+/// @see emit_typed_signal
 pub fn emit_signal<'a, D: 'a>(data: &'a D) -> HdkResult<()>
 where
     SerializedBytes: TryFrom<&'a D, Error = SerializedBytesError>,
 {
     let sb = SerializedBytes::try_from(data)?;
+    emit(AppSignal::new(sb))
+}
+
+/// Emit a signal tagged with a `signal_type`.
+///
+/// Clients can subscribe to only the signal types they care about, and statically typed
+/// clients can use the type as a hint of what to deserialize the data to.
+pub fn emit_typed_signal<'a, S, D: 'a>(signal_type: S, data: &'a D) -> HdkResult<()>
+where
+    S: Into<String>,
+    SerializedBytes: TryFrom<&'a D, Error = SerializedBytesError>,
+{
+    let sb = SerializedBytes::try_from(data)?;
+    emit(AppSignal::typed(signal_type, sb))
+}
+
+fn emit(signal: AppSignal) -> HdkResult<()> {
     #[allow(clippy::unit_arg)]
-    Ok(
-        host_call::<EmitSignalInput, EmitSignalOutput>(__emit_signal, &EmitSignalInput::new(sb))?
-            .into_inner(),
-    )
+    Ok(host_call::<EmitSignalInput, EmitSignalOutput>(
+        __emit_signal,
+        &EmitSignalInput::new(signal),
+    )?
+    .into_inner())
 }
@@ -1 +1,24 @@
-//! @todo
+use crate::prelude::*;
+
+/// Encrypt some data from one x25519 key to another.
+///
+/// ```ignore
+/// let alice = create_x25519_keypair()?;
+/// let encrypted = encrypt(Encrypt::new(alice, bob, b"hi bob".to_vec()))?;
+/// ```
+///
+/// The private key for the sender must be in the keystore of the conductor running the zome,
+/// e.g. created with `create_x25519_keypair`. The private key never leaves the keystore.
+///
+/// The data is encrypted and authenticated with xsalsa20poly1305 using a key derived from the
+/// sender and recipient keys, so only the holder of the recipient's private key can decrypt it
+/// and they can be sure it was encrypted by the holder of the sender's private key.
+///
+/// Use `Encrypt::to_self` with the same key as sender and recipient for symmetric encryption,
+/// e.g. for private data stored on the source chain.
+pub fn encrypt(input: Encrypt) -> HdkResult<XSalsa20Poly1305EncryptedData> {
+    Ok(
+        host_call::<EncryptInput, EncryptOutput>(__encrypt, &EncryptInput::new(input))?
+            .into_inner(),
+    )
+}
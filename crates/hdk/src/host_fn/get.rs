@@ -10,13 +10,18 @@ use crate::prelude::*;
 /// An update to an element does not change its liveness.
 /// @see get_details for more information about how CRUD elements reference each other.
 ///
-/// Note: `get` __always triggers and blocks on a network call__.
-///       @todo implement a 'get optimistic' that returns based on the current opinion of the world
-///       and performs network calls in the background so they are available 'next time'.
+/// Note: With `GetOptions::latest()` (the default) `get` __always triggers and blocks on a
+///       network call__ so the latest deletes are known.
+///       `GetOptions::content()` returns the element if it is already held locally, regardless
+///       of deletes, and only goes to the network otherwise.
+///       `GetOptions::local()` never goes to the network.
+///       The network timeout and how many agents are asked can also be set on the options.
 ///
-/// Note: Deletes are considered in the liveness but Updates are not currently followed
-///       automatically due to the need for the happ to disambiguate update logic.
-///       @todo implement 'redirect' logic so that updates are followed by `get`.
+/// Note: Deletes are considered in the liveness but Updates are not followed by default
+///       due to the need for the happ to disambiguate update logic.
+///       `GetOptions::follow_updates()` follows the live updates to the end of the update chain
+///       and returns the latest version. When the chain forks the oldest update is followed, and
+///       an update back to an entry that's already in the chain is never followed.
 ///
 /// Note: Updates typically point to a different entry hash than what they are updating but not
 ///       always, e.g. consider changing `foo` to `bar` back to `foo`. The entry hashes in a crud
@@ -1 +1,32 @@
-//! @todo
+use crate::prelude::*;
+
+/// Ask the conductor to call a function in this zome at some point in the future.
+///
+/// ```ignore
+/// // call `tick` in this zome once an hour, starting in one minute
+/// schedule(Schedule::repeating(
+///     "tick",
+///     ().try_into()?,
+///     core::time::Duration::from_secs(60),
+///     core::time::Duration::from_secs(60 * 60),
+/// ))?;
+/// ```
+///
+/// Schedules are persisted by the conductor alongside the source chain so they survive a
+/// restart. They are only persisted if the zome call that created them succeeds.
+///
+/// The scheduled function is called like any other zome function, as the agent that owns the
+/// cell, so it has full access to the host and can commit to the source chain, emit signals, etc.
+/// It receives the payload from the `Schedule` as its input and its output is discarded.
+///
+/// Scheduled functions are called "at most once" per firing. If the conductor is not running
+/// when a function is due it will be called as soon as the conductor starts again. A repeating
+/// function that missed several intervals while the conductor was down is called only once and
+/// then continues at its interval from that point.
+pub fn schedule(schedule: Schedule) -> HdkResult<()> {
+    #[allow(clippy::unit_arg)]
+    Ok(
+        host_call::<ScheduleInput, ScheduleOutput>(__schedule, &ScheduleInput::new(schedule))?
+            .into_inner(),
+    )
+}
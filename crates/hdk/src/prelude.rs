@@ -1,3 +1,6 @@
+pub use crate::capability::capability_claims::capability_claims;
+pub use crate::capability::capability_grants::capability_grants;
+pub use crate::capability::capability_info::capability_info;
 pub use crate::capability::create_cap_claim::create_cap_claim;
 pub use crate::capability::create_cap_grant::create_cap_grant;
 pub use crate::capability::delete_cap_grant::delete_cap_grant;
@@ -23,9 +26,13 @@ pub use crate::host_fn::call::call;
 pub use crate::host_fn::call_remote::call_remote;
 pub use crate::host_fn::create::create;
 pub use crate::host_fn::create_link::create_link;
+pub use crate::host_fn::create_x25519_keypair::create_x25519_keypair;
+pub use crate::host_fn::decrypt::decrypt;
 pub use crate::host_fn::delete::delete;
 pub use crate::host_fn::delete_link::delete_link;
 pub use crate::host_fn::emit_signal::emit_signal;
+pub use crate::host_fn::emit_signal::emit_typed_signal;
+pub use crate::host_fn::encrypt::encrypt;
 pub use crate::host_fn::get::get;
 pub use crate::host_fn::get_agent_activity::get_agent_activity;
 pub use crate::host_fn::get_details::get_details;
@@ -34,6 +41,7 @@ pub use crate::host_fn::get_links::get_links;
 pub use crate::host_fn::hash_entry::hash_entry;
 pub use crate::host_fn::query::query;
 pub use crate::host_fn::random_bytes::random_bytes;
+pub use crate::host_fn::schedule::schedule;
 pub use crate::host_fn::sign::sign;
 pub use crate::host_fn::sys_time::sys_time;
 pub use crate::host_fn::update::update;
@@ -63,6 +71,8 @@ pub use holochain_zome_types::debug_msg;
 pub use holochain_zome_types::element::{Element, ElementVec};
 pub use holochain_zome_types::entry::*;
 pub use holochain_zome_types::entry_def::*;
+pub use holochain_zome_types::genesis::GenesisSelfCheckCallbackResult;
+pub use holochain_zome_types::genesis::GenesisSelfCheckData;
 pub use holochain_zome_types::header::*;
 pub use holochain_zome_types::init::InitCallbackResult;
 pub use holochain_zome_types::link::LinkDetails;
@@ -74,8 +84,13 @@ pub use holochain_zome_types::migrate_agent::MigrateAgentCallbackResult;
 pub use holochain_zome_types::post_commit::PostCommitCallbackResult;
 pub use holochain_zome_types::query::ActivityRequest;
 pub use holochain_zome_types::query::AgentActivity;
+pub use holochain_zome_types::query::ChainFork;
+pub use holochain_zome_types::query::ChainHead;
 pub use holochain_zome_types::query::ChainQueryFilter as QueryFilter;
 pub use holochain_zome_types::query::ChainQueryFilter;
+pub use holochain_zome_types::query::ChainStatus;
+pub use holochain_zome_types::schedule::Schedule;
+pub use holochain_zome_types::signal::AppSignal;
 pub use holochain_zome_types::signature::Sign;
 pub use holochain_zome_types::signature::Signature;
 pub use holochain_zome_types::signature::VerifySignature;
@@ -87,6 +102,13 @@ pub use holochain_zome_types::validate::ValidationPackageCallbackResult;
 pub use holochain_zome_types::validate_link::ValidateCreateLinkData;
 pub use holochain_zome_types::validate_link::ValidateDeleteLinkData;
 pub use holochain_zome_types::validate_link::ValidateLinkCallbackResult;
+pub use holochain_zome_types::warrant::SignedWarrant;
+pub use holochain_zome_types::warrant::Warrant;
+pub use holochain_zome_types::warrant::WarrantProof;
+pub use holochain_zome_types::x_salsa20_poly1305::Decrypt;
+pub use holochain_zome_types::x_salsa20_poly1305::Encrypt;
+pub use holochain_zome_types::x_salsa20_poly1305::X25519PubKey;
+pub use holochain_zome_types::x_salsa20_poly1305::XSalsa20Poly1305EncryptedData;
 pub use holochain_zome_types::zome::FunctionName;
 pub use holochain_zome_types::zome::ZomeName;
 pub use holochain_zome_types::zome_info::ZomeInfo;
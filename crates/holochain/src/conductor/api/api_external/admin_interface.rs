@@ -4,7 +4,6 @@ use crate::conductor::api::error::{
 };
 use crate::conductor::{
     config::AdminInterfaceConfig,
-    error::CreateAppError,
     interface::error::{InterfaceError, InterfaceResult},
     ConductorHandle,
 };
@@ -13,6 +12,7 @@ use holochain_keystore::KeystoreSenderExt;
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::{
     app::{InstallAppDnaPayload, InstallAppPayload, InstalledApp, InstalledAppId, InstalledCell},
+    autonomic::AutonomicCue,
     cell::CellId,
     dna::{DnaFile, JsonProperties},
 };
@@ -152,12 +152,7 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 errors
                     .into_iter()
                     // We only care about this app for the activate command
-                    .find(|cell_error| match cell_error {
-                        CreateAppError::Failed {
-                            installed_app_id: error_app_id,
-                            ..
-                        } => error_app_id == &installed_app_id,
-                    })
+                    .find(|cell_error| cell_error.installed_app_id() == &installed_app_id)
                     // There was an error in this app so return it
                     .map(|this_app_error| Ok(AdminResponse::Error(this_app_error.into())))
                     // No error, return success
@@ -179,10 +174,45 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppInterfaceAttached { port })
             }
+            MigrateAgent {
+                installed_app_id,
+                dna,
+            } => {
+                let InstallAppDnaPayload {
+                    path,
+                    properties,
+                    membrane_proof,
+                    nick,
+                } = dna;
+
+                // Install the Dna being moved to
+                let dna = read_parse_dna(path, properties).await?;
+                let dna_hash = dna.dna_hash().clone();
+                self.conductor_handle.install_dna(dna).await?;
+
+                // Close the old chain and open the new one
+                let app = self
+                    .conductor_handle
+                    .clone()
+                    .migrate_agent(installed_app_id.clone(), nick, dna_hash, membrane_proof)
+                    .await?;
+
+                // Create the new cell if the app is active
+                let errors = self.conductor_handle.clone().setup_cells().await?;
+                errors
+                    .into_iter()
+                    .find(|cell_error| cell_error.installed_app_id() == &installed_app_id)
+                    .map(|this_app_error| Ok(AdminResponse::Error(this_app_error.into())))
+                    .unwrap_or(Ok(AdminResponse::AgentMigrated(app)))
+            }
             DumpState { cell_id } => {
                 let state = self.conductor_handle.dump_cell_state(&cell_id).await?;
                 Ok(AdminResponse::StateDumped(state))
             }
+            CueAutonomicProcess { cell_id, cue } => {
+                self.conductor_handle.autonomic_cue(cue, &cell_id).await?;
+                Ok(AdminResponse::AutonomicProcessCued)
+            }
         }
     }
 }
@@ -352,6 +382,25 @@ pub enum AdminRequest {
         /// OS choose a free port
         port: Option<u16>,
     },
+    /// Move the agent of the `Cell` with the nick given in `dna` within the `App`
+    /// specified by argument `installed_app_id` over to the `Dna` read from the path in `dna`.
+    /// The `migrate_agent` callbacks of both `Dna`s are run and any zome can veto the move.
+    /// If none do, the old source chain is closed with a pointer forward to the new `Dna`,
+    /// a new `Cell` is created whose chain opens with a pointer back to the old `Dna`,
+    /// and the new `Cell` replaces the old one in the `App`.
+    /// The agent can't be moved to a `Dna` they already have a `Cell` for in any `App`.
+    ///
+    /// Will be responded to with an [`AdminResponse::AgentMigrated`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::AgentMigrated`]: enum.AdminResponse.html#variant.AgentMigrated
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    MigrateAgent {
+        /// The InstalledAppId containing the `Cell` to migrate
+        installed_app_id: InstalledAppId,
+        /// The `Dna` to migrate to, whose nick picks the `Cell` to replace
+        dna: InstallAppDnaPayload,
+    },
     /// Dump the full state of the `Cell` specified by argument `cell_id`,
     /// including its chain, as a string containing JSON.
     ///
@@ -364,6 +413,20 @@ pub enum AdminRequest {
         /// The `CellId` for which to dump state
         cell_id: Box<CellId>,
     },
+    /// Run one of the autonomic processes of the `Cell` specified by argument `cell_id`
+    /// now, rather than waiting for its next scheduled run.
+    ///
+    /// Will be responded to with an [`AdminResponse::AutonomicProcessCued`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::AutonomicProcessCued`]: enum.AdminResponse.html#variant.AutonomicProcessCued
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    CueAutonomicProcess {
+        /// The `CellId` whose process to run
+        cell_id: Box<CellId>,
+        /// Which process to run
+        cue: AutonomicCue,
+    },
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -451,6 +514,14 @@ pub enum AdminResponse {
     ///
     /// [`AdminRequest::DeactivateApp`]: enum.AdminRequest.html#variant.DeactivateApp
     AppDeactivated,
+    /// The succesful response to an [`AdminRequest::MigrateAgent`].
+    ///
+    /// The resulting [`InstalledApp`] contains the new [`CellId`] in place of the old one.
+    ///
+    /// [`AdminRequest::MigrateAgent`]: enum.AdminRequest.html#variant.MigrateAgent
+    /// [`InstalledApp`]: ../../../holochain_types/app/struct.InstalledApp.html
+    /// [`CellId`]: ../../../holochain_types/cell/struct.CellId.html
+    AgentMigrated(InstalledApp),
     /// The succesful response to an [`AdminRequest::DumpState`].
     ///
     /// The result contains a string of serialized JSON data which can be deserialized to access the
@@ -458,6 +529,12 @@ pub enum AdminResponse {
     ///
     /// [`AdminRequest::DumpState`]: enum.AdminRequest.html#variant.DumpState
     StateDumped(String),
+    /// The succesful response to an [`AdminRequest::CueAutonomicProcess`].
+    ///
+    /// It means the process has been started. It may still be running.
+    ///
+    /// [`AdminRequest::CueAutonomicProcess`]: enum.AdminRequest.html#variant.CueAutonomicProcess
+    AutonomicProcessCued,
 }
 
 #[cfg(test)]
@@ -540,6 +617,127 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn migrate_agent_replaces_cell() -> Result<()> {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+        let agent_key = fake_agent_pubkey_1();
+
+        // Install and activate an app on the first version of the dna
+        let dna = fake_dna_zomes(
+            &Uuid::new_v4().to_string(),
+            vec![(
+                TestWasm::MigrateAgentPass.into(),
+                TestWasm::MigrateAgentPass.into(),
+            )],
+        );
+        let (dna_path, _tempdir) = write_fake_dna_file(dna.clone()).await.unwrap();
+        let payload = InstallAppPayload {
+            dnas: vec![InstallAppDnaPayload::path_only(
+                dna_path,
+                "nick".to_string(),
+            )],
+            installed_app_id: "test".to_string(),
+            agent_key: agent_key.clone(),
+        };
+        let res = admin_api
+            .handle_admin_request(AdminRequest::InstallApp(Box::new(payload)))
+            .await;
+        assert_matches!(res, AdminResponse::AppInstalled(_));
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ActivateApp {
+                installed_app_id: "test".to_string(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::AppActivated);
+        let prev_cell_id = CellId::new(dna.dna_hash().clone(), agent_key.clone());
+
+        // A zome in the next dna vetoes the move
+        let vetoing_dna = fake_dna_zomes(
+            &Uuid::new_v4().to_string(),
+            vec![(
+                TestWasm::MigrateAgentFail.into(),
+                TestWasm::MigrateAgentFail.into(),
+            )],
+        );
+        let (vetoing_dna_path, _tempdir) = write_fake_dna_file(vetoing_dna).await.unwrap();
+        let res = admin_api
+            .handle_admin_request(AdminRequest::MigrateAgent {
+                installed_app_id: "test".to_string(),
+                dna: InstallAppDnaPayload::path_only(vetoing_dna_path, "nick".to_string()),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::Error(_));
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ListCellIds)
+            .await;
+        assert_matches!(res, AdminResponse::CellIdsListed(v) if v == vec![prev_cell_id.clone()]);
+
+        // The agent already has a cell on the next dna in another app
+        let taken_dna = fake_dna_zomes(
+            &Uuid::new_v4().to_string(),
+            vec![(
+                TestWasm::MigrateAgentPass.into(),
+                TestWasm::MigrateAgentPass.into(),
+            )],
+        );
+        let (taken_dna_path, _tempdir) = write_fake_dna_file(taken_dna).await.unwrap();
+        let payload = InstallAppPayload {
+            dnas: vec![InstallAppDnaPayload::path_only(
+                taken_dna_path.clone(),
+                "nick".to_string(),
+            )],
+            installed_app_id: "other".to_string(),
+            agent_key: agent_key.clone(),
+        };
+        let res = admin_api
+            .handle_admin_request(AdminRequest::InstallApp(Box::new(payload)))
+            .await;
+        assert_matches!(res, AdminResponse::AppInstalled(_));
+        let res = admin_api
+            .handle_admin_request(AdminRequest::MigrateAgent {
+                installed_app_id: "test".to_string(),
+                dna: InstallAppDnaPayload::path_only(taken_dna_path, "nick".to_string()),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::Error(_));
+
+        // The move is allowed and the new cell replaces the old one
+        let next_dna = fake_dna_zomes(
+            &Uuid::new_v4().to_string(),
+            vec![(
+                TestWasm::MigrateAgentPass.into(),
+                TestWasm::MigrateAgentPass.into(),
+            )],
+        );
+        let (next_dna_path, _tempdir) = write_fake_dna_file(next_dna.clone()).await.unwrap();
+        let next_cell_id = CellId::new(next_dna.dna_hash().clone(), agent_key);
+        let expected = InstalledApp {
+            installed_app_id: "test".to_string(),
+            cell_data: vec![InstalledCell::new(next_cell_id.clone(), "nick".to_string())],
+        };
+        let res = admin_api
+            .handle_admin_request(AdminRequest::MigrateAgent {
+                installed_app_id: "test".to_string(),
+                dna: InstallAppDnaPayload::path_only(next_dna_path, "nick".to_string()),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::AgentMigrated(app) if app == expected);
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ListCellIds)
+            .await;
+        assert_matches!(res, AdminResponse::CellIdsListed(v) if v == vec![next_cell_id]);
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn dna_read_parses() -> Result<()> {
         let uuid = Uuid::new_v4();
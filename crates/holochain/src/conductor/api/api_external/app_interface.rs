@@ -1,6 +1,8 @@
-use super::{InterfaceApi, SignalSubscription};
+use super::{ConnectionSignalFilters, InterfaceApi, SignalSubscription};
 use crate::conductor::{
-    api::error::{ConductorApiResult, ExternalApiWireError, SerializationError},
+    api::error::{
+        ConductorApiError, ConductorApiResult, ExternalApiWireError, SerializationError,
+    },
     state::AppInterfaceId,
 };
 use crate::conductor::{
@@ -8,10 +10,23 @@ use crate::conductor::{
     ConductorHandle,
 };
 use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::signal::Signal;
+use crate::core::state::x25519_keys::X25519KeysBuf;
+use holochain_keystore::{AgentPubKeyExt, KeystoreSenderExt};
 use holochain_serialized_bytes::prelude::*;
-use holochain_types::app::{InstalledApp, InstalledAppId};
+use holochain_types::{
+    app::{InstalledApp, InstalledAppId},
+    cell::CellId,
+};
+use holochain_zome_types::bytes::Bytes;
+use holochain_zome_types::signature::Signature;
+use holochain_zome_types::x_salsa20_poly1305::{
+    Decrypt, Encrypt, XSalsa20Poly1305EncryptedData,
+};
 use holochain_zome_types::ExternOutput;
 use holochain_zome_types::ZomeCallResponse;
+use parking_lot::RwLock;
+use std::sync::Arc;
 
 /// The interface that a Conductor exposes to the outside world.
 #[async_trait::async_trait]
@@ -22,6 +37,13 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
         request: AppRequest,
     ) -> ConductorApiResult<AppResponse>;
 
+    /// A copy of this api for a single connection,
+    /// with its own signal subscription which starts out allowing all signals
+    fn for_connection(&self) -> Self;
+
+    /// Should this signal be sent out across this connection?
+    fn allows_signal(&self, signal: &Signal) -> bool;
+
     // -- provided -- //
 
     /// Deal with error cases produced by `handle_app_request_inner`
@@ -41,6 +63,8 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
     interface_id: AppInterfaceId,
+    /// The filters for the connection this api is serving
+    signal_filters: Arc<RwLock<ConnectionSignalFilters>>,
 }
 
 impl RealAppInterfaceApi {
@@ -49,8 +73,102 @@ impl RealAppInterfaceApi {
         Self {
             conductor_handle,
             interface_id,
+            signal_filters: Arc::new(RwLock::new(ConnectionSignalFilters::default())),
         }
     }
+
+    /// Merge the filters into those of the app for this connection,
+    /// as long as they only name cells belonging to the app
+    async fn handle_signal_subscription(
+        &self,
+        subscription: SignalSubscription,
+    ) -> ConductorApiResult<AppResponse> {
+        let installed_app_id = subscription.installed_app_id();
+        let app = match self
+            .conductor_handle
+            .get_app_info(installed_app_id)
+            .await?
+        {
+            Some(app) => app,
+            None => {
+                return Ok(AppResponse::Error(
+                    ExternalApiWireError::InvalidSignalSubscription(format!(
+                        "App {} is not installed",
+                        installed_app_id
+                    )),
+                ))
+            }
+        };
+        if let Some(cell_id) = subscription
+            .filters()
+            .cell_ids()
+            .find(|cell_id| !app.cell_data.iter().any(|c| c.as_id() == *cell_id))
+        {
+            return Ok(AppResponse::Error(
+                ExternalApiWireError::InvalidSignalSubscription(format!(
+                    "Cell {:?} is not part of app {}",
+                    cell_id, installed_app_id
+                )),
+            ));
+        }
+        let app_cells = app.cell_data.iter().map(|c| c.as_id().clone()).collect();
+        self.signal_filters
+            .write()
+            .subscribe(subscription, app_cells);
+        Ok(AppResponse::SignalSubscriptionUpdated)
+    }
+
+    /// Run a [CryptoRequest] through the keystore on behalf of a running cell
+    async fn handle_crypto_request(
+        &self,
+        request: CryptoRequest,
+    ) -> ConductorApiResult<AppResponse> {
+        let cell_id = request.cell_id();
+        if !self
+            .conductor_handle
+            .list_cell_ids()
+            .await?
+            .contains(cell_id)
+        {
+            return Err(ConductorApiError::CellMissing(cell_id.clone()));
+        }
+        // only the cell's own keys can encrypt as the sender or decrypt as the recipient
+        let own_key = match &request {
+            CryptoRequest::Sign { .. } => None,
+            CryptoRequest::Encrypt { input, .. } => Some(input.sender),
+            CryptoRequest::Decrypt { input, .. } => Some(input.recipient),
+        };
+        if let Some(key) = own_key {
+            let env = self.conductor_handle.get_cell_env(cell_id).await?;
+            if !X25519KeysBuf::new(env.into())?.contains(&key)? {
+                return Ok(AppResponse::Error(ExternalApiWireError::ForeignX25519Key(
+                    format!(
+                        "The x25519 key {:?} was not created by the agent of cell {:?}",
+                        key, cell_id
+                    ),
+                )));
+            }
+        }
+        let keystore = self.conductor_handle.keystore();
+        let response = match request {
+            CryptoRequest::Sign { cell_id, data } => {
+                CryptoResponse::Signature(cell_id.agent_pubkey().sign_raw(keystore, &data).await?)
+            }
+            CryptoRequest::Encrypt { input, .. } => {
+                CryptoResponse::Encrypted(keystore.encrypt(input).await?)
+            }
+            CryptoRequest::Decrypt { input, .. } => match keystore.decrypt(input).await? {
+                Some(data) => CryptoResponse::Decrypted(data.into()),
+                None => {
+                    return Ok(AppResponse::Error(ExternalApiWireError::DecryptionFailed(
+                        "The data could not be decrypted and authenticated with the given keys"
+                            .to_string(),
+                    )))
+                }
+            },
+        };
+        Ok(AppResponse::Crypto(Box::new(response)))
+    }
 }
 
 #[async_trait::async_trait]
@@ -83,10 +201,23 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                 Err(e) => Ok(AppResponse::Error(e.into())),
               }
             }
-            AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
-            AppRequest::Crypto(_) => Ok(AppResponse::Unimplemented(request)),
+            AppRequest::SignalSubscription(subscription) => {
+                self.handle_signal_subscription(subscription).await
+            }
+            AppRequest::Crypto(request) => self.handle_crypto_request(*request).await,
+        }
+    }
+
+    fn for_connection(&self) -> Self {
+        Self {
+            signal_filters: Arc::new(RwLock::new(ConnectionSignalFilters::default())),
+            ..self.clone()
         }
     }
+
+    fn allows_signal(&self, signal: &Signal) -> bool {
+        self.signal_filters.read().allows(signal)
+    }
 }
 
 #[async_trait::async_trait]
@@ -129,10 +260,15 @@ pub enum AppRequest {
         /// The InstalledAppId for which to get information
         installed_app_id: InstalledAppId,
     },
-    /// Asks the conductor to do some crypto.
+    /// Asks the conductor to do some crypto with the keystore on behalf of a cell.
+    /// See the inner [`CryptoRequest`] for the available operations.
+    ///
+    /// Will be responded to with an [`AppResponse::Crypto`]
+    /// or an [`AppResponse::Error`]
     ///
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`](enum.AppResponse.html#variant.Unimplemented)
+    /// [`CryptoRequest`]: enum.CryptoRequest.html
+    /// [`AppResponse::Crypto`]: enum.AppResponse.html#variant.Crypto
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     Crypto(Box<CryptoRequest>),
     /// Call a zome function. See the inner [`ZomeCallInvocation`]
     /// struct to understand the data that must be provided.
@@ -145,10 +281,16 @@ pub enum AppRequest {
     /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     ZomeCallInvocation(Box<ZomeCallInvocation>),
 
-    /// Update signal subscriptions.
+    /// Update the signal subscription of the given app for this connection.
+    /// The new filters are merged per cell into any previous ones for the app,
+    /// and may only name cells belonging to the app.
+    /// An empty filter set replaces all the filters for the app.
+    ///
+    /// Will be responded to with an [`AppResponse::SignalSubscriptionUpdated`]
+    /// or an [`AppResponse::Error`]
     ///
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`](enum.AppResponse.html#variant.Unimplemented)
+    /// [`AppResponse::SignalSubscriptionUpdated`]: enum.AppResponse.html#variant.SignalSubscriptionUpdated
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     SignalSubscription(SignalSubscription),
 }
 
@@ -186,13 +328,84 @@ pub enum AppResponse {
     /// [`ExternOutput`]: ../../../holochain_zome_types/zome_io/struct.ExternOutput.html
     /// [`SerializedBytes`]: ../../../holochain_zome_types/query/struct.SerializedBytes.html
     ZomeCallInvocation(Box<ExternOutput>),
+
+    /// The succesful response to an [`AppRequest::Crypto`].
+    ///
+    /// [`AppRequest::Crypto`]: enum.AppRequest.html#variant.Crypto
+    Crypto(Box<CryptoResponse>),
+
+    /// The succesful response to an [`AppRequest::SignalSubscription`].
+    ///
+    /// [`AppRequest::SignalSubscription`]: enum.AppRequest.html#variant.SignalSubscription
+    SignalSubscriptionUpdated,
 }
 
-#[allow(missing_docs)]
+/// Crypto operations which can be run through the keystore over an App interface.
+///
+/// Every request names a cell, which must be running in the conductor.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
 pub enum CryptoRequest {
-    Sign(String),
-    Decrypt(String),
-    Encrypt(String),
+    /// Sign some bytes as the agent of the cell.
+    ///
+    /// Will be responded to with a [`CryptoResponse::Signature`]
+    ///
+    /// [`CryptoResponse::Signature`]: enum.CryptoResponse.html#variant.Signature
+    Sign {
+        /// The cell whose agent signs the data
+        cell_id: CellId,
+        /// The raw bytes to sign
+        data: Bytes,
+    },
+    /// Encrypt some bytes from one x25519 key to another.
+    /// The sender's key must have been created by the cell's agent.
+    ///
+    /// Will be responded to with a [`CryptoResponse::Encrypted`]
+    ///
+    /// [`CryptoResponse::Encrypted`]: enum.CryptoResponse.html#variant.Encrypted
+    Encrypt {
+        /// The cell on whose behalf the data is encrypted
+        cell_id: CellId,
+        /// The keys and data to encrypt
+        input: Encrypt,
+    },
+    /// Decrypt some bytes sent from one x25519 key to another.
+    /// The recipient's key must have been created by the cell's agent.
+    ///
+    /// Will be responded to with a [`CryptoResponse::Decrypted`],
+    /// or an [`ExternalApiWireError::DecryptionFailed`] if the data can't be authenticated
+    ///
+    /// [`CryptoResponse::Decrypted`]: enum.CryptoResponse.html#variant.Decrypted
+    /// [`ExternalApiWireError::DecryptionFailed`]: ../error/enum.ExternalApiWireError.html#variant.DecryptionFailed
+    Decrypt {
+        /// The cell on whose behalf the data is decrypted
+        cell_id: CellId,
+        /// The keys and data to decrypt
+        input: Decrypt,
+    },
+}
+
+impl CryptoRequest {
+    /// The cell this request is made on behalf of
+    pub fn cell_id(&self) -> &CellId {
+        match self {
+            CryptoRequest::Sign { cell_id, .. }
+            | CryptoRequest::Encrypt { cell_id, .. }
+            | CryptoRequest::Decrypt { cell_id, .. } => cell_id,
+        }
+    }
+}
+
+/// The results of a [`CryptoRequest`]
+///
+/// [`CryptoRequest`]: enum.CryptoRequest.html
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum CryptoResponse {
+    /// The signature of the data by the cell's agent
+    Signature(Signature),
+    /// The encrypted data and the nonce needed to decrypt it
+    Encrypted(XSalsa20Poly1305EncryptedData),
+    /// The decrypted bytes
+    Decrypted(Bytes),
 }
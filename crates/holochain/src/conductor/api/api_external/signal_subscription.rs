@@ -1,6 +1,8 @@
+use crate::core::signal::Signal;
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::{app::InstalledAppId, cell::CellId};
-use std::collections::HashMap;
+use holochain_zome_types::zome::ZomeName;
+use std::collections::{HashMap, HashSet};
 
 /// Declares updated Signal subscription settings for an App.
 /// This message is part of the AppInterfaceApi
@@ -13,12 +15,40 @@ pub struct SignalSubscription {
     filters: SignalFilterSet,
 }
 
+impl SignalSubscription {
+    /// Constructor
+    pub fn new(installed_app_id: InstalledAppId, filters: SignalFilterSet) -> Self {
+        Self {
+            installed_app_id,
+            filters,
+        }
+    }
+
+    /// The app for which to manage subscription
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        &self.installed_app_id
+    }
+
+    /// Fine-grained per-cell filters
+    pub fn filters(&self) -> &SignalFilterSet {
+        &self.filters
+    }
+
+    /// Take the filters out of the subscription
+    pub fn into_filters(self) -> SignalFilterSet {
+        self.filters
+    }
+}
+
 /// Associate a SignalFilter with each Cell in an App.
 /// The filtering can be interpreted as inclusive or exclusive,
 /// depending on the use case.
 ///
 /// An empty Exclude filter means "allow all signals" (subscribe to all).
 /// An empty Include filter means "block all signals" (unsubscribe from all).
+///
+/// Signals which are not about any particular Cell are checked against
+/// the filters of every Cell in the set.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum SignalFilterSet {
@@ -47,12 +77,117 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
+
+    /// The Cells which have filters in this set
+    pub fn cell_ids(&self) -> impl Iterator<Item = &CellId> {
+        match self {
+            SignalFilterSet::Include(filters) | SignalFilterSet::Exclude(filters) => filters.keys(),
+        }
+    }
+
+    /// Should this signal be let through?
+    pub fn allows(&self, signal: &Signal) -> bool {
+        match self {
+            SignalFilterSet::Include(filters) => Self::any_match(filters, signal),
+            SignalFilterSet::Exclude(filters) => !Self::any_match(filters, signal),
+        }
+    }
+
+    /// Merge a newer set of filters for the same app into this one.
+    ///
+    /// When both sets are of the same kind, the newer filter for each Cell
+    /// replaces the older one and the filters for other Cells are kept.
+    /// An empty set, or a set of the other kind, replaces this one entirely.
+    pub fn merge(&mut self, newer: SignalFilterSet) {
+        match (self, newer) {
+            (SignalFilterSet::Include(filters), SignalFilterSet::Include(newer))
+            | (SignalFilterSet::Exclude(filters), SignalFilterSet::Exclude(newer))
+                if !newer.is_empty() =>
+            {
+                filters.extend(newer)
+            }
+            (this, newer) => *this = newer,
+        }
+    }
+
+    fn any_match(filters: &HashMap<CellId, SignalFilter>, signal: &Signal) -> bool {
+        match signal.cell_id() {
+            Some(cell_id) => filters
+                .get(cell_id)
+                .map(|filter| filter.matches(signal))
+                .unwrap_or(false),
+            None => filters.values().any(|filter| filter.matches(signal)),
+        }
+    }
+}
+
+/// The signal filters of a single connection.
+///
+/// A connection can serve many apps, so the filters are kept per app and a
+/// subscription for one app never touches the filters of another.
+/// Signals from the Cells of an app without a subscription are let through.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionSignalFilters(HashMap<InstalledAppId, (HashSet<CellId>, SignalFilterSet)>);
+
+impl ConnectionSignalFilters {
+    /// Merge a subscription into the filters for its app,
+    /// given the Cells which belong to the app
+    pub fn subscribe(&mut self, subscription: SignalSubscription, app_cells: HashSet<CellId>) {
+        let SignalSubscription {
+            installed_app_id,
+            filters,
+        } = subscription;
+        match self.0.get_mut(&installed_app_id) {
+            Some((cells, existing)) => {
+                *cells = app_cells;
+                existing.merge(filters);
+            }
+            None => {
+                self.0.insert(installed_app_id, (app_cells, filters));
+            }
+        }
+    }
+
+    /// Should this signal be let through?
+    pub fn allows(&self, signal: &Signal) -> bool {
+        match signal.cell_id() {
+            Some(cell_id) => {
+                let mut subscribed = self
+                    .0
+                    .values()
+                    .filter(|(cells, _)| cells.contains(cell_id))
+                    .peekable();
+                subscribed.peek().is_none() || subscribed.any(|(_, filters)| filters.allows(signal))
+            }
+            None => self.0.is_empty() || self.0.values().any(|(_, filters)| filters.allows(signal)),
+        }
+    }
+}
+
+/// The two kinds of [Signal]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKind {
+    /// Signals emitted by zomes
+    App,
+    /// Signals emitted by Holochain itself
+    System,
 }
 
 /// Specifies fine-grained filter controls for the signals
+///
+/// A signal matches the filter when it matches every constraint which is set.
+/// Empty constraints match everything.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[cfg_attr(test, derive(PartialEq))]
-pub struct SignalFilter;
+pub struct SignalFilter {
+    /// Only match app signals or only match system signals
+    pub kind: Option<SignalKind>,
+    /// Only match app signals emitted by one of these zomes
+    pub zome_names: HashSet<ZomeName>,
+    /// Only match signals with one of these type discriminators
+    pub signal_types: HashSet<String>,
+}
 
 impl Default for SignalFilter {
     fn default() -> Self {
@@ -64,6 +199,196 @@ impl Default for SignalFilter {
 impl SignalFilter {
     /// A passthrough filter which filters nothing
     pub fn empty() -> Self {
-        SignalFilter
+        SignalFilter {
+            kind: None,
+            zome_names: HashSet::new(),
+            signal_types: HashSet::new(),
+        }
+    }
+
+    /// Only match signals of this kind
+    pub fn kind(mut self, kind: SignalKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Also match app signals from this zome
+    pub fn zome_name(mut self, zome_name: ZomeName) -> Self {
+        self.zome_names.insert(zome_name);
+        self
+    }
+
+    /// Also match signals with this type discriminator
+    pub fn signal_type<S: Into<String>>(mut self, signal_type: S) -> Self {
+        self.signal_types.insert(signal_type.into());
+        self
+    }
+
+    /// Does this signal match every constraint of the filter?
+    pub fn matches(&self, signal: &Signal) -> bool {
+        let kind = match signal {
+            Signal::App(..) => SignalKind::App,
+            Signal::System(_) => SignalKind::System,
+        };
+        self.kind.map(|k| k == kind).unwrap_or(true)
+            && (self.zome_names.is_empty()
+                || signal
+                    .zome_name()
+                    .map(|z| self.zome_names.contains(z))
+                    .unwrap_or(false))
+            && (self.signal_types.is_empty()
+                || signal
+                    .signal_type()
+                    .map(|t| self.signal_types.contains(t))
+                    .unwrap_or(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::signal::test_signal;
+    use ::fixt::prelude::*;
+    use holochain_types::fixt::CellIdFixturator;
+    use holochain_zome_types::signal::AppSignal;
+
+    fn app_signal(cell_id: &CellId, zome: &str, signal_type: Option<&str>) -> Signal {
+        let data = SerializedBytes::try_from(()).unwrap();
+        let signal = match signal_type {
+            Some(t) => AppSignal::typed(t, data),
+            None => AppSignal::new(data),
+        };
+        Signal::App(cell_id.clone(), zome.into(), signal)
+    }
+
+    #[test]
+    fn filter_matches_every_set_constraint() {
+        let cell_id = fixt!(CellId);
+        let chat_message = app_signal(&cell_id, "chat", Some("message"));
+        let chat_untyped = app_signal(&cell_id, "chat", None);
+        let profile_message = app_signal(&cell_id, "profile", Some("message"));
+        let system = test_signal("hi");
+
+        let empty = SignalFilter::empty();
+        assert!(empty.matches(&chat_message));
+        assert!(empty.matches(&system));
+
+        let app_only = SignalFilter::empty().kind(SignalKind::App);
+        assert!(app_only.matches(&chat_untyped));
+        assert!(!app_only.matches(&system));
+
+        let chat = SignalFilter::empty().zome_name("chat".into());
+        assert!(chat.matches(&chat_message));
+        assert!(chat.matches(&chat_untyped));
+        assert!(!chat.matches(&profile_message));
+        assert!(!chat.matches(&system));
+
+        let chat_messages = chat.signal_type("message");
+        assert!(chat_messages.matches(&chat_message));
+        assert!(!chat_messages.matches(&chat_untyped));
+        assert!(!chat_messages.matches(&profile_message));
+
+        let system_tests = SignalFilter::empty().signal_type("test");
+        assert!(system_tests.matches(&system));
+        assert!(!system_tests.matches(&chat_message));
+    }
+
+    #[test]
+    fn filter_set_includes_and_excludes_by_cell() {
+        let cell_id = fixt!(CellId);
+        let other_cell_id = fixt!(CellId);
+        let chat = app_signal(&cell_id, "chat", None);
+        let profile = app_signal(&cell_id, "profile", None);
+        let other_chat = app_signal(&other_cell_id, "chat", None);
+        let system = test_signal("hi");
+
+        assert!(SignalFilterSet::allow_all().allows(&chat));
+        assert!(SignalFilterSet::allow_all().allows(&system));
+        assert!(!SignalFilterSet::block_all().allows(&chat));
+        assert!(!SignalFilterSet::block_all().allows(&system));
+
+        let filters: HashMap<_, _> = vec![(
+            cell_id.clone(),
+            SignalFilter::empty().zome_name("chat".into()),
+        )]
+        .into_iter()
+        .collect();
+
+        let include = SignalFilterSet::Include(filters.clone());
+        assert!(include.allows(&chat));
+        assert!(!include.allows(&profile));
+        assert!(!include.allows(&other_chat));
+        assert!(!include.allows(&system));
+
+        let exclude = SignalFilterSet::Exclude(filters);
+        assert!(!exclude.allows(&chat));
+        assert!(exclude.allows(&profile));
+        assert!(exclude.allows(&other_chat));
+        assert!(exclude.allows(&system));
+    }
+
+    #[test]
+    fn subscriptions_merge_per_app_and_cell() {
+        let cell_id = fixt!(CellId);
+        let other_cell_id = fixt!(CellId);
+        let other_app_cell_id = fixt!(CellId);
+        let chat = app_signal(&cell_id, "chat", None);
+        let other_chat = app_signal(&other_cell_id, "chat", None);
+        let other_app_chat = app_signal(&other_app_cell_id, "chat", None);
+        let include = |cell_id: &CellId, filter: SignalFilter| {
+            SignalFilterSet::Include(vec![(cell_id.clone(), filter)].into_iter().collect())
+        };
+        let app_cells: HashSet<_> = vec![cell_id.clone(), other_cell_id.clone()]
+            .into_iter()
+            .collect();
+        let other_app_cells: HashSet<_> = vec![other_app_cell_id.clone()].into_iter().collect();
+
+        let mut filters = ConnectionSignalFilters::default();
+        assert!(filters.allows(&chat));
+
+        filters.subscribe(
+            SignalSubscription::new("app".into(), include(&cell_id, SignalFilter::empty())),
+            app_cells.clone(),
+        );
+        assert!(filters.allows(&chat));
+        assert!(!filters.allows(&other_chat));
+        // Apps without a subscription are untouched
+        assert!(filters.allows(&other_app_chat));
+
+        // A filter for another cell of the same app is added to the first one
+        filters.subscribe(
+            SignalSubscription::new("app".into(), include(&other_cell_id, SignalFilter::empty())),
+            app_cells.clone(),
+        );
+        assert!(filters.allows(&chat));
+        assert!(filters.allows(&other_chat));
+
+        // and a newer filter for the same cell replaces the older one
+        filters.subscribe(
+            SignalSubscription::new(
+                "app".into(),
+                include(&cell_id, SignalFilter::empty().zome_name("profile".into())),
+            ),
+            app_cells.clone(),
+        );
+        assert!(!filters.allows(&chat));
+        assert!(filters.allows(&other_chat));
+
+        // Subscribing for another app leaves the first app's filters alone
+        filters.subscribe(
+            SignalSubscription::new("other app".into(), SignalFilterSet::block_all()),
+            other_app_cells,
+        );
+        assert!(!filters.allows(&other_app_chat));
+        assert!(!filters.allows(&chat));
+        assert!(filters.allows(&other_chat));
+
+        // An empty set replaces all the filters of the app
+        filters.subscribe(
+            SignalSubscription::new("app".into(), SignalFilterSet::allow_all()),
+            app_cells,
+        );
+        assert!(filters.allows(&chat));
+        assert!(!filters.allows(&other_app_chat));
     }
 }
@@ -119,6 +119,16 @@ pub enum ExternalApiWireError {
     ActivateApp(String),
     /// The zome call is unauthorized
     ZomeCallUnauthorized(String),
+    /// The cell named in the request is not running in this conductor
+    CellMissing(String),
+    /// The keystore failed to carry out the request
+    KeystoreError(String),
+    /// The data could not be decrypted and authenticated
+    DecryptionFailed(String),
+    /// The x25519 key used to encrypt or decrypt does not belong to the cell's agent
+    ForeignX25519Key(String),
+    /// The signal subscription named an app or cells which aren't installed
+    InvalidSignalSubscription(String),
 }
 
 impl ExternalApiWireError {
@@ -134,6 +144,12 @@ impl From<ConductorApiError> for ExternalApiWireError {
     fn from(err: ConductorApiError) -> Self {
         match err {
             ConductorApiError::DnaReadError(e) => ExternalApiWireError::DnaReadError(e),
+            ConductorApiError::CellMissing(cell_id) => {
+                ExternalApiWireError::CellMissing(format!("{:?}", cell_id))
+            }
+            ConductorApiError::KeystoreError(e) => {
+                ExternalApiWireError::KeystoreError(e.to_string())
+            }
             e => ExternalApiWireError::internal(e),
         }
     }
@@ -7,11 +7,15 @@
 use super::{interface::SignalBroadcaster, manager::ManagedTaskAdd};
 use crate::conductor::handle::ConductorHandle;
 use crate::conductor::{api::error::ConductorApiError, entry_def_store::get_entry_def_from_ids};
-use crate::core::queue_consumer::{spawn_queue_consumer_tasks, InitialQueueTriggers};
+use crate::core::queue_consumer::{
+    spawn_queue_consumer_tasks, InitialQueueTriggers, TriggerSender,
+};
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::{
     conductor::api::CellConductorApiT,
-    core::workflow::produce_dht_ops_workflow::dht_op_light::light_to_op,
+    core::workflow::produce_dht_ops_workflow::dht_op_light::{
+        error::DhtOpConvertError, light_to_op,
+    },
 };
 use call_zome_workflow::call_zome_workspace_lock::CallZomeWorkspaceLock;
 use holochain_types::activity::AgentActivity;
@@ -23,7 +27,10 @@ use validation_package::ValidationPackageDb;
 
 use crate::{
     conductor::{api::CellConductorApi, cell::error::CellResult},
-    core::ribosome::{guest_callback::init::InitResult, wasm_ribosome::WasmRibosome},
+    core::ribosome::{
+        guest_callback::{init::InitResult, migrate_agent::MigrateAgentResult},
+        wasm_ribosome::WasmRibosome,
+    },
     core::{
         state::{
             dht_op_integration::IntegratedDhtOpsBuf,
@@ -32,8 +39,18 @@ use crate::{
             source_chain::{SourceChain, SourceChainBuf},
         },
         workflow::{
-            call_zome_workflow, error::WorkflowError, genesis_workflow::genesis_workflow,
-            incoming_dht_ops_workflow::incoming_dht_ops_workflow, initialize_zomes_workflow,
+            call_zome_workflow,
+            error::WorkflowError,
+            genesis_workflow::genesis_workflow,
+            incoming_dht_ops_workflow::incoming_dht_ops_workflow,
+            incoming_validation_receipt_workflow::incoming_validation_receipt_workflow,
+            incoming_warrants_workflow::incoming_warrants_workflow,
+            initialize_zomes_workflow,
+            migrate_agent_workflow::{
+                migrate_agent_workflow, MigrateAgentWorkflowArgs, MigrateAgentWorkspace,
+            },
+            produce_dht_ops_workflow::{produce_dht_ops_workflow, ProduceDhtOpsWorkspace},
+            publish_dht_ops_workflow::{publish_dht_ops_workflow, PublishDhtOpsWorkspace},
             CallZomeWorkflowArgs, CallZomeWorkspace, GenesisWorkflowArgs, GenesisWorkspace,
             InitializeZomesWorkflowArgs, ZomeCallInvocationResult,
         },
@@ -53,6 +70,7 @@ use holochain_state::{
 use holochain_types::{
     autonomic::AutonomicProcess,
     cell::CellId,
+    dna::PublishRateLimits,
     element::GetElementResponse,
     link::{GetLinksResponse, WireLinkMetaKey},
     metadata::{MetadataSet, TimedHeaderHash},
@@ -63,6 +81,7 @@ use holochain_zome_types::capability::CapSecret;
 use holochain_zome_types::header::{CreateLink, DeleteLink};
 use holochain_zome_types::signature::Signature;
 use holochain_zome_types::validate::RequiredValidationType;
+use holochain_zome_types::warrant::SignedWarrant;
 use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::ExternInput;
 use observability::OpenSpanExt;
@@ -124,6 +143,9 @@ where
     env: EnvironmentWrite,
     holochain_p2p_cell: P2pCell,
     queue_triggers: InitialQueueTriggers,
+    /// The DNA's publish rate limits, which can't change
+    /// because they are part of the DNA's hash
+    rate_limits: PublishRateLimits,
 }
 
 impl Cell {
@@ -147,6 +169,13 @@ impl Cell {
         };
 
         if has_genesis {
+            let rate_limits = conductor_handle
+                .get_dna(id.dna_hash())
+                .await
+                .ok_or(CellError::DnaMissing)?
+                .dna()
+                .rate_limits
+                .clone();
             holochain_p2p_cell.join().await?;
             let queue_triggers = spawn_queue_consumer_tasks(
                 &env,
@@ -163,6 +192,7 @@ impl Cell {
                 env,
                 holochain_p2p_cell,
                 queue_triggers,
+                rate_limits,
             })
         } else {
             Err(CellError::CellWithoutGenesis(id))
@@ -198,13 +228,95 @@ impl Cell {
             .await
             .map_err(ConductorApiError::from)
             .map_err(Box::new)?;
-        let args = GenesisWorkflowArgs::new(dna_file, id.agent_pubkey().clone(), membrane_proof);
+        let ribosome = WasmRibosome::new(dna_file.clone());
+        let args = GenesisWorkflowArgs::new(
+            dna_file,
+            id.agent_pubkey().clone(),
+            membrane_proof,
+            ribosome,
+        );
 
         genesis_workflow(workspace, cell_env.clone().into(), conductor_api, args)
             .await
-            .map_err(Box::new)
-            .map_err(ConductorApiError::from)
+            .map_err(|e| match e {
+                WorkflowError::GenesisSelfCheckInvalid(zome_name, reason) => {
+                    CellError::GenesisSelfCheckInvalid(id.clone(), zome_name, reason)
+                }
+                e => Box::new(ConductorApiError::from(Box::new(e))).into(),
+            })?;
+        Ok(())
+    }
+
+    /// Moves the agent of the `prev_id` Cell over to the `next_id` Cell,
+    /// closing the previous source chain and opening the next one.
+    /// Genesis must already have been run on the next Cell.
+    pub async fn migrate_agent(
+        prev_id: CellId,
+        prev_env: EnvironmentWrite,
+        next_id: CellId,
+        next_env: EnvironmentWrite,
+        conductor_handle: ConductorHandle,
+    ) -> CellResult<()> {
+        // get the dnas
+        let prev_dna_file = conductor_handle
+            .get_dna(prev_id.dna_hash())
+            .await
+            .ok_or(CellError::DnaMissing)?;
+        let next_dna_file = conductor_handle
+            .get_dna(next_id.dna_hash())
+            .await
+            .ok_or(CellError::DnaMissing)?;
+
+        let prev_workspace = MigrateAgentWorkspace::new(prev_env.clone().into())
+            .map_err(WorkflowError::from)
+            .map_err(Box::new)?;
+        let next_workspace = MigrateAgentWorkspace::new(next_env.clone().into())
+            .map_err(WorkflowError::from)
+            .map_err(Box::new)?;
+        let args = MigrateAgentWorkflowArgs::new(
+            prev_dna_file.dna().clone(),
+            WasmRibosome::new(prev_dna_file),
+            next_dna_file.dna().clone(),
+            WasmRibosome::new(next_dna_file),
+        );
+
+        let result = migrate_agent_workflow(
+            prev_workspace,
+            prev_env.into(),
+            next_workspace,
+            next_env.into(),
+            args,
+        )
+        .await
+        .map_err(Box::new)?;
+        trace!(?result);
+        match result {
+            MigrateAgentResult::Pass => Ok(()),
+            MigrateAgentResult::Fail(zome_name, reason) => Err(CellError::MigrateAgentFailed(
+                prev_id, next_id, zome_name, reason,
+            )),
+        }
+    }
+
+    /// Produce and publish this Cell's authored ops straight away rather than
+    /// waiting on the queue consumers, so that the last headers of a Cell
+    /// about to be removed still reach the DHT.
+    pub(super) async fn publish_authored_ops(&self) -> CellResult<()> {
+        let workspace = ProduceDhtOpsWorkspace::new(self.env.clone().into())?;
+        // Publishing is run directly below
+        let (mut trigger_publish, _) = TriggerSender::new();
+        produce_dht_ops_workflow(workspace, self.env.clone().into(), &mut trigger_publish)
+            .await
             .map_err(Box::new)?;
+
+        let workspace = PublishDhtOpsWorkspace::new(self.env.clone().into())?;
+        publish_dht_ops_workflow(
+            workspace,
+            self.env.clone().into(),
+            &mut self.holochain_p2p_cell.clone(),
+        )
+        .await
+        .map_err(Box::new)?;
         Ok(())
     }
 
@@ -239,9 +351,13 @@ impl Cell {
     ) -> CellResult<()> {
         use holochain_p2p::event::HolochainP2pEvent::*;
         match evt {
-            PutAgentInfoSigned { .. } | GetAgentInfoSigned { .. } | QueryAgentInfoSigned { .. } => {
+            PutAgentInfoSigned { .. }
+            | GetAgentInfoSigned { .. }
+            | QueryAgentInfoSigned { .. }
+            | QueryAgentInfoSignedNearBasis { .. }
+            | SignNetworkData { .. } => {
                 // PutAgentInfoSigned needs to be handled at the conductor level where the p2p
-                // store lives.
+                // store lives. SignNetworkData is handled there with the conductor's keystore.
                 unreachable!()
             }
             CallRemote {
@@ -284,6 +400,23 @@ impl Cell {
                 .instrument(debug_span!("cell_handle_publish"))
                 .await;
             }
+            PublishWarrants {
+                span_context: _,
+                respond,
+                agent,
+                warrants,
+                ..
+            } => {
+                async {
+                    let res = self
+                        .handle_publish_warrants(agent, warrants)
+                        .await
+                        .map_err(holochain_p2p::HolochainP2pError::other);
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(debug_span!("cell_handle_publish_warrants"))
+                .await;
+            }
             GetValidationPackage {
                 span_context: _,
                 respond,
@@ -416,31 +549,16 @@ impl Cell {
                 .instrument(debug_span!("cell_handle_fetch_op_hash_data"))
                 .await;
             }
-            SignNetworkData {
-                span_context: _,
-                respond,
-                ..
-            } => {
-                async {
-                    let res = self
-                        .handle_sign_network_data()
-                        .await
-                        .map_err(holochain_p2p::HolochainP2pError::other);
-                    respond.respond(Ok(async move { res }.boxed().into()));
-                }
-                .instrument(debug_span!("cell_handle_sign_network_data"))
-                .await;
-            }
         }
         Ok(())
     }
 
-    #[instrument(skip(self, _request_validation_receipt, _dht_hash, ops))]
+    #[instrument(skip(self, request_validation_receipt, _dht_hash, ops))]
     /// we are receiving a "publish" event from the network
     async fn handle_publish(
         &self,
         from_agent: AgentPubKey,
-        _request_validation_receipt: bool,
+        request_validation_receipt: bool,
         _dht_hash: holo_hash::AnyDhtHash,
         ops: Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
     ) -> CellResult<()> {
@@ -449,6 +567,8 @@ impl Cell {
             self.queue_triggers.sys_validation.clone(),
             ops,
             Some(from_agent),
+            request_validation_receipt,
+            Some(&self.rate_limits),
         )
         .await
         .map_err(Box::new)
@@ -457,6 +577,21 @@ impl Cell {
         Ok(())
     }
 
+    #[instrument(skip(self, warrants))]
+    /// we are receiving warrants about an agent whose activity we hold
+    async fn handle_publish_warrants(
+        &self,
+        agent: AgentPubKey,
+        warrants: Vec<SignedWarrant>,
+    ) -> CellResult<()> {
+        incoming_warrants_workflow(&self.env, agent, warrants)
+            .await
+            .map_err(Box::new)
+            .map_err(ConductorApiError::from)
+            .map_err(Box::new)?;
+        Ok(())
+    }
+
     #[instrument(skip(self))]
     /// a remote node is attempting to retrieve a validation package
     #[tracing::instrument(skip(self), level = "trace")]
@@ -507,13 +642,11 @@ impl Cell {
         dht_hash: holo_hash::AnyDhtHash,
         options: holochain_p2p::event::GetOptions,
     ) -> CellResult<GetElementResponse> {
-        // TODO: Later we will need more get types but for now
-        // we can just have these defaults depending on whether or not
-        // the hash is an entry or header.
-        // In the future we should use GetOptions to choose which get to run.
+        // The options choose how much metadata is returned
+        // with the entry or element
         let r = match *dht_hash.hash_type() {
             AnyDht::Entry => self.handle_get_entry(dht_hash.into(), options).await,
-            AnyDht::Header => self.handle_get_element(dht_hash.into()).await,
+            AnyDht::Header => self.handle_get_element(dht_hash.into(), options).await,
         };
         if let Err(e) = &r {
             error!(msg = "Error handling a get", ?e, agent = ?self.id.agent_pubkey());
@@ -531,20 +664,24 @@ impl Cell {
         authority::handle_get_entry(env, hash, options).await
     }
 
-    #[tracing::instrument(skip(self))]
-    async fn handle_get_element(&self, hash: HeaderHash) -> CellResult<GetElementResponse> {
+    #[tracing::instrument(skip(self, options))]
+    async fn handle_get_element(
+        &self,
+        hash: HeaderHash,
+        options: holochain_p2p::event::GetOptions,
+    ) -> CellResult<GetElementResponse> {
         let env = self.env.clone();
-        authority::handle_get_element(env, hash).await
+        authority::handle_get_element(env, hash, options).await
     }
 
-    #[instrument(skip(self, _dht_hash, _options))]
+    #[instrument(skip(self, options))]
     /// a remote node is asking us for metadata
     async fn handle_get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: holochain_p2p::event::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: holochain_p2p::event::GetMetaOptions,
     ) -> CellResult<MetadataSet> {
-        unimplemented!()
+        authority::handle_get_meta(self.env.clone().into(), dht_hash, options)
     }
 
     #[instrument(skip(self, _options))]
@@ -625,8 +762,18 @@ impl Cell {
 
     /// a remote agent is sending us a validation receipt.
     #[tracing::instrument(skip(self))]
-    async fn handle_validation_receipt(&self, _receipt: SerializedBytes) -> CellResult<()> {
-        unimplemented!()
+    async fn handle_validation_receipt(&self, receipt: SerializedBytes) -> CellResult<()> {
+        incoming_validation_receipt_workflow(
+            &self.env,
+            self.conductor_api.signal_broadcaster().await,
+            self.id(),
+            receipt,
+        )
+        .await
+        .map_err(Box::new)
+        .map_err(ConductorApiError::from)
+        .map_err(Box::new)?;
+        Ok(())
     }
 
     #[instrument(skip(self, dht_arc, since, until))]
@@ -673,7 +820,19 @@ impl Cell {
                         let cas = ElementBuf::rejected(self.env.clone().into())?;
                         light_to_op(val.op, &cas)?
                     }
-                    ValidationStatus::Abandoned => todo!("Add when abandoned store is added"),
+                    ValidationStatus::Abandoned => {
+                        let cas = ElementBuf::abandoned(self.env.clone().into())?;
+                        match light_to_op(val.op, &cas) {
+                            Ok(op) => op,
+                            // The entries of abandoned ops are not kept so
+                            // ops that need them can't be gossiped.
+                            Err(DhtOpConvertError::MissingData(_)) => {
+                                debug!(msg = "Skipping abandoned op without its entry", ?op_hash);
+                                continue;
+                            }
+                            Err(e) => return Err(e.into()),
+                        }
+                    }
                 };
                 let basis = full_op.dht_basis();
                 out.push((basis, op_hash, full_op));
@@ -682,19 +841,17 @@ impl Cell {
         Ok(out)
     }
 
-    /// the network module would like this cell/agent to sign some data
-    #[tracing::instrument(skip(self))]
-    async fn handle_sign_network_data(&self) -> CellResult<Signature> {
-        Ok(vec![0; 64].into())
-    }
-
     /// When the Conductor determines that it's time to execute some [AutonomicProcess],
     /// whether scheduled or through an [AutonomicCue], this function gets called
     #[tracing::instrument(skip(self, process))]
     pub async fn handle_autonomic_process(&self, process: AutonomicProcess) -> CellResult<()> {
         match process {
             AutonomicProcess::SlowHeal => unimplemented!(),
-            AutonomicProcess::HealthCheck => unimplemented!(),
+            AutonomicProcess::HealthCheck => {
+                // The check itself runs in the health check queue consumer
+                self.queue_triggers.health_check.clone().trigger();
+                Ok(())
+            }
         }
     }
 
@@ -757,9 +914,10 @@ impl Cell {
             workspace_lock,
             self.holochain_p2p_cell.clone(),
             keystore,
-            arc.clone().into(),
+            arc.clone(),
             args,
             self.queue_triggers.produce_dht_ops.clone(),
+            self.queue_triggers.schedule.clone(),
         )
         .await
         .map_err(Box::new)?)
@@ -3,12 +3,12 @@ use crate::{
     conductor::CellError,
     core::state::{
         element_buf::ElementBuf,
-        metadata::{ChainItemKey, MetadataBuf, MetadataBufT},
+        metadata::{ChainItemKey, LinkMetaKey, MetadataBuf, MetadataBufT},
     },
 };
 use fallible_iterator::FallibleIterator;
 
-use holo_hash::{AgentPubKey, EntryHash, HeaderHash};
+use holo_hash::{hash_type::AnyDht, AgentPubKey, AnyDhtHash, EntryHash, HeaderHash};
 use holochain_state::{
     env::EnvironmentRead,
     env::{EnvironmentWrite, ReadManager},
@@ -22,7 +22,7 @@ use holochain_types::{
     element::{ElementStatus, GetElementResponse, RawGetEntryResponse, WireElement},
     header::WireHeaderStatus,
     header::WireUpdateRelationship,
-    metadata::TimedHeaderHash,
+    metadata::{MetadataSet, TimedHeaderHash},
 };
 use holochain_zome_types::{
     element::SignedHeaderHashed, header::conversions::WrongHeaderError, query::ChainQueryFilter,
@@ -40,6 +40,7 @@ pub async fn handle_get_entry(
     // Get the vaults
     let element_vault = ElementBuf::vault(state_env.clone().into(), false)?;
     let element_rejected = ElementBuf::rejected(state_env.clone().into())?;
+    let element_abandoned = ElementBuf::abandoned(state_env.clone().into())?;
     let meta_vault = MetadataBuf::vault(state_env.clone().into())?;
 
     // ## Helper closures to DRY and make more readable
@@ -54,6 +55,10 @@ pub async fn handle_get_entry(
             r = element_rejected.get_header(&header_hash)?;
             status = ValidationStatus::Rejected;
         }
+        if r.is_none() {
+            r = element_abandoned.get_header(&header_hash)?;
+            status = ValidationStatus::Abandoned;
+        }
         let r = r.ok_or_else(|| AuthorityDataError::missing_data(header_hash))?;
         CellResult::Ok((r, status))
     };
@@ -88,8 +93,15 @@ pub async fn handle_get_entry(
             .collect::<Vec<_>>()?;
         let mut live_headers = BTreeSet::new();
 
+        // We only want the headers that created the content
+        if options.content_only {
+            for hash in headers {
+                let header_status = render_header_and_status(hash)?;
+                live_headers.insert(header_status.try_into()?);
+            }
+
         // We want all the live headers and deletes
-        if options.all_live_headers_with_metadata {
+        } else if options.all_live_headers_with_metadata {
             for hash in headers {
                 deletes.extend(
                     meta_vault
@@ -184,6 +196,7 @@ pub async fn handle_get_entry(
 pub async fn handle_get_element(
     env: EnvironmentWrite,
     hash: HeaderHash,
+    options: holochain_p2p::event::GetOptions,
 ) -> CellResult<GetElementResponse> {
     // Get the vaults
     let env_ref = env.guard();
@@ -191,6 +204,7 @@ pub async fn handle_get_element(
     let element_vault = ElementBuf::vault(env.clone().into(), false)?;
     let meta_vault = MetadataBuf::vault(env.clone().into())?;
     let element_rejected = ElementBuf::rejected(env.clone().into())?;
+    let element_abandoned = ElementBuf::abandoned(env.clone().into())?;
 
     // Check that we have the authority to serve this request because we have
     // done the StoreElement validation
@@ -198,6 +212,26 @@ pub async fn handle_get_element(
         return Ok(GetElementResponse::GetHeader(None));
     }
 
+    // Get the actual header and return it with proof of deleted if there is any
+    let mut r = element_vault.get_element(&hash)?;
+    let mut status = ValidationStatus::Valid;
+    if r.is_none() {
+        r = element_rejected.get_element(&hash)?;
+        status = ValidationStatus::Rejected;
+    }
+    if r.is_none() {
+        r = element_abandoned.get_element(&hash)?;
+        status = ValidationStatus::Abandoned;
+    }
+
+    // The content is all that's wanted
+    if options.content_only {
+        let r = r
+            .map(|e| WireElement::from_element(ElementStatus::new(e, status), vec![], vec![]))
+            .map(Box::new);
+        return Ok(GetElementResponse::GetHeader(r));
+    }
+
     // Look for a deletes on the header and collect them
     let deletes = meta_vault
         .get_deletes_on_header(&reader, hash.clone())?
@@ -210,6 +244,10 @@ pub async fn handle_get_element(
                 delete = element_rejected.get_header(&delete_hash)?;
                 status = ValidationStatus::Rejected;
             }
+            if delete.is_none() {
+                delete = element_abandoned.get_header(&delete_hash)?;
+                status = ValidationStatus::Abandoned;
+            }
             match delete {
                 Some(delete) => Ok((delete, status)
                     .try_into()
@@ -231,6 +269,10 @@ pub async fn handle_get_element(
                 update = element_rejected.get_header(&update_hash)?;
                 status = ValidationStatus::Rejected;
             }
+            if update.is_none() {
+                update = element_abandoned.get_header(&update_hash)?;
+                status = ValidationStatus::Abandoned;
+            }
             match update {
                 Some(update) => Ok((update, status)
                     .try_into()
@@ -240,13 +282,6 @@ pub async fn handle_get_element(
         })
         .collect()?;
 
-    // Get the actual header and return it with proof of deleted if there is any
-    let mut r = element_vault.get_element(&hash)?;
-    let mut status = ValidationStatus::Valid;
-    if r.is_none() {
-        r = element_rejected.get_element(&hash)?;
-        status = ValidationStatus::Rejected;
-    }
     let r = r
         .map(|e| WireElement::from_element(ElementStatus::new(e, status), deletes, updates))
         .map(Box::new);
@@ -272,6 +307,13 @@ pub fn handle_get_agent_activity(
         .unwrap_or(ChainStatus::Empty);
     let highest_observed = meta_integrated.get_activity_observed(&agent)?;
 
+    // Warrants
+    let warrants = fresh_reader!(env, |r| {
+        meta_integrated
+            .get_warrants(&r, &agent)?
+            .collect::<Vec<_>>()
+    })?;
+
     // Valid headers
     let valid_activity = if options.include_valid_activity {
         fresh_reader!(env, |r| {
@@ -310,9 +352,82 @@ pub fn handle_get_agent_activity(
         agent,
         status,
         highest_observed,
+        warrants,
     })
 }
 
+#[instrument(skip(env))]
+pub fn handle_get_meta(
+    env: EnvironmentRead,
+    dht_hash: AnyDhtHash,
+    options: holochain_p2p::event::GetMetaOptions,
+) -> CellResult<MetadataSet> {
+    let meta_vault = MetadataBuf::vault(env.clone())?;
+    let request = options.metadata_request;
+    let mut metadata = MetadataSet::default();
+
+    fresh_reader!(env, |r| {
+        match *dht_hash.hash_type() {
+            AnyDht::Entry => {
+                let entry_hash: EntryHash = dht_hash.clone().into();
+                if request.all_valid_headers {
+                    metadata.headers = meta_vault.get_headers(&r, entry_hash.clone())?.collect()?;
+                }
+                if request.all_invalid_headers {
+                    metadata.invalid_headers = meta_vault
+                        .get_rejected_headers(&r, entry_hash.clone())?
+                        .collect()?;
+                }
+                if request.all_deletes {
+                    metadata.deletes = meta_vault
+                        .get_deletes_on_entry(&r, entry_hash.clone())?
+                        .collect()?;
+                }
+                if request.all_links {
+                    metadata.links = meta_vault
+                        .get_links_all(&r, &LinkMetaKey::Base(&entry_hash))?
+                        .map(|link_add| {
+                            Ok(TimedHeaderHash {
+                                timestamp: link_add.timestamp,
+                                header_hash: link_add.link_add_hash,
+                            })
+                        })
+                        .collect()?;
+                }
+                if request.entry_dht_status {
+                    metadata.entry_dht_status = Some(meta_vault.get_dht_status(&r, &entry_hash)?);
+                }
+            }
+            AnyDht::Header => {
+                let header_hash: HeaderHash = dht_hash.clone().into();
+                if request.all_deletes {
+                    metadata.deletes = meta_vault
+                        .get_deletes_on_header(&r, header_hash.clone())?
+                        .collect()?;
+                }
+                if request.all_links {
+                    metadata.link_removes = meta_vault
+                        .get_link_removes_on_link_add(&r, header_hash.clone())?
+                        .collect()?;
+                }
+                if request.validation_status {
+                    metadata.validation_status = meta_vault
+                        .get_validation_status(&r, &header_hash)?
+                        .statuses()
+                        .copied()
+                        .collect();
+                }
+            }
+        }
+        if request.all_updates {
+            metadata.updates = meta_vault.get_updates(&r, dht_hash)?.collect()?;
+        }
+        CellResult::Ok(())
+    })?;
+
+    Ok(metadata)
+}
+
 fn get_full_headers<'a, P: PrefixType + 'a, R: Readable>(
     hashes: impl FallibleIterator<Item = (u32, HeaderHash), Error = DatabaseError> + 'a,
     query: ChainQueryFilter,
@@ -14,6 +14,7 @@ use holochain_p2p::HolochainP2pError;
 use holochain_state::error::DatabaseError;
 use holochain_types::{cell::CellId, header::error::HeaderError};
 use holochain_zome_types::header::conversions::WrongHeaderError;
+use holochain_zome_types::zome::ZomeName;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -29,6 +30,8 @@ pub enum CellError {
     JoinError(#[from] tokio::task::JoinError),
     #[error("Genesis failed: {0}")]
     Genesis(#[from] Box<ConductorApiError>),
+    #[error("The membrane proof for cell {0:?} was rejected by the genesis self check in the {1} zome: {2}")]
+    GenesisSelfCheckInvalid(CellId, ZomeName, String),
     #[error(transparent)]
     HeaderError(#[from] HeaderError),
     #[error("This cell has not had a successful genesis and cannot be created")]
@@ -47,6 +50,8 @@ pub enum CellError {
     SourceChainError(#[from] SourceChainError),
     #[error("The cell tried to run the initialize zomes callback but failed because {0:?}")]
     InitFailed(InitResult),
+    #[error("The agent could not be migrated from cell {0:?} to cell {1:?} because the {2} zome vetoed it: {3}")]
+    MigrateAgentFailed(CellId, CellId, ZomeName, String),
     #[error(transparent)]
     HolochainP2pError(#[from] HolochainP2pError),
     #[error(transparent)]
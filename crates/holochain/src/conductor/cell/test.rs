@@ -1,18 +1,25 @@
 use crate::{
     conductor::manager::spawn_task_manager,
+    core::state::metadata::{MetadataBuf, MetadataBufT, SysMetaVal},
     core::workflow::incoming_dht_ops_workflow::IncomingDhtOpsWorkspace,
     fixt::{DnaFileFixturator, SignatureFixturator},
     test_utils::test_network,
 };
 use ::fixt::prelude::*;
-use holo_hash::HasHash;
-use holochain_state::test_utils::test_cell_env;
+use holo_hash::{
+    fixt::{EntryHashFixturator, HeaderHashFixturator},
+    HasHash,
+};
+use holochain_p2p::event::GetMetaOptions;
+use holochain_state::{buffer::BufferedStore, env::WriteManager, test_utils::test_cell_env};
 use holochain_types::{
     dht_op::{DhtOp, DhtOpHashed},
+    metadata::TimedHeaderHash,
     test_utils::{fake_agent_pubkey_2, fake_cell_id},
     HeaderHashed, Timestamp,
 };
-use holochain_zome_types::header;
+use holochain_zome_types::{header, request::MetadataRequest, validate::ValidationStatus};
+use maplit::btreeset;
 use std::sync::Arc;
 use tokio::sync;
 
@@ -80,3 +87,67 @@ async fn test_cell_handle_publish() {
     stop_tx.send(()).unwrap();
     shutdown.await.unwrap();
 }
+
+#[tokio::test(threaded_scheduler)]
+async fn test_cell_handle_get_meta() {
+    let cell_env = test_cell_env();
+    let env = cell_env.env();
+
+    let entry_hash = fixt!(EntryHash);
+    let header_hash = fixt!(HeaderHash);
+    let create = TimedHeaderHash::from(header_hash.clone());
+    let rejected = TimedHeaderHash::from(fixt!(HeaderHash));
+    let update = TimedHeaderHash::from(fixt!(HeaderHash));
+    let delete = TimedHeaderHash::from(fixt!(HeaderHash));
+
+    // Put some metadata on an entry and one of its headers in the vault
+    {
+        let mut meta_vault = MetadataBuf::vault(env.clone().into()).unwrap();
+        meta_vault
+            .register_raw_on_entry(entry_hash.clone(), SysMetaVal::NewEntry(create.clone()))
+            .unwrap();
+        meta_vault
+            .register_raw_on_entry(
+                entry_hash.clone(),
+                SysMetaVal::RejectedNewEntry(rejected.clone()),
+            )
+            .unwrap();
+        meta_vault
+            .register_raw_on_entry(entry_hash.clone(), SysMetaVal::Update(update.clone()))
+            .unwrap();
+        meta_vault.register_raw_on_header(header_hash.clone(), SysMetaVal::Delete(delete.clone()));
+        meta_vault.register_validation_status(header_hash.clone(), ValidationStatus::Valid);
+        env.guard()
+            .with_commit(|writer| meta_vault.flush_to_txn(writer))
+            .unwrap();
+    }
+
+    // Only the requested metadata on the entry is returned
+    let options = GetMetaOptions {
+        metadata_request: MetadataRequest {
+            all_invalid_headers: true,
+            all_updates: false,
+            entry_dht_status: true,
+            ..Default::default()
+        },
+    };
+    let metadata =
+        super::authority::handle_get_meta(env.clone().into(), entry_hash.into(), options).unwrap();
+    assert_eq!(metadata.headers, btreeset! {create});
+    assert_eq!(metadata.invalid_headers, btreeset! {rejected});
+    assert!(metadata.updates.is_empty());
+    assert!(metadata.entry_dht_status.is_some());
+
+    // Deletes and the validation status are returned for the header
+    let options = GetMetaOptions {
+        metadata_request: MetadataRequest::default(),
+    };
+    let metadata =
+        super::authority::handle_get_meta(env.clone().into(), header_hash.into(), options).unwrap();
+    assert_eq!(metadata.deletes, btreeset! {delete});
+    assert_eq!(
+        metadata.validation_status,
+        btreeset! {ValidationStatus::Valid}
+    );
+    assert!(metadata.headers.is_empty());
+}
@@ -14,7 +14,7 @@ use super::{
     dna_store::{DnaDefBuf, DnaStore, RealDnaStore},
     entry_def_store::{get_entry_defs, EntryDefBuf, EntryDefBufferKey},
     error::{ConductorError, CreateAppError},
-    handle::ConductorHandleImpl,
+    handle::{ConductorHandleImpl, ConductorHandleT},
     interface::{
         error::InterfaceResult,
         websocket::{
@@ -32,13 +32,13 @@ use super::{
     state::ConductorState,
     CellError,
 };
-use crate::conductor::p2p_store::{AgentKv, AgentKvKey};
+use crate::conductor::p2p_store::{self, AgentKv};
 use crate::{
     conductor::{
         api::error::ConductorApiResult, cell::Cell, config::ConductorConfig,
         dna_store::MockDnaStore, error::ConductorResult, handle::ConductorHandle,
     },
-    core::signal::Signal,
+    core::signal::{Signal, SystemSignal},
     core::state::{source_chain::SourceChainBuf, wasm::WasmBuf},
 };
 pub use builder::*;
@@ -66,7 +66,7 @@ use holochain_types::{
 use holochain_zome_types::entry_def::EntryDef;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use std::collections::HashMap;
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryFrom;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use tracing::*;
@@ -383,11 +383,77 @@ where
 
             Err(ConductorError::GenesisFailed { errors })
         } else {
-            // No errors so return the cells
+            let mut signal_broadcaster = self.signal_broadcaster();
+            for cell_id in success {
+                signal_broadcaster.send_system(SystemSignal::GenesisComplete(cell_id));
+            }
             Ok(())
         }
     }
 
+    /// Move an agent from the `prev_id` cell to the `next_id` cell, running
+    /// genesis on the next cell first unless an interrupted move already did.
+    /// If the move is not made and this call created the next chain, which
+    /// the move never wrote to, the next cell's environment is removed.
+    pub(super) async fn migrate_agent_chain(
+        &self,
+        prev_id: CellId,
+        next_id: CellId,
+        membrane_proof: Option<MembraneProof>,
+        conductor_handle: ConductorHandle,
+    ) -> ConductorResult<()> {
+        // The next chain must not belong to an installed cell
+        let state = self.get_state().await?;
+        if self.cells.contains_key(&next_id)
+            || state
+                .active_apps
+                .values()
+                .chain(state.inactive_apps.values())
+                .flatten()
+                .any(|cell| cell.as_id() == &next_id)
+        {
+            return Err(ConductorError::MigrateToInstalledCell(next_id));
+        }
+
+        let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
+        let prev_env =
+            EnvironmentWrite::new_cell(&root_env_dir, prev_id.clone(), self.keystore.clone())?;
+        let next_env =
+            EnvironmentWrite::new_cell(&root_env_dir, next_id.clone(), self.keystore.clone())?;
+
+        let ran_genesis = !SourceChainBuf::new(next_env.clone().into())?.has_genesis();
+        if ran_genesis {
+            self.genesis_cells(
+                vec![(next_id.clone(), membrane_proof)],
+                conductor_handle.clone(),
+            )
+            .await?;
+        }
+        let genesis_head = SourceChainBuf::new(next_env.clone().into())?
+            .chain_head()
+            .cloned();
+
+        if let Err(e) = Cell::migrate_agent(
+            prev_id,
+            prev_env,
+            next_id,
+            next_env.clone(),
+            conductor_handle,
+        )
+        .await
+        {
+            // A chain the move wrote to is needed to resume it
+            let next_head = SourceChainBuf::new(next_env.clone().into())?
+                .chain_head()
+                .cloned();
+            if ran_genesis && next_head == genesis_head {
+                next_env.remove().await?;
+            }
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
     /// Create Cells for each CellId marked active in the ConductorState db
     pub(super) async fn create_active_app_cells(
         &self,
@@ -564,11 +630,55 @@ where
             .collect())
     }
 
+    /// Swap the cell with the same nick in an installed app for `cell`,
+    /// returning the updated app
+    pub(super) async fn replace_app_cell_in_db(
+        &mut self,
+        installed_app_id: InstalledAppId,
+        cell: InstalledCell,
+    ) -> ConductorResult<InstalledApp> {
+        let state = self
+            .update_state({
+                let installed_app_id = installed_app_id.clone();
+                move |mut state| {
+                    let cell_data = match state.active_apps.get_mut(&installed_app_id) {
+                        Some(cell_data) => cell_data,
+                        None => {
+                            state
+                                .inactive_apps
+                                .get_mut(&installed_app_id)
+                                .ok_or_else(|| {
+                                    ConductorError::AppNotInstalled(installed_app_id.clone())
+                                })?
+                        }
+                    };
+                    let existing = cell_data
+                        .iter_mut()
+                        .find(|c| c.as_nick() == cell.as_nick())
+                        .ok_or_else(|| {
+                            ConductorError::AppCellMissing(
+                                installed_app_id.clone(),
+                                cell.as_nick().clone(),
+                            )
+                        })?;
+                    *existing = cell;
+                    Ok(state)
+                }
+            })
+            .await?;
+        Ok(state
+            .get_app_info(&installed_app_id)
+            .expect("This app was just updated"))
+    }
+
     /// Add fully constructed cells to the cell map in the Conductor
+    /// Cells have already joined the network when they are created
     pub(super) fn add_cells(&mut self, cells: Vec<Cell>) {
+        let mut signal_broadcaster = self.signal_broadcaster();
         for cell in cells {
             let cell_id = cell.id().clone();
             tracing::info!(?cell_id, "ADD CELL");
+            signal_broadcaster.send_system(SystemSignal::NetworkJoined(cell_id.clone()));
             self.cells.insert(
                 cell_id,
                 CellItem {
@@ -629,27 +739,24 @@ where
         Ok((dnas, defs))
     }
 
-    /// Remove cells from the cell map in the Conductor
-    pub(super) fn remove_cells(&mut self, cell_ids: Vec<CellId>) {
-        for cell_id in cell_ids {
-            self.cells.remove(&cell_id);
-        }
+    /// Remove cells from the cell map in the Conductor,
+    /// returning the removed cells so they can leave the network
+    pub(super) fn remove_cells(&mut self, cell_ids: Vec<CellId>) -> Vec<Cell> {
+        cell_ids
+            .into_iter()
+            .filter_map(|cell_id| self.cells.remove(&cell_id))
+            .map(|item| item.cell)
+            .collect()
     }
 
     pub(super) fn put_agent_info_signed(
         &self,
         agent_info_signed: kitsune_p2p::agent_store::AgentInfoSigned,
     ) -> ConductorResult<()> {
-        let environ = self.p2p_env.clone();
-        let p2p_kv = AgentKv::new(environ.clone().into())?;
-        let env = environ.guard();
-        Ok(env.with_commit(|writer| {
-            p2p_kv.as_store_ref().put(
-                writer,
-                &(&agent_info_signed).try_into()?,
-                &agent_info_signed,
-            )
-        })?)
+        Ok(p2p_store::put_agent_info_signed(
+            self.p2p_env.clone(),
+            agent_info_signed,
+        )?)
     }
 
     pub(super) fn get_agent_info_signed(
@@ -691,51 +798,26 @@ where
 
     pub(super) fn query_agent_info_signed(
         &self,
-        _kitsune_space: Arc<kitsune_p2p::KitsuneSpace>,
+        kitsune_space: Arc<kitsune_p2p::KitsuneSpace>,
     ) -> ConductorResult<Vec<AgentInfoSigned>> {
-        let environ = self.p2p_env.clone();
-
-        let p2p_kv = AgentKv::new(environ.clone().into())?;
-        let env = environ.guard();
-
-        let mut out = Vec::new();
-        env.with_commit(|writer| {
-            let mut expired = Vec::new();
-
-            {
-                let mut iter = p2p_kv.as_store_ref().iter(writer)?;
-
-                let now: u64 = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64;
-
-                loop {
-                    match iter.next() {
-                        Ok(Some((k, v))) => {
-                            let info = kitsune_p2p::agent_store::AgentInfo::try_from(&v)?;
-                            if info.signed_at_ms() + info.expires_after_ms() <= now {
-                                expired.push(AgentKvKey::from(k));
-                            } else {
-                                out.push(v);
-                            }
-                        }
-                        Ok(None) => break,
-                        Err(e) => return Err(e.into()),
-                    }
-                }
-            }
-
-            if !expired.is_empty() {
-                for exp in expired {
-                    p2p_kv.as_store_ref().delete(writer, &exp)?;
-                }
-            }
-
-            ConductorResult::Ok(())
-        })?;
+        Ok(p2p_store::query_agent_infos(
+            self.p2p_env.clone().into(),
+            &kitsune_space,
+        )?)
+    }
 
-        Ok(out)
+    pub(super) fn query_agent_info_signed_near_basis(
+        &self,
+        kitsune_space: Arc<kitsune_p2p::KitsuneSpace>,
+        basis_loc: u32,
+        limit: u32,
+    ) -> ConductorResult<Vec<AgentInfoSigned>> {
+        Ok(p2p_store::query_agent_infos_near_basis(
+            self.p2p_env.clone().into(),
+            &kitsune_space,
+            basis_loc,
+            limit,
+        )?)
     }
 
     pub(super) async fn put_wasm(
@@ -1024,6 +1106,7 @@ mod builder {
             handle.add_dnas().await?;
 
             tokio::task::spawn(p2p_event_task(p2p_evt, handle.clone()));
+            tokio::task::spawn(p2p_store_prune_task(Arc::downgrade(&handle)));
 
             let cell_startup_errors = handle.clone().setup_cells().await?;
 
@@ -1118,6 +1201,24 @@ async fn p2p_event_task(
     tracing::warn!("p2p_event_task has ended");
 }
 
+/// Periodically prune expired agent infos from the peer store,
+/// for as long as the conductor is around
+async fn p2p_store_prune_task(handle: std::sync::Weak<dyn ConductorHandleT>) {
+    loop {
+        tokio::time::delay_for(p2p_store::PRUNE_EXPIRED_AGENT_INFOS_INTERVAL).await;
+        let handle = match handle.upgrade() {
+            Some(handle) => handle,
+            None => break,
+        };
+        if let Err(e) = p2p_store::prune_expired_agent_infos(handle.get_p2p_env().await) {
+            tracing::error!(
+                message = "error pruning expired agent infos",
+                error = ?e,
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
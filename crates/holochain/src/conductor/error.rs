@@ -1,7 +1,10 @@
 use super::{entry_def_store::error::EntryDefStoreError, interface::error::InterfaceError};
 use crate::{conductor::cell::error::CellError, core::workflow::error::WorkflowError};
 use holochain_state::error::DatabaseError;
-use holochain_types::{app::InstalledAppId, cell::CellId};
+use holochain_types::{
+    app::{CellNick, InstalledAppId},
+    cell::CellId,
+};
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -79,6 +82,15 @@ pub enum ConductorError {
     #[error("Tried to deactivate an app that was not active: {0}")]
     AppNotActive(InstalledAppId),
 
+    #[error("The app {0} has no cell with the nick {1}")]
+    AppCellMissing(InstalledAppId, CellNick),
+
+    #[error("Tried to migrate the agent of cell {0:?} to the DNA it is already in")]
+    MigrateToSameDna(CellId),
+
+    #[error("Tried to migrate an agent to cell {0:?} which is already installed")]
+    MigrateToInstalledCell(CellId),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
@@ -99,6 +111,25 @@ pub enum CreateAppError {
         installed_app_id: InstalledAppId,
         errors: Vec<CellError>,
     },
+    #[error("The membrane proofs for the {installed_app_id} app were rejected by the genesis self check: {errors:?}")]
+    GenesisSelfCheckFailed {
+        installed_app_id: InstalledAppId,
+        errors: Vec<CellError>,
+    },
+}
+
+impl CreateAppError {
+    /// The app that failed to be created
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        match self {
+            CreateAppError::Failed {
+                installed_app_id, ..
+            }
+            | CreateAppError::GenesisSelfCheckFailed {
+                installed_app_id, ..
+            } => installed_app_id,
+        }
+    }
 }
 
 // TODO: can this be removed?
@@ -46,18 +46,20 @@ use super::{
     config::AdminInterfaceConfig,
     dna_store::DnaStore,
     entry_def_store::EntryDefBufferKey,
-    error::{ConductorResult, CreateAppError},
+    error::{ConductorError, ConductorResult, CreateAppError},
     interface::SignalBroadcaster,
     manager::TaskManagerRunHandle,
-    Cell, Conductor,
+    Cell, CellError, Conductor,
 };
+use crate::core::signal::SystemSignal;
 use crate::core::workflow::ZomeCallInvocationResult;
 use crate::core::{ribosome::ZomeCallInvocation, workflow::CallZomeWorkspaceLock};
 use derive_more::From;
 use futures::future::FutureExt;
 use holochain_p2p::event::HolochainP2pEvent::*;
+use holochain_p2p::HolochainP2pCellT;
 use holochain_types::{
-    app::{InstalledApp, InstalledAppId, InstalledCell, MembraneProof},
+    app::{CellNick, InstalledApp, InstalledAppId, InstalledCell, MembraneProof},
     autonomic::AutonomicCue,
     cell::CellId,
     dna::DnaFile,
@@ -179,6 +181,18 @@ pub trait ConductorHandleT: Send + Sync {
     #[allow(clippy::ptr_arg)]
     async fn deactivate_app(&self, installed_app_id: InstalledAppId) -> ConductorResult<()>;
 
+    /// Move the agent of an installed app's cell over to the Dna with
+    /// `dna_hash`, closing the cell's source chain and replacing the cell in
+    /// the app with a new one whose chain opens from it.
+    /// Any zome in either Dna can veto the move in its migrate agent callback.
+    async fn migrate_agent(
+        self: Arc<Self>,
+        installed_app_id: InstalledAppId,
+        cell_nick: CellNick,
+        dna_hash: DnaHash,
+        membrane_proof: Option<MembraneProof>,
+    ) -> ConductorResult<InstalledApp>;
+
     /// List Cell Ids
     async fn list_cell_ids(&self) -> ConductorResult<Vec<CellId>>;
 
@@ -326,6 +340,18 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
                     .map_err(holochain_p2p::HolochainP2pError::other);
                 respond.respond(Ok(async move { res }.boxed().into()));
             }
+            QueryAgentInfoSignedNearBasis {
+                kitsune_space,
+                basis_loc,
+                limit,
+                respond,
+                ..
+            } => {
+                let res = lock
+                    .query_agent_info_signed_near_basis(kitsune_space, basis_loc, limit)
+                    .map_err(holochain_p2p::HolochainP2pError::other);
+                respond.respond(Ok(async move { res }.boxed().into()));
+            }
             SignNetworkData { respond, data, .. } => {
                 let signature = cell_id
                     .agent_pubkey()
@@ -370,9 +396,9 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
     }
 
     async fn autonomic_cue(&self, cue: AutonomicCue, cell_id: &CellId) -> ConductorApiResult<()> {
-        let lock = self.conductor.write().await;
+        let lock = self.conductor.read().await;
         let cell = lock.cell_by_id(cell_id)?;
-        let _ = cell.handle_autonomic_process(cue.into()).await;
+        cell.handle_autonomic_process(cue.into()).await?;
         Ok(())
     }
 
@@ -414,7 +440,21 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
                     .collect(),
                 self.clone(),
             )
-            .await?;
+            .await
+            .map_err(|e| match e {
+                ConductorError::GenesisFailed { errors }
+                    if errors
+                        .iter()
+                        .any(|e| matches!(e, CellError::GenesisSelfCheckInvalid(..))) =>
+                {
+                    CreateAppError::GenesisSelfCheckFailed {
+                        installed_app_id: installed_app_id.clone(),
+                        errors,
+                    }
+                    .into()
+                }
+                e => e,
+            })?;
 
         let cell_data = cell_data.into_iter().map(|(c, _)| c).collect();
         let app = InstalledApp {
@@ -459,11 +499,11 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
     }
 
     async fn activate_app(&self, installed_app_id: InstalledAppId) -> ConductorResult<()> {
-        self.conductor
-            .write()
-            .await
-            .activate_app_in_db(installed_app_id)
-            .await
+        let mut lock = self.conductor.write().await;
+        lock.activate_app_in_db(installed_app_id.clone()).await?;
+        lock.signal_broadcaster()
+            .send_system(SystemSignal::AppActivated(installed_app_id));
+        Ok(())
     }
 
     async fn deactivate_app(&self, installed_app_id: InstalledAppId) -> ConductorResult<()> {
@@ -471,15 +511,92 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .conductor
             .write()
             .await
-            .deactivate_app_in_db(installed_app_id)
+            .deactivate_app_in_db(installed_app_id.clone())
             .await?;
-        self.conductor
+        let removed_cells = self
+            .conductor
             .write()
             .await
             .remove_cells(cell_ids_to_remove);
+        let mut signal_broadcaster = self.signal_broadcaster().await;
+        for cell in removed_cells {
+            let cell_id = cell.id().clone();
+            match cell.holochain_p2p_cell().clone().leave().await {
+                Ok(()) => signal_broadcaster.send_system(SystemSignal::NetworkLeft(cell_id)),
+                Err(e) => warn!(?cell_id, ?e, "Cell failed to leave the network"),
+            }
+        }
+        signal_broadcaster.send_system(SystemSignal::AppDeactivated(installed_app_id));
         Ok(())
     }
 
+    async fn migrate_agent(
+        self: Arc<Self>,
+        installed_app_id: InstalledAppId,
+        cell_nick: CellNick,
+        dna_hash: DnaHash,
+        membrane_proof: Option<MembraneProof>,
+    ) -> ConductorResult<InstalledApp> {
+        let prev_id = self
+            .get_app_info(&installed_app_id)
+            .await?
+            .ok_or_else(|| ConductorError::AppNotInstalled(installed_app_id.clone()))?
+            .cell_data
+            .into_iter()
+            .find(|c| c.as_nick() == &cell_nick)
+            .ok_or_else(|| {
+                ConductorError::AppCellMissing(installed_app_id.clone(), cell_nick.clone())
+            })?
+            .into_id();
+        if prev_id.dna_hash() == &dna_hash {
+            return Err(ConductorError::MigrateToSameDna(prev_id));
+        }
+        let next_id = CellId::new(dna_hash, prev_id.agent_pubkey().clone());
+
+        // The old cell must be running to publish its closed chain
+        self.conductor.read().await.cell_by_id(&prev_id)?;
+
+        // Run genesis on the new chain then move the agent onto it
+        self.conductor
+            .read()
+            .await
+            .migrate_agent_chain(
+                prev_id.clone(),
+                next_id.clone(),
+                membrane_proof,
+                self.clone(),
+            )
+            .await?;
+
+        // Get the CloseChain out to the network before the old cell is dropped
+        self.conductor
+            .read()
+            .await
+            .cell_by_id(&prev_id)?
+            .publish_authored_ops()
+            .await?;
+
+        // Swap the new cell in for the old one
+        let app = self
+            .conductor
+            .write()
+            .await
+            .replace_app_cell_in_db(installed_app_id, InstalledCell::new(next_id, cell_nick))
+            .await?;
+
+        // The old cell's chain is closed so it leaves the network
+        let removed_cells = self.conductor.write().await.remove_cells(vec![prev_id]);
+        let mut signal_broadcaster = self.signal_broadcaster().await;
+        for cell in removed_cells {
+            let cell_id = cell.id().clone();
+            match cell.holochain_p2p_cell().clone().leave().await {
+                Ok(()) => signal_broadcaster.send_system(SystemSignal::NetworkLeft(cell_id)),
+                Err(e) => warn!(?cell_id, ?e, "Cell failed to leave the network"),
+            }
+        }
+        Ok(app)
+    }
+
     async fn list_cell_ids(&self) -> ConductorResult<Vec<CellId>> {
         self.conductor.read().await.list_cell_ids().await
     }
@@ -5,7 +5,10 @@
 //! Currently the only InterfaceDriver is a Websocket-based one, whose
 //! implementation can be found in the `websocket` module here.
 
-use crate::{conductor::api::*, core::signal::Signal};
+use crate::{
+    conductor::api::*,
+    core::signal::{Signal, SystemSignal},
+};
 use error::{InterfaceError, InterfaceResult};
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
@@ -31,6 +34,17 @@ impl SignalBroadcaster {
         Ok(())
     }
 
+    /// Send a system signal to every app interface which has connected clients.
+    /// It's normal for nobody to be listening, so that is not an error.
+    pub fn send_system(&mut self, sig: SystemSignal) {
+        let sig = Signal::from(sig);
+        for tx in self.0.iter_mut() {
+            if tx.send(sig.clone()).is_err() {
+                tracing::trace!(?sig, "No clients connected to receive system signal");
+            }
+        }
+    }
+
     /// internal constructor
     pub fn new(senders: Vec<broadcast::Sender<Signal>>) -> Self {
         Self(senders)
@@ -41,6 +55,12 @@ impl SignalBroadcaster {
     pub fn noop() -> Self {
         Self(Vec::new())
     }
+
+    #[cfg(test)]
+    /// Receive everything sent on any interface. For tests
+    pub fn subscribe(&self) -> Vec<broadcast::Receiver<Signal>> {
+        self.0.iter().map(|tx| tx.subscribe()).collect()
+    }
 }
 
 /// Configuration for interfaces, specifying the means by which an interface
@@ -99,7 +99,7 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 
 /// Create an App Interface, which includes the ability to receive signals
 /// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
+pub async fn spawn_app_interface_task<A: InterfaceApi + AppInterfaceApi>(
     port: u16,
     api: A,
     signal_broadcaster: broadcast::Sender<Signal>,
@@ -123,7 +123,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
             |tx_to_iface: WebsocketSender, rx_from_iface: WebsocketReceiver| {
                 let rx_from_cell = signal_broadcaster.subscribe();
                 listener_handles.push(tokio::task::spawn(recv_incoming_msgs_and_outgoing_signals(
-                    api.clone(),
+                    api.for_connection(),
                     rx_from_iface,
                     rx_from_cell,
                     tx_to_iface,
@@ -182,7 +182,8 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(api: A, mut rx_from_iface: We
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
 /// App interface.
-async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
+/// Only signals allowed by this connection's subscription are sent on.
+async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi + AppInterfaceApi>(
     api: A,
     mut rx_from_iface: WebsocketReceiver,
     mut rx_from_cell: broadcast::Receiver<Signal>,
@@ -198,10 +199,11 @@ async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
             // tx and rx together in a new spawned task
             signal = rx_from_cell.next() => {
                 if let Some(signal) = signal {
-                    let bytes = SerializedBytes::try_from(
-                        signal.map_err(InterfaceError::SignalReceive)?,
-                    )?;
-                    tx_to_iface.signal(bytes).await?;
+                    let signal = signal.map_err(InterfaceError::SignalReceive)?;
+                    if api.allows_signal(&signal) {
+                        let bytes = SerializedBytes::try_from(signal)?;
+                        tx_to_iface.signal(bytes).await?;
+                    }
                 } else {
                     debug!("Closing interface: signal stream empty");
                     break;
@@ -257,9 +259,10 @@ pub mod test {
     use holochain_state::test_utils::test_environments;
     use holochain_types::{
         app::{InstallAppDnaPayload, InstallAppPayload, InstalledCell},
+        autonomic::AutonomicCue,
         cell::CellId,
         observability,
-        test_utils::{fake_agent_pubkey_1, fake_dna_file, fake_dna_zomes},
+        test_utils::{fake_agent_pubkey_1, fake_agent_pubkey_2, fake_dna_file, fake_dna_zomes},
     };
     use holochain_wasm_test_utils::TestWasm;
     use holochain_websocket::WebsocketMessage;
@@ -476,6 +479,296 @@ pub mod test {
         shutdown.await.unwrap();
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn websocket_crypto_sign() {
+        use crate::conductor::api::{CryptoRequest, CryptoResponse};
+        use holochain_keystore::AgentPubKeyExt;
+        use holochain_zome_types::bytes::Bytes;
+
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        let installed_cell = InstalledCell::new(cell_id.clone(), "handle".into());
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .with(predicate::eq(dna_hash.clone()))
+            .returning(move |_| Some(dna.clone()));
+        dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+        dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+
+        let (_tmpdir, app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
+        let data = vec![1_u8, 2, 3];
+
+        // Sign as the agent of the running cell
+        let msg = AppRequest::Crypto(Box::new(CryptoRequest::Sign {
+            cell_id: cell_id.clone(),
+            data: Bytes::from(data.clone()),
+        }));
+        let msg = msg.try_into().unwrap();
+        let agent_key = cell_id.agent_pubkey().clone();
+        let respond = move |bytes: SerializedBytes| {
+            let response: AppResponse = bytes.try_into().unwrap();
+            async move {
+                match response {
+                    AppResponse::Crypto(response) => match *response {
+                        CryptoResponse::Signature(signature) => assert!(agent_key
+                            .verify_signature_raw(&signature, &data)
+                            .await
+                            .unwrap()),
+                        r => panic!("Expected a signature, got {:?}", r),
+                    },
+                    r => panic!("Expected a crypto response, got {:?}", r),
+                }
+                Ok(())
+            }
+            .boxed()
+        };
+        let msg = WebsocketMessage::Request(msg, Box::new(respond));
+        handle_incoming_message(msg, app_api.clone()).await.unwrap();
+
+        // A cell which isn't running can't use the keystore
+        let msg = AppRequest::Crypto(Box::new(CryptoRequest::Sign {
+            cell_id: CellId::from((dna_hash, fake_agent_pubkey_2())),
+            data: Bytes::from(vec![1_u8, 2, 3]),
+        }));
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AppResponse = bytes.try_into().unwrap();
+            assert_matches!(
+                response,
+                AppResponse::Error(ExternalApiWireError::CellMissing(_))
+            );
+            async { Ok(()) }.boxed()
+        };
+        let msg = WebsocketMessage::Request(msg, Box::new(respond));
+        handle_incoming_message(msg, app_api).await.unwrap();
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn websocket_crypto_encrypt_decrypt() {
+        use crate::conductor::api::{CryptoRequest, CryptoResponse};
+        use crate::core::state::x25519_keys::X25519KeysBuf;
+        use holochain_keystore::KeystoreSenderExt;
+        use holochain_state::{buffer::BufferedStore, env::WriteManager};
+        use holochain_zome_types::x_salsa20_poly1305::{Decrypt, Encrypt};
+
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        let installed_cell = InstalledCell::new(cell_id.clone(), "handle".into());
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .with(predicate::eq(dna_hash.clone()))
+            .returning(move |_| Some(dna.clone()));
+        dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+        dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+
+        let (_tmpdir, app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
+
+        // Both keys are in the keystore but only one was created by the cell's agent
+        let own_key = handle.keystore().create_x25519_keypair().await.unwrap();
+        let foreign_key = handle.keystore().create_x25519_keypair().await.unwrap();
+        let env = handle.get_cell_env(&cell_id).await.unwrap();
+        let mut keys = X25519KeysBuf::new(env.clone().into()).unwrap();
+        keys.add(&own_key).unwrap();
+        env.guard()
+            .with_commit(|writer| keys.flush_to_txn(writer))
+            .unwrap();
+
+        let request = |request: CryptoRequest| {
+            let app_api = app_api.clone();
+            async move {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                let msg = AppRequest::Crypto(Box::new(request)).try_into().unwrap();
+                let respond = move |bytes: SerializedBytes| {
+                    let response: AppResponse = bytes.try_into().unwrap();
+                    tx.send(response).unwrap();
+                    async { Ok(()) }.boxed()
+                };
+                let msg = WebsocketMessage::Request(msg, Box::new(respond));
+                handle_incoming_message(msg, app_api).await.unwrap();
+                rx.await.unwrap()
+            }
+        };
+        let data = vec![1_u8, 2, 3];
+
+        // Data encrypted to the cell's own key round trips
+        let encrypted = match request(CryptoRequest::Encrypt {
+            cell_id: cell_id.clone(),
+            input: Encrypt::to_self(own_key, data.clone()),
+        })
+        .await
+        {
+            AppResponse::Crypto(response) => match *response {
+                CryptoResponse::Encrypted(encrypted) => encrypted,
+                r => panic!("Expected encrypted data, got {:?}", r),
+            },
+            r => panic!("Expected a crypto response, got {:?}", r),
+        };
+        match request(CryptoRequest::Decrypt {
+            cell_id: cell_id.clone(),
+            input: Decrypt::from_self(own_key, encrypted.clone()),
+        })
+        .await
+        {
+            AppResponse::Crypto(response) => match *response {
+                CryptoResponse::Decrypted(decrypted) => assert_eq!(decrypted.to_vec(), data),
+                r => panic!("Expected decrypted data, got {:?}", r),
+            },
+            r => panic!("Expected a crypto response, got {:?}", r),
+        }
+
+        // Keys of other agents can't be used by the cell
+        assert_matches!(
+            request(CryptoRequest::Encrypt {
+                cell_id: cell_id.clone(),
+                input: Encrypt::new(foreign_key, own_key, data.clone()),
+            })
+            .await,
+            AppResponse::Error(ExternalApiWireError::ForeignX25519Key(_))
+        );
+        assert_matches!(
+            request(CryptoRequest::Decrypt {
+                cell_id: cell_id.clone(),
+                input: Decrypt::new(foreign_key, own_key, encrypted.clone()),
+            })
+            .await,
+            AppResponse::Error(ExternalApiWireError::ForeignX25519Key(_))
+        );
+
+        // and data can't be decrypted with the wrong keys
+        let to_foreign = handle
+            .keystore()
+            .encrypt(Encrypt::new(own_key, foreign_key, data))
+            .await
+            .unwrap();
+        assert_matches!(
+            request(CryptoRequest::Decrypt {
+                cell_id,
+                input: Decrypt::from_self(own_key, to_foreign),
+            })
+            .await,
+            AppResponse::Error(ExternalApiWireError::DecryptionFailed(_))
+        );
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn websocket_signal_subscription() {
+        use crate::conductor::api::{SignalFilter, SignalFilterSet, SignalSubscription};
+        use crate::core::signal::Signal;
+        use holochain_zome_types::signal::AppSignal;
+
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        let installed_cell = InstalledCell::new(cell_id.clone(), "handle".into());
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .with(predicate::eq(dna_hash.clone()))
+            .returning(move |_| Some(dna.clone()));
+        dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+        dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+
+        let (_tmpdir, app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
+        let signal = |zome: &str| {
+            Signal::App(
+                cell_id.clone(),
+                zome.into(),
+                AppSignal::new(().try_into().unwrap()),
+            )
+        };
+
+        // Each connection starts out allowing everything
+        let connection_1 = app_api.for_connection();
+        let connection_2 = app_api.for_connection();
+        assert!(connection_1.allows_signal(&signal("foo")));
+
+        // Only subscribe connection 1 to signals from the foo zome
+        let filters = vec![(
+            cell_id.clone(),
+            SignalFilter::empty().zome_name("foo".into()),
+        )]
+        .into_iter()
+        .collect();
+        let msg = AppRequest::SignalSubscription(SignalSubscription::new(
+            "test app".to_string(),
+            SignalFilterSet::Include(filters),
+        ));
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AppResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+            async { Ok(()) }.boxed()
+        };
+        let msg = WebsocketMessage::Request(msg, Box::new(respond));
+        handle_incoming_message(msg, connection_1.clone())
+            .await
+            .unwrap();
+
+        assert!(connection_1.allows_signal(&signal("foo")));
+        assert!(!connection_1.allows_signal(&signal("bar")));
+        assert!(connection_2.allows_signal(&signal("bar")));
+
+        // Cells from outside the app can't be subscribed to
+        let filters = vec![(
+            CellId::from((dna_hash, fake_agent_pubkey_2())),
+            SignalFilter::empty(),
+        )]
+        .into_iter()
+        .collect();
+        let msg = AppRequest::SignalSubscription(SignalSubscription::new(
+            "test app".to_string(),
+            SignalFilterSet::Include(filters),
+        ));
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AppResponse = bytes.try_into().unwrap();
+            assert_matches!(
+                response,
+                AppResponse::Error(ExternalApiWireError::InvalidSignalSubscription(_))
+            );
+            async { Ok(()) }.boxed()
+        };
+        let msg = WebsocketMessage::Request(msg, Box::new(respond));
+        handle_incoming_message(msg, connection_2.clone())
+            .await
+            .unwrap();
+        assert!(connection_2.allows_signal(&signal("bar")));
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn activate_app() {
         observability::test_run().ok();
@@ -592,6 +885,63 @@ pub mod test {
         shutdown.await.unwrap();
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn app_lifecycle_emits_system_signals() {
+        use crate::core::signal::{Signal, SystemSignal};
+
+        observability::test_run().ok();
+        let dna = fake_dna_file(&Uuid::new_v4().to_string());
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+        dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+
+        let envs = test_environments();
+        let conductor_handle = ConductorBuilder::with_mock_dna_store(dna_store)
+            .test(&envs)
+            .await
+            .unwrap();
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        conductor_handle.clone().add_app_interface(0).await.unwrap();
+        let mut rx = conductor_handle
+            .signal_broadcaster()
+            .await
+            .subscribe()
+            .pop()
+            .unwrap();
+
+        conductor_handle
+            .clone()
+            .install_app(
+                "test app".to_string(),
+                vec![(InstalledCell::new(cell_id.clone(), "handle".into()), None)],
+            )
+            .await
+            .unwrap();
+        let conductor_handle = activate(conductor_handle).await;
+        conductor_handle
+            .deactivate_app("test app".to_string())
+            .await
+            .unwrap();
+
+        let mut signals = Vec::new();
+        while let Ok(signal) = rx.try_recv() {
+            signals.push(signal);
+        }
+        let expected: Vec<Signal> = vec![
+            SystemSignal::GenesisComplete(cell_id.clone()).into(),
+            SystemSignal::AppActivated("test app".to_string()).into(),
+            SystemSignal::NetworkJoined(cell_id.clone()).into(),
+            SystemSignal::NetworkLeft(cell_id).into(),
+            SystemSignal::AppDeactivated("test app".to_string()).into(),
+        ];
+        assert_eq!(signals, expected);
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn attach_app_interface() {
         observability::test_run().ok();
@@ -663,4 +1013,48 @@ pub mod test {
         conductor_handle.shutdown().await;
         shutdown.await.unwrap();
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn cue_health_check() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![(cell_id.clone(), None)], dna_store).await;
+        let conductor_handle = activate(conductor_handle).await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::CueAutonomicProcess {
+            cell_id: Box::new(cell_id),
+            cue: AutonomicCue::HealthCheck,
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = move |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AdminResponse::AutonomicProcessCued);
+            async { Ok(()) }.boxed()
+        };
+        let respond = Box::new(respond);
+        let msg = WebsocketMessage::Request(msg, respond);
+        handle_incoming_message(msg, admin_api).await.unwrap();
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
 }
@@ -1,4 +1,10 @@
 //! A simple KvBuf for AgentInfoSigned.
+//!
+//! Only the newest agent info for each agent is kept. Expired agent infos
+//! are pruned from a space whenever an agent info is put into it, and
+//! periodically by the conductor.
+//! Keys are the space followed by the agent, so the agent infos in a space
+//! can be read without scanning the rest of the store.
 
 use fallible_iterator::FallibleIterator;
 use holo_hash::{AgentPubKey, DnaHash};
@@ -8,12 +14,21 @@ use holochain_state::{buffer::KvStore, buffer::KvStoreT, fresh_reader};
 use holochain_state::{db::GetDb, prelude::Readable};
 use holochain_state::{env::EnvironmentRead, error::DatabaseError};
 use holochain_state::{env::EnvironmentWrite, error::DatabaseResult};
-use holochain_state::{env::WriteManager, key::BufKey};
+use holochain_state::{env::WriteManager, key::BufKey, prelude::Writer};
+use kitsune_p2p::dependencies::kitsune_p2p_types::dht_arc::shortest_arc_distance;
+use kitsune_p2p::{KitsuneBinType, KitsuneSpace};
+use std::collections::HashSet;
 use std::convert::TryInto;
 
 const AGENT_KEY_LEN: usize = 64;
 const AGENT_KEY_COMPONENT_LEN: usize = 32;
 
+/// How often the conductor prunes expired agent infos from the peer store,
+/// for spaces that no agent infos are being put into.
+/// Until then expired agent infos are skipped by the queries.
+pub const PRUNE_EXPIRED_AGENT_INFOS_INTERVAL: std::time::Duration =
+    std::time::Duration::from_secs(60);
+
 /// Required new type for KvBuf key.
 pub struct AgentKvKey([u8; AGENT_KEY_LEN]);
 
@@ -64,9 +79,12 @@ impl From<(DnaHash, AgentPubKey)> for AgentKvKey {
 impl From<(&kitsune_p2p::KitsuneSpace, &kitsune_p2p::KitsuneAgent)> for AgentKvKey {
     fn from(o: (&kitsune_p2p::KitsuneSpace, &kitsune_p2p::KitsuneAgent)) -> Self {
         use kitsune_p2p::KitsuneBinType;
+        // Holochain spaces and agents have 4 location bytes after the 32 byte hash
         let mut bytes = [0; AGENT_KEY_LEN];
-        bytes[..AGENT_KEY_COMPONENT_LEN].copy_from_slice(&o.0.get_bytes());
-        bytes[AGENT_KEY_COMPONENT_LEN..].copy_from_slice(&o.1.get_bytes());
+        bytes[..AGENT_KEY_COMPONENT_LEN]
+            .copy_from_slice(&o.0.get_bytes()[..AGENT_KEY_COMPONENT_LEN]);
+        bytes[AGENT_KEY_COMPONENT_LEN..]
+            .copy_from_slice(&o.1.get_bytes()[..AGENT_KEY_COMPONENT_LEN]);
         Self(bytes)
     }
 }
@@ -144,27 +162,235 @@ impl AgentKv {
             .iter(reader)?
             .map(|(k, v)| Ok((k.into(), v))))
     }
+
+    /// Get an iterator of the agent info stored in this database for a space.
+    pub fn iter_space<'r, R: Readable>(
+        &'r self,
+        reader: &'r R,
+        space: &KitsuneSpace,
+    ) -> DatabaseResult<
+        impl FallibleIterator<Item = (AgentKvKey, AgentInfoSigned), Error = DatabaseError> + 'r,
+    > {
+        let mut start = [0; AGENT_KEY_LEN];
+        start[..AGENT_KEY_COMPONENT_LEN]
+            .copy_from_slice(&space.get_bytes()[..AGENT_KEY_COMPONENT_LEN]);
+        Ok(self
+            .as_store_ref()
+            .iter_from(reader, AgentKvKey(start))?
+            .take_while(move |(k, _)| {
+                Ok(k[..AGENT_KEY_COMPONENT_LEN] == start[..AGENT_KEY_COMPONENT_LEN])
+            })
+            .map(|(k, v)| Ok((k.into(), v))))
+    }
+
+    /// Put an agent info into the database, unless it has expired or the
+    /// database already holds a newer one for the same agent.
+    pub fn put_agent_info_signed(
+        &self,
+        writer: &mut Writer,
+        agent_info_signed: AgentInfoSigned,
+        now_ms: u64,
+    ) -> DatabaseResult<()> {
+        let key: AgentKvKey = (&agent_info_signed).try_into()?;
+        let agent_info = match unexpired_agent_info(&agent_info_signed, now_ms) {
+            Some(agent_info) => agent_info,
+            None => return Ok(()),
+        };
+        if let Some(existing) = self
+            .0
+            .get(writer, &key)?
+            .and_then(|existing| unexpired_agent_info(&existing, now_ms))
+        {
+            if existing.signed_at_ms() >= agent_info.signed_at_ms() {
+                return Ok(());
+            }
+        }
+        self.0.put(writer, &key, &agent_info_signed)
+    }
+
+    /// Delete every expired agent info from the database.
+    pub fn prune_expired(&self, writer: &mut Writer, now_ms: u64) -> DatabaseResult<()> {
+        let expired: Vec<AgentKvKey> = self
+            .iter(writer)?
+            .filter(|(_, v)| Ok(unexpired_agent_info(v, now_ms).is_none()))
+            .map(|(k, _)| Ok(k))
+            .collect()?;
+        for key in expired {
+            self.0.delete(writer, &key)?;
+        }
+        Ok(())
+    }
+
+    /// Delete the expired agent infos in a space from the database.
+    pub fn prune_expired_in_space(
+        &self,
+        writer: &mut Writer,
+        space: &KitsuneSpace,
+        now_ms: u64,
+    ) -> DatabaseResult<()> {
+        let expired: Vec<AgentKvKey> = self
+            .iter_space(writer, space)?
+            .filter(|(_, v)| Ok(unexpired_agent_info(v, now_ms).is_none()))
+            .map(|(k, _)| Ok(k))
+            .collect()?;
+        for key in expired {
+            self.0.delete(writer, &key)?;
+        }
+        Ok(())
+    }
+
+    /// Get the unexpired agent infos in a space.
+    pub fn query_agent_infos<R: Readable>(
+        &self,
+        reader: &R,
+        space: &KitsuneSpace,
+        now_ms: u64,
+    ) -> DatabaseResult<Vec<AgentInfoSigned>> {
+        Ok(self
+            .query_agent_infos_with_info(reader, space, now_ms)?
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect())
+    }
+
+    /// Get the unexpired agent infos in a space whose storage arcs cover
+    /// a basis location, with the arcs centered nearest the basis first.
+    pub fn query_agent_infos_near_basis<R: Readable>(
+        &self,
+        reader: &R,
+        space: &KitsuneSpace,
+        basis_loc: u32,
+        limit: u32,
+        now_ms: u64,
+    ) -> DatabaseResult<Vec<AgentInfoSigned>> {
+        let mut out: Vec<_> = self
+            .query_agent_infos_with_info(reader, space, now_ms)?
+            .into_iter()
+            .filter(|(info, _)| info.storage_arc().contains(basis_loc))
+            .collect();
+        out.sort_by_key(|(info, _)| {
+            shortest_arc_distance(info.storage_arc().center_loc, basis_loc)
+        });
+        Ok(out
+            .into_iter()
+            .take(limit as usize)
+            .map(|(_, v)| v)
+            .collect())
+    }
+
+    fn query_agent_infos_with_info<R: Readable>(
+        &self,
+        reader: &R,
+        space: &KitsuneSpace,
+        now_ms: u64,
+    ) -> DatabaseResult<Vec<(AgentInfo, AgentInfoSigned)>> {
+        self.iter_space(reader, space)?
+            .filter_map(|(_, v)| Ok(unexpired_agent_info(&v, now_ms).map(|info| (info, v))))
+            .collect()
+    }
 }
 
-/// Inject multiple agent info entries into the peer store
+/// The decoded agent info if it has not expired yet.
+/// Agent infos that can't be decoded are treated as expired.
+fn unexpired_agent_info(agent_info_signed: &AgentInfoSigned, now_ms: u64) -> Option<AgentInfo> {
+    let agent_info: AgentInfo = agent_info_signed.try_into().ok()?;
+    let expires_at_ms = agent_info
+        .signed_at_ms()
+        .saturating_add(agent_info.expires_after_ms());
+    if expires_at_ms <= now_ms {
+        None
+    } else {
+        Some(agent_info)
+    }
+}
+
+/// The current time in milliseconds, as agent infos are timestamped.
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system time is after the unix epoch")
+        .as_millis() as u64
+}
+
+/// Inject multiple agent info entries into the peer store, keeping only the
+/// newest unexpired agent info for each agent and pruning the expired agent
+/// infos from the spaces written to
 pub fn inject_agent_infos<I: IntoIterator<Item = AgentInfoSigned> + Send>(
     env: EnvironmentWrite,
     iter: I,
 ) -> DatabaseResult<()> {
     let p2p_store = AgentKv::new(env.clone().into())?;
     let env_ref = env.guard();
+    let now_ms = now_ms();
     Ok(env_ref.with_commit(|writer| {
+        let mut spaces = HashSet::new();
         for agent_info_signed in iter {
-            p2p_store.as_store_ref().put(
-                writer,
-                &(&agent_info_signed).try_into()?,
-                &agent_info_signed,
-            )?
+            spaces.insert(space_of(&agent_info_signed)?);
+            p2p_store.put_agent_info_signed(writer, agent_info_signed, now_ms)?
+        }
+        for space in spaces {
+            p2p_store.prune_expired_in_space(writer, &space, now_ms)?;
         }
         DatabaseResult::Ok(())
     })?)
 }
 
+/// Put an agent info into the peer store, keeping only the newest unexpired
+/// agent info for each agent and pruning the expired agent infos from its space
+pub fn put_agent_info_signed(
+    env: EnvironmentWrite,
+    agent_info_signed: AgentInfoSigned,
+) -> DatabaseResult<()> {
+    let p2p_store = AgentKv::new(env.clone().into())?;
+    let env_ref = env.guard();
+    let now_ms = now_ms();
+    env_ref.with_commit(|writer| {
+        let space = space_of(&agent_info_signed)?;
+        p2p_store.put_agent_info_signed(writer, agent_info_signed, now_ms)?;
+        p2p_store.prune_expired_in_space(writer, &space, now_ms)
+    })
+}
+
+/// The space an agent info is for
+fn space_of(agent_info_signed: &AgentInfoSigned) -> DatabaseResult<KitsuneSpace> {
+    let agent_info: AgentInfo = agent_info_signed
+        .try_into()
+        .map_err(|_| DatabaseError::KeyConstruction)?;
+    Ok(agent_info.as_space_ref().clone())
+}
+
+/// Prune the expired agent infos from the peer store
+pub fn prune_expired_agent_infos(env: EnvironmentWrite) -> DatabaseResult<()> {
+    let p2p_store = AgentKv::new(env.clone().into())?;
+    let env_ref = env.guard();
+    env_ref.with_commit(|writer| p2p_store.prune_expired(writer, now_ms()))
+}
+
+/// Helper function to get the unexpired agent infos in a space
+pub fn query_agent_infos(
+    env: EnvironmentRead,
+    space: &KitsuneSpace,
+) -> DatabaseResult<Vec<AgentInfoSigned>> {
+    let p2p_store = AgentKv::new(env.clone())?;
+    fresh_reader!(env, |r| {
+        p2p_store.query_agent_infos(&r, space, now_ms())
+    })
+}
+
+/// Helper function to get the unexpired agent infos in a space whose storage
+/// arcs cover a basis location
+pub fn query_agent_infos_near_basis(
+    env: EnvironmentRead,
+    space: &KitsuneSpace,
+    basis_loc: u32,
+    limit: u32,
+) -> DatabaseResult<Vec<AgentInfoSigned>> {
+    let p2p_store = AgentKv::new(env.clone())?;
+    fresh_reader!(env, |r| {
+        p2p_store.query_agent_infos_near_basis(&r, space, basis_loc, limit, now_ms())
+    })
+}
+
 /// Helper function to get all the peer data from this conductor
 pub fn all_agent_infos(env: EnvironmentRead) -> DatabaseResult<Vec<AgentInfoSigned>> {
     let p2p_store = AgentKv::new(env.clone())?;
@@ -188,15 +414,44 @@ mod tests {
 
     use super::*;
     use fixt::prelude::*;
+    use holochain_keystore::{AgentPubKeyExt, KeystoreSender};
     use holochain_state::env::ReadManager;
     use holochain_state::env::WriteManager;
     use holochain_state::test_utils::test_p2p_env;
     use holochain_state::{buffer::KvStoreT, fresh_reader_test};
+    use holochain_types::test_utils::fake_dna_hash;
+    use kitsune_p2p::dependencies::kitsune_p2p_types::dht_arc::{DhtArc, MAX_HALF_LENGTH};
     use kitsune_p2p::fixt::AgentInfoFixturator;
     use kitsune_p2p::fixt::AgentInfoSignedFixturator;
-    use kitsune_p2p::KitsuneBinType;
     use std::convert::TryInto;
 
+    /// An agent info signed by a real agent, so it verifies
+    async fn signed_agent_info(
+        keystore: &KeystoreSender,
+        space: DnaHash,
+        agent: &AgentPubKey,
+        storage_arc: DhtArc,
+        signed_at_ms: u64,
+        expires_after_ms: u64,
+    ) -> AgentInfoSigned {
+        let kitsune_agent = holochain_p2p::agent_holo_to_kit(agent.clone());
+        let agent_info = AgentInfo::new(
+            holochain_p2p::space_holo_to_kit(space),
+            kitsune_agent.clone(),
+            Vec::new(),
+            storage_arc,
+            signed_at_ms,
+            expires_after_ms,
+        );
+        let mut data = Vec::new();
+        kitsune_p2p::dependencies::kitsune_p2p_types::codec::rmp_encode(&mut data, &agent_info)
+            .unwrap();
+        let signature = agent.sign_raw(keystore, &data).await.unwrap();
+        AgentInfoSigned::try_new(kitsune_agent, signature.0.into(), data)
+            .await
+            .unwrap()
+    }
+
     #[test]
     fn kv_key_from() {
         let agent_info = fixt!(AgentInfo);
@@ -205,9 +460,9 @@ mod tests {
 
         let bytes = kv_key.as_ref().to_owned();
 
-        assert_eq!(&bytes[..32], agent_info.as_space_ref().get_bytes(),);
+        assert_eq!(&bytes[..32], &agent_info.as_space_ref().get_bytes()[..32],);
 
-        assert_eq!(&bytes[32..], agent_info.as_agent_ref().get_bytes(),);
+        assert_eq!(&bytes[32..], &agent_info.as_agent_ref().get_bytes()[..32],);
     }
 
     #[tokio::test(threaded_scheduler)]
@@ -277,4 +532,160 @@ mod tests {
 
         assert_eq!(expect, agents);
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn only_newest_unexpired_agent_info_is_kept() {
+        let test_env = test_p2p_env();
+        let env = test_env.env();
+        let keystore = env.keystore().clone();
+        let p2p_store = AgentKv::new(env.clone().into()).unwrap();
+        let space = fake_dna_hash(1);
+        let alice = AgentPubKey::new_from_pure_entropy(&keystore).await.unwrap();
+        let bob = AgentPubKey::new_from_pure_entropy(&keystore).await.unwrap();
+        let arc = DhtArc::new(0, MAX_HALF_LENGTH);
+
+        let put = |info: AgentInfoSigned, now_ms: u64| {
+            env.guard()
+                .with_commit(|writer| p2p_store.put_agent_info_signed(writer, info, now_ms))
+                .unwrap()
+        };
+        let get = |agent: &AgentPubKey| {
+            fresh_reader_test!(env, |r| p2p_store
+                .get_agent_info(&r, space.clone(), agent.clone())
+                .unwrap())
+        };
+
+        let alice_1 = signed_agent_info(&keystore, space.clone(), &alice, arc, 1000, 1000).await;
+        let alice_0 = signed_agent_info(&keystore, space.clone(), &alice, arc, 500, 5000).await;
+        let alice_2 = signed_agent_info(&keystore, space.clone(), &alice, arc, 1200, 1000).await;
+
+        put(alice_1.clone(), 1500);
+        assert_eq!(get(&alice), Some(alice_1.clone()));
+
+        // An older agent info doesn't replace a newer one
+        put(alice_0, 1500);
+        assert_eq!(get(&alice), Some(alice_1));
+        put(alice_2.clone(), 1500);
+        assert_eq!(get(&alice), Some(alice_2.clone()));
+
+        // Expired agent infos are never stored
+        let bob_1 = signed_agent_info(&keystore, space.clone(), &bob, arc, 2000, 1000).await;
+        put(bob_1.clone(), 2500);
+        let alice_3 = signed_agent_info(&keystore, space.clone(), &alice, arc, 1300, 1000).await;
+        put(alice_3, 2500);
+        assert_eq!(get(&alice), Some(alice_2));
+        assert_eq!(get(&bob), Some(bob_1));
+
+        // but an expired agent info can be replaced by a newer one
+        let alice_4 = signed_agent_info(&keystore, space.clone(), &alice, arc, 2400, 1000).await;
+        put(alice_4.clone(), 2500);
+        assert_eq!(get(&alice), Some(alice_4.clone()));
+
+        // and they are removed by pruning
+        env.guard()
+            .with_commit(|writer| p2p_store.prune_expired(writer, 3200))
+            .unwrap();
+        assert_eq!(get(&bob), None);
+        assert_eq!(get(&alice), Some(alice_4));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn putting_agent_info_prunes_its_space() {
+        let test_env = test_p2p_env();
+        let env = test_env.env();
+        let keystore = env.keystore().clone();
+        let p2p_store = AgentKv::new(env.clone().into()).unwrap();
+        let space = fake_dna_hash(1);
+        let other_space = fake_dna_hash(2);
+        let alice = AgentPubKey::new_from_pure_entropy(&keystore).await.unwrap();
+        let bob = AgentPubKey::new_from_pure_entropy(&keystore).await.unwrap();
+        let arc = DhtArc::new(0, MAX_HALF_LENGTH);
+        let now_ms = now_ms();
+
+        // Expired agent infos left in both spaces
+        let expired_alice =
+            signed_agent_info(&keystore, space.clone(), &alice, arc, now_ms - 2000, 1000).await;
+        let expired_other = signed_agent_info(
+            &keystore,
+            other_space.clone(),
+            &alice,
+            arc,
+            now_ms - 2000,
+            1000,
+        )
+        .await;
+        env.guard()
+            .with_commit(|writer| {
+                for info in vec![&expired_alice, &expired_other] {
+                    p2p_store
+                        .as_store_ref()
+                        .put(writer, &info.try_into().unwrap(), info)?;
+                }
+                DatabaseResult::Ok(())
+            })
+            .unwrap();
+
+        let bob_1 = signed_agent_info(&keystore, space.clone(), &bob, arc, now_ms, 60_000).await;
+        put_agent_info_signed(env.clone(), bob_1).unwrap();
+
+        // Only the space written to is pruned
+        let get = |space: &DnaHash| {
+            fresh_reader_test!(env, |r| p2p_store
+                .get_agent_info(&r, space.clone(), alice.clone())
+                .unwrap())
+        };
+        assert_eq!(get(&space), None);
+        assert_eq!(get(&other_space), Some(expired_other));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn query_agent_infos_by_space_and_basis() {
+        let test_env = test_p2p_env();
+        let env = test_env.env();
+        let keystore = env.keystore().clone();
+        let p2p_store = AgentKv::new(env.clone().into()).unwrap();
+        let space = fake_dna_hash(1);
+        let other_space = fake_dna_hash(2);
+
+        let mut infos = Vec::new();
+        for (space, arc) in vec![
+            (space.clone(), DhtArc::new(0, 100)),
+            (space.clone(), DhtArc::new(1000, 2000)),
+            (other_space, DhtArc::new(0, MAX_HALF_LENGTH)),
+        ] {
+            let agent = AgentPubKey::new_from_pure_entropy(&keystore).await.unwrap();
+            infos.push(signed_agent_info(&keystore, space, &agent, arc, 1000, 1000).await);
+        }
+        for info in infos.clone() {
+            env.guard()
+                .with_commit(|writer| p2p_store.put_agent_info_signed(writer, info, 1500))
+                .unwrap();
+        }
+        let kitsune_space = holochain_p2p::space_holo_to_kit(space);
+
+        let mut in_space = fresh_reader_test!(env, |r| p2p_store
+            .query_agent_infos(&r, &kitsune_space, 1500)
+            .unwrap());
+        in_space.sort();
+        let mut expect = infos[..2].to_vec();
+        expect.sort();
+        assert_eq!(in_space, expect);
+
+        let near = |basis_loc: u32, limit: u32| {
+            fresh_reader_test!(env, |r| p2p_store
+                .query_agent_infos_near_basis(&r, &kitsune_space, basis_loc, limit, 1500)
+                .unwrap())
+        };
+        // Nearest arc center first
+        assert_eq!(near(50, 10), vec![infos[0].clone(), infos[1].clone()]);
+        assert_eq!(near(50, 1), vec![infos[0].clone()]);
+        assert_eq!(near(1500, 10), vec![infos[1].clone()]);
+        assert!(near(u32::MAX / 2, 10).is_empty());
+
+        // Nothing is returned once the agent infos have expired
+        let expired = fresh_reader_test!(env, |r| p2p_store
+            .query_agent_infos_near_basis(&r, &kitsune_space, 50, 10, 2000)
+            .unwrap());
+        assert!(expired.is_empty());
+    }
 }
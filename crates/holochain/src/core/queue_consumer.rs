@@ -19,9 +19,20 @@
 //! |                 **integration, common to both paths**                 |
 //! | DhtOpIntegr.   | IntegrationLimbo | IntegratedDhtOps | Publish        |
 //! | Publish        | AuthoredDhtOps   | *n/a*            | *n/a*          |
+//! |                         **scheduled calls**                           |
+//! | CallZome       | *n/a*            | ScheduledFns     | Schedule       |
+//! | Schedule       | ScheduledFns     | ScheduledFns     | *n/a* ‡        |
+//! |                          **periodic checks**                          |
+//! | HealthCheck    | AuthoredDhtOps   | AuthoredDhtOps   | Publish §      |
 //!
 //! († Auth'd + IntQ is short for: AuthoredDhtOps + IntegrationLimbo)
 //!
+//! (‡ Schedule calls due zome functions as ordinary zome calls, and also
+//! re-triggers itself when the next scheduled function becomes due)
+//!
+//! (§ HealthCheck runs every [HEALTH_CHECK_INTERVAL] or when cued, and only
+//! notifies Publish when some authored ops are under-replicated)
+//!
 //! Implicitly, every workflow also writes to its own source queue, i.e. to
 //! remove the item it has just processed.
 
@@ -44,11 +55,16 @@ mod app_validation_consumer;
 use app_validation_consumer::*;
 mod produce_dht_ops_consumer;
 use produce_dht_ops_consumer::*;
+mod health_check_consumer;
 mod publish_dht_ops_consumer;
+mod schedule_consumer;
 use super::state::workspace::WorkspaceError;
 use crate::conductor::{api::CellConductorApiT, manager::ManagedTaskAdd};
+pub use health_check_consumer::HEALTH_CHECK_INTERVAL;
+use health_check_consumer::*;
 use holochain_p2p::HolochainP2pCell;
 use publish_dht_ops_consumer::*;
+use schedule_consumer::*;
 
 /// Spawns several long-running tasks which are responsible for processing work
 /// which shows up on various databases.
@@ -73,8 +89,12 @@ pub async fn spawn_queue_consumer_tasks(
     let (create_tx_sys, get_tx_sys) = tokio::sync::oneshot::channel();
 
     // Integration
-    let (tx_integration, handle) =
-        spawn_integrate_dht_ops_consumer(env.clone(), stop.subscribe(), get_tx_sys);
+    let (tx_integration, handle) = spawn_integrate_dht_ops_consumer(
+        env.clone(),
+        stop.subscribe(),
+        get_tx_sys,
+        cell_network.clone(),
+    );
     task_sender
         .send(ManagedTaskAdd::dont_handle(handle))
         .await
@@ -93,6 +113,26 @@ pub async fn spawn_queue_consumer_tasks(
         .await
         .expect("Failed to manage workflow handle");
 
+    // Schedule
+    let (tx_schedule, handle) =
+        spawn_schedule_consumer(env.clone(), stop.subscribe(), conductor_api.clone());
+    task_sender
+        .send(ManagedTaskAdd::dont_handle(handle))
+        .await
+        .expect("Failed to manage workflow handle");
+
+    // Health check
+    let (tx_health_check, handle) = spawn_health_check_consumer(
+        env.clone(),
+        stop.subscribe(),
+        conductor_api.clone(),
+        tx_publish.clone(),
+    );
+    task_sender
+        .send(ManagedTaskAdd::dont_handle(handle))
+        .await
+        .expect("Failed to manage workflow handle");
+
     // Sys validation
     let (tx_sys, handle) = spawn_sys_validation_consumer(
         env.clone(),
@@ -117,7 +157,15 @@ pub async fn spawn_queue_consumer_tasks(
         .await
         .expect("Failed to manage workflow handle");
 
-    InitialQueueTriggers::new(tx_sys, tx_produce, tx_publish, tx_app, tx_integration)
+    InitialQueueTriggers::new(
+        tx_sys,
+        tx_produce,
+        tx_schedule,
+        tx_health_check,
+        tx_publish,
+        tx_app,
+        tx_integration,
+    )
 }
 
 #[derive(Clone)]
@@ -127,6 +175,10 @@ pub struct InitialQueueTriggers {
     pub sys_validation: TriggerSender,
     /// Notify the ProduceDhtOps workflow to run, i.e. after InvokeCallZome
     pub produce_dht_ops: TriggerSender,
+    /// Notify the Schedule workflow to run, i.e. after a zome call schedules a function
+    pub schedule: TriggerSender,
+    /// Notify the HealthCheck workflow to run, i.e. when the check is cued
+    pub health_check: TriggerSender,
 
     /// These triggers can only be run once
     /// so they are private
@@ -140,6 +192,8 @@ impl InitialQueueTriggers {
     fn new(
         sys_validation: TriggerSender,
         produce_dht_ops: TriggerSender,
+        schedule: TriggerSender,
+        health_check: TriggerSender,
         publish_dht_ops: TriggerSender,
         app_validation: TriggerSender,
         integrate_dht_ops: TriggerSender,
@@ -147,6 +201,8 @@ impl InitialQueueTriggers {
         Self {
             sys_validation,
             produce_dht_ops,
+            schedule,
+            health_check,
             publish_dht_ops,
             app_validation,
             integrate_dht_ops,
@@ -165,6 +221,8 @@ impl InitialQueueTriggers {
                 self.publish_dht_ops.trigger();
                 self.integrate_dht_ops.trigger();
                 self.produce_dht_ops.trigger();
+                self.schedule.trigger();
+                self.health_check.trigger();
             })
         }
     }
@@ -0,0 +1,56 @@
+//! The workflow and queue consumer for the authored op health check
+
+use super::*;
+
+use crate::{
+    conductor::manager::ManagedTaskResult,
+    core::workflow::health_check_workflow::{health_check_workflow, HealthCheckWorkspace},
+};
+use holochain_state::env::EnvironmentWrite;
+
+use tokio::task::JoinHandle;
+use tracing::*;
+
+/// How often each cell checks the health of the ops it has authored,
+/// unless the check is cued earlier.
+pub const HEALTH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 5);
+
+/// Spawn the QueueConsumer for the HealthCheck workflow
+#[instrument(skip(env, stop, conductor_api, trigger_publish))]
+pub fn spawn_health_check_consumer(
+    env: EnvironmentWrite,
+    mut stop: sync::broadcast::Receiver<()>,
+    conductor_api: impl CellConductorApiT + 'static,
+    mut trigger_publish: TriggerSender,
+) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
+    let (tx, mut rx) = TriggerSender::new();
+    let handle = tokio::spawn(async move {
+        loop {
+            // Wait for next job, or run anyway once the interval has passed
+            let next_job =
+                tokio::time::timeout(HEALTH_CHECK_INTERVAL, next_job_or_exit(&mut rx, &mut stop))
+                    .await
+                    .unwrap_or(Job::Run);
+            if let Job::Shutdown = next_job {
+                tracing::warn!(
+                    "Cell is shutting down: stopping health_check_workflow queue consumer."
+                );
+                break;
+            }
+
+            // Run the workflow
+            let workspace =
+                HealthCheckWorkspace::new(env.clone().into()).expect("Could not create Workspace");
+            health_check_workflow(
+                workspace,
+                env.clone().into(),
+                &conductor_api,
+                &mut trigger_publish,
+            )
+            .await
+            .expect("Error running Workflow");
+        }
+        Ok(())
+    });
+    (tx, handle)
+}
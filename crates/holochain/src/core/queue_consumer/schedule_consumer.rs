@@ -0,0 +1,69 @@
+//! The workflow and queue consumer for scheduled zome functions
+
+use super::*;
+
+use crate::{
+    conductor::manager::ManagedTaskResult,
+    core::workflow::schedule_workflow::{schedule_workflow, ScheduleWorkspace},
+};
+use holochain_state::env::EnvironmentWrite;
+use holochain_types::Timestamp;
+
+use tokio::task::JoinHandle;
+use tracing::*;
+
+/// The longest the consumer will sleep before checking the schedule again.
+/// This keeps the timer well within tokio's limits for functions which are
+/// scheduled a long way in the future.
+const MAX_SLEEP: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Spawn the QueueConsumer for the Schedule workflow
+#[instrument(skip(env, stop, conductor_api))]
+pub fn spawn_schedule_consumer(
+    env: EnvironmentWrite,
+    mut stop: sync::broadcast::Receiver<()>,
+    conductor_api: impl CellConductorApiT + 'static,
+) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
+    let (tx, mut rx) = TriggerSender::new();
+    let handle = tokio::spawn(async move {
+        // When the earliest scheduled function is due, if there is one
+        let mut next_due: Option<Timestamp> = None;
+        loop {
+            // Wait for next job, or until the next function is due
+            let job = next_job_or_exit(&mut rx, &mut stop);
+            let job = match next_due {
+                Some(at) => tokio::time::timeout(sleep_until(at), job)
+                    .await
+                    .unwrap_or(Job::Run),
+                None => job.await,
+            };
+            if let Job::Shutdown = job {
+                tracing::warn!("Cell is shutting down: stopping schedule_workflow queue consumer.");
+                break;
+            }
+
+            // Run the workflow
+            let workspace =
+                ScheduleWorkspace::new(env.clone().into()).expect("Could not create Workspace");
+            next_due = schedule_workflow(
+                workspace,
+                env.clone().into(),
+                &conductor_api,
+                Timestamp::now(),
+            )
+            .await
+            .expect("Error running Workflow");
+        }
+        Ok(())
+    });
+    (tx, handle)
+}
+
+/// How long to sleep until the given time, capped at [MAX_SLEEP]
+fn sleep_until(at: Timestamp) -> std::time::Duration {
+    let at: chrono::DateTime<chrono::Utc> = at.into();
+    (at - chrono::Utc::now())
+        .to_std()
+        .unwrap_or_default()
+        .min(MAX_SLEEP)
+}
@@ -13,6 +13,8 @@ pub mod host_fn;
 pub mod wasm_ribosome;
 
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckInvocation;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult;
 use crate::core::ribosome::guest_callback::init::InitInvocation;
 use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
@@ -27,6 +29,7 @@ use crate::core::ribosome::guest_callback::validate_link::ValidateLinkResult;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
 use crate::core::ribosome::guest_callback::CallIterator;
+use crate::core::state::cap_grant_uses::CapGrantUsesBuf;
 use crate::core::workflow::CallZomeWorkspaceLock;
 use crate::fixt::ExternInputFixturator;
 use crate::fixt::FunctionNameFixturator;
@@ -37,25 +40,28 @@ use crate::{
 };
 use crate::{conductor::interface::SignalBroadcaster, core::ribosome::error::RibosomeError};
 use ::fixt::prelude::*;
-use derive_more::Constructor;
 use error::RibosomeResult;
 use guest_callback::{
-    entry_defs::EntryDefsHostAccess, init::InitHostAccess, migrate_agent::MigrateAgentHostAccess,
-    post_commit::PostCommitHostAccess, validate::ValidateHostAccess,
-    validation_package::ValidationPackageHostAccess,
+    entry_defs::EntryDefsHostAccess, genesis_self_check::GenesisSelfCheckHostAccess,
+    init::InitHostAccess, migrate_agent::MigrateAgentHostAccess, post_commit::PostCommitHostAccess,
+    validate::ValidateHostAccess, validation_package::ValidationPackageHostAccess,
 };
 use holo_hash::fixt::AgentPubKeyFixturator;
 use holo_hash::AgentPubKey;
 use holochain_keystore::KeystoreSender;
 use holochain_p2p::HolochainP2pCell;
 use holochain_serialized_bytes::prelude::*;
+use holochain_state::env::EnvironmentWrite;
 use holochain_types::cell::CellId;
 use holochain_types::dna::zome::HostFnAccess;
 use holochain_types::dna::DnaFile;
 use holochain_types::fixt::CapSecretFixturator;
 use holochain_types::fixt::CellIdFixturator;
+use holochain_types::EntryHashed;
 use holochain_wasm_test_utils::TestWasm;
 use holochain_zome_types::capability::CapGrant;
+use holochain_zome_types::capability::CapInfo;
+use holochain_zome_types::entry::Entry;
 use holochain_zome_types::zome::FunctionName;
 use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::ExternOutput;
@@ -93,9 +99,10 @@ pub enum HostAccess {
     ValidateCreateLink(ValidateLinkHostAccess),
     Init(InitHostAccess),
     EntryDefs(EntryDefsHostAccess),
+    GenesisSelfCheck(GenesisSelfCheckHostAccess),
     MigrateAgent(MigrateAgentHostAccess),
     ValidationPackage(ValidationPackageHostAccess),
-    PostCommit(PostCommitHostAccess), // TODO: add emit_signal access here?
+    PostCommit(PostCommitHostAccess),
 }
 
 impl From<&HostAccess> for HostFnAccess {
@@ -108,6 +115,9 @@ impl From<&HostAccess> for HostFnAccess {
             }
             HostAccess::Init(init_host_access) => init_host_access.into(),
             HostAccess::EntryDefs(entry_defs_host_access) => entry_defs_host_access.into(),
+            HostAccess::GenesisSelfCheck(genesis_self_check_host_access) => {
+                genesis_self_check_host_access.into()
+            }
             HostAccess::MigrateAgent(migrate_agent_host_access) => migrate_agent_host_access.into(),
             HostAccess::ValidationPackage(validation_package_host_access) => {
                 validation_package_host_access.into()
@@ -164,7 +174,8 @@ impl HostAccess {
     /// Get the signal broadcaster, panics if none was provided
     pub fn signal_tx(&mut self) -> &mut SignalBroadcaster {
         match self {
-            Self::ZomeCall(ZomeCallHostAccess { signal_tx, .. }) => signal_tx,
+            Self::ZomeCall(ZomeCallHostAccess { signal_tx, .. })
+            | Self::PostCommit(PostCommitHostAccess { signal_tx, .. }) => signal_tx,
             _ => panic!(
                 "Gave access to a host function that uses the signal broadcaster without providing one"
             ),
@@ -174,7 +185,8 @@ impl HostAccess {
     /// Get the associated CellId, panics if not applicable
     pub fn cell_id(&self) -> &CellId {
         match self {
-            Self::ZomeCall(ZomeCallHostAccess { cell_id, .. }) => cell_id,
+            Self::ZomeCall(ZomeCallHostAccess { cell_id, .. })
+            | Self::PostCommit(PostCommitHostAccess { cell_id, .. }) => cell_id,
             _ => panic!("Gave access to a host function that references a CellId"),
         }
     }
@@ -182,7 +194,8 @@ impl HostAccess {
     /// Get the call zome handle, panics if none was provided
     pub fn call_zome_handle(&self) -> &CellConductorReadHandle {
         match self {
-            Self::ZomeCall(ZomeCallHostAccess{call_zome_handle, .. }) => {
+            Self::ZomeCall(ZomeCallHostAccess{call_zome_handle, .. }) |
+            Self::PostCommit(PostCommitHostAccess{call_zome_handle, .. }) => {
                 call_zome_handle
             }
             _ => panic!("Gave access to a host function that uses the call zome handle without providing a call zome handle"),
@@ -269,19 +282,43 @@ impl ZomeCallInvocation {
     /// - the live cap grant needs to include the invocation's provenance AND zome/function name
     #[allow(clippy::extra_unused_lifetimes)]
     pub fn is_authorized<'a>(&self, host_access: &ZomeCallHostAccess) -> RibosomeResult<bool> {
+        Ok(self.authorized_grant(host_access)?.is_some())
+    }
+
+    /// the grant that authorizes this zome call, if there is one
+    ///
+    /// if the grant is use-limited this counts as a use of it, the check and the count happen
+    /// in their own write transaction before the call runs so concurrent calls can't use a
+    /// grant more than allowed
+    pub fn authorized_grant(
+        &self,
+        host_access: &ZomeCallHostAccess,
+    ) -> RibosomeResult<Option<CapGrant>> {
         let check_function = (self.zome_name.clone(), self.fn_name.clone());
         let check_agent = self.provenance.clone();
         let check_secret = self.cap;
 
         tokio_safe_block_on::tokio_safe_block_forever_on(async move {
-            let maybe_grant: Option<CapGrant> = host_access
-                .workspace
-                .read()
-                .await
-                .source_chain
-                .valid_cap_grant(&check_function, &check_agent, check_secret.as_ref())?;
-
-            Ok(maybe_grant.is_some())
+            let workspace = host_access.workspace.read().await;
+            let grant = workspace.source_chain.valid_cap_grant(
+                &check_function,
+                &check_agent,
+                check_secret.as_ref(),
+                &workspace.cap_grant_uses,
+            )?;
+            if let Some(CapGrant::RemoteAgent(zome_call_cap_grant)) = &grant {
+                if let Some(max_uses) = zome_call_cap_grant.limits.max_uses {
+                    let entry_hash = EntryHashed::from_content_sync(Entry::CapGrant(
+                        zome_call_cap_grant.clone(),
+                    ))
+                    .into_hash();
+                    // another call may have used the grant up since we checked it
+                    if !CapGrantUsesBuf::use_grant(&host_access.env, &entry_hash, max_uses)? {
+                        return Ok(None);
+                    }
+                }
+            }
+            Ok(grant)
         })
     }
 }
@@ -397,9 +434,11 @@ impl Invocation for ZomeCallInvocation {
     }
 }
 
-#[derive(Clone, Constructor)]
+#[derive(Clone)]
 pub struct ZomeCallHostAccess {
     pub workspace: CallZomeWorkspaceLock,
+    /// The cell's environment, for writes which can't wait for the workspace to be flushed
+    pub env: EnvironmentWrite,
     pub keystore: KeystoreSender,
     pub network: HolochainP2pCell,
     pub signal_tx: SignalBroadcaster,
@@ -408,6 +447,32 @@ pub struct ZomeCallHostAccess {
     // "resource" to give access to, but rather it's a bit of data that makes sense in
     // the context of zome calls, but not every CallContext
     pub cell_id: CellId,
+    /// The grant that authorized this call and who made it.
+    /// Set by the ribosome once the call has been authorized.
+    pub cap_info: Option<CapInfo>,
+}
+
+impl ZomeCallHostAccess {
+    pub fn new(
+        workspace: CallZomeWorkspaceLock,
+        env: EnvironmentWrite,
+        keystore: KeystoreSender,
+        network: HolochainP2pCell,
+        signal_tx: SignalBroadcaster,
+        call_zome_handle: CellConductorReadHandle,
+        cell_id: CellId,
+    ) -> Self {
+        Self {
+            workspace,
+            env,
+            keystore,
+            network,
+            signal_tx,
+            call_zome_handle,
+            cell_id,
+            cap_info: None,
+        }
+    }
 }
 
 impl From<ZomeCallHostAccess> for HostAccess {
@@ -466,6 +531,12 @@ pub trait RibosomeT: Sized + std::fmt::Debug {
         invocation: MigrateAgentInvocation,
     ) -> RibosomeResult<MigrateAgentResult>;
 
+    fn run_genesis_self_check(
+        &self,
+        access: GenesisSelfCheckHostAccess,
+        invocation: GenesisSelfCheckInvocation,
+    ) -> RibosomeResult<GenesisSelfCheckResult>;
+
     fn run_entry_defs(
         &self,
         access: EntryDefsHostAccess,
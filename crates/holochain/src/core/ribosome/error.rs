@@ -9,6 +9,7 @@ use holo_hash::AnyDhtHash;
 use holochain_serialized_bytes::prelude::SerializedBytesError;
 use holochain_types::dna::error::DnaError;
 use holochain_wasmer_host::prelude::WasmError;
+use holochain_zome_types::x_salsa20_poly1305::X25519PubKey;
 use holochain_zome_types::zome::FunctionName;
 use holochain_zome_types::zome::ZomeName;
 use thiserror::Error;
@@ -49,6 +50,10 @@ pub enum RibosomeError {
     #[error("A mandatory element is missing, dht hash: {0}")]
     ElementDeps(AnyDhtHash),
 
+    /// an x25519 key the cell's agent didn't create was used as its own
+    #[error("The x25519 key {0:?} was not created by this cell's agent")]
+    ForeignX25519Key(X25519PubKey),
+
     /// ident
     #[error("Unspecified ring error")]
     RingUnspecified,
@@ -0,0 +1,298 @@
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostAccess;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holo_hash::AgentPubKey;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::dna::zome::HostFnAccess;
+use holochain_types::dna::DnaDef;
+use holochain_zome_types::genesis::GenesisSelfCheckCallbackResult;
+use holochain_zome_types::genesis::GenesisSelfCheckData;
+use holochain_zome_types::zome::ZomeName;
+use holochain_zome_types::ExternInput;
+
+#[derive(Clone)]
+pub struct GenesisSelfCheckInvocation {
+    pub dna_def: DnaDef,
+    pub data: GenesisSelfCheckData,
+}
+
+impl GenesisSelfCheckInvocation {
+    pub fn new(
+        dna_def: DnaDef,
+        agent_key: AgentPubKey,
+        membrane_proof: Option<SerializedBytes>,
+    ) -> Self {
+        Self {
+            dna_def,
+            data: GenesisSelfCheckData {
+                agent_key,
+                membrane_proof,
+            },
+        }
+    }
+}
+
+/// The genesis self check runs before the source chain exists so
+/// there is nothing on the host it can be given access to.
+#[derive(Clone, Constructor)]
+pub struct GenesisSelfCheckHostAccess;
+
+impl From<GenesisSelfCheckHostAccess> for HostAccess {
+    fn from(genesis_self_check_host_access: GenesisSelfCheckHostAccess) -> Self {
+        Self::GenesisSelfCheck(genesis_self_check_host_access)
+    }
+}
+
+impl From<&GenesisSelfCheckHostAccess> for HostFnAccess {
+    fn from(_: &GenesisSelfCheckHostAccess) -> Self {
+        Self::none()
+    }
+}
+
+impl Invocation for GenesisSelfCheckInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::All
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["genesis_self_check".into()].into()
+    }
+    fn host_input(self) -> Result<ExternInput, SerializedBytesError> {
+        Ok(ExternInput::new((&self.data).try_into()?))
+    }
+}
+
+impl TryFrom<GenesisSelfCheckInvocation> for ExternInput {
+    type Error = SerializedBytesError;
+    fn try_from(
+        genesis_self_check_invocation: GenesisSelfCheckInvocation,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self::new((&genesis_self_check_invocation.data).try_into()?))
+    }
+}
+
+/// the aggregate result of all zome genesis self check callbacks
+#[derive(PartialEq, Debug)]
+pub enum GenesisSelfCheckResult {
+    /// all implemented genesis self check callbacks accepted the membrane proof
+    Valid,
+    /// some genesis self check callback rejected the membrane proof
+    /// ZomeName is the first zome that rejected it
+    /// String is some human readable string explaining the rejection
+    Invalid(ZomeName, String),
+}
+
+impl From<Vec<(ZomeName, GenesisSelfCheckCallbackResult)>> for GenesisSelfCheckResult {
+    fn from(callback_results: Vec<(ZomeName, GenesisSelfCheckCallbackResult)>) -> Self {
+        callback_results
+            .into_iter()
+            .fold(Self::Valid, |acc, (zome_name, x)| {
+                match x {
+                    // invalid always overrides the acc
+                    GenesisSelfCheckCallbackResult::Invalid(reason) => {
+                        Self::Invalid(zome_name, reason)
+                    }
+                    // valid allows the acc to continue
+                    GenesisSelfCheckCallbackResult::Valid => acc,
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::GenesisSelfCheckResult;
+    use crate::core::ribosome::Invocation;
+    use crate::core::ribosome::ZomesToInvoke;
+    use crate::fixt::GenesisSelfCheckHostAccessFixturator;
+    use crate::fixt::GenesisSelfCheckInvocationFixturator;
+    use crate::fixt::ZomeNameFixturator;
+    use holochain_serialized_bytes::prelude::*;
+    use holochain_types::dna::zome::HostFnAccess;
+    use holochain_zome_types::genesis::GenesisSelfCheckCallbackResult;
+    use holochain_zome_types::ExternInput;
+    use rand::prelude::*;
+
+    #[test]
+    fn genesis_self_check_callback_result_fold() {
+        let mut rng = fixt::rng();
+
+        let result_valid = || GenesisSelfCheckResult::Valid;
+        let result_invalid = || {
+            GenesisSelfCheckResult::Invalid(
+                ZomeNameFixturator::new(fixt::Empty).next().unwrap(),
+                "".into(),
+            )
+        };
+
+        let cb_valid = || {
+            (
+                ZomeNameFixturator::new(fixt::Empty).next().unwrap(),
+                GenesisSelfCheckCallbackResult::Valid,
+            )
+        };
+        let cb_invalid = || {
+            (
+                ZomeNameFixturator::new(fixt::Empty).next().unwrap(),
+                GenesisSelfCheckCallbackResult::Invalid("".into()),
+            )
+        };
+
+        for (mut results, expected) in vec![
+            (vec![], result_valid()),
+            (vec![cb_valid()], result_valid()),
+            (vec![cb_invalid()], result_invalid()),
+            (vec![cb_invalid(), cb_valid()], result_invalid()),
+        ] {
+            // order of the results should not change the final result
+            results.shuffle(&mut rng);
+
+            // number of times a callback result appears should not change the final result
+            let number_of_extras = rng.gen_range(0, 5);
+            for _ in 0..number_of_extras {
+                let maybe_extra = results.choose(&mut rng).cloned();
+                match maybe_extra {
+                    Some(extra) => results.push(extra),
+                    _ => {}
+                };
+            }
+
+            assert_eq!(expected, results.into(),);
+        }
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn genesis_self_check_invocation_allow_side_effects() {
+        let genesis_self_check_host_access =
+            GenesisSelfCheckHostAccessFixturator::new(fixt::Unpredictable)
+                .next()
+                .unwrap();
+        assert_eq!(
+            HostFnAccess::from(&genesis_self_check_host_access),
+            HostFnAccess::none(),
+        );
+    }
+
+    #[test]
+    fn genesis_self_check_invocation_zomes() {
+        let genesis_self_check_invocation =
+            GenesisSelfCheckInvocationFixturator::new(fixt::Unpredictable)
+                .next()
+                .unwrap();
+        assert_eq!(ZomesToInvoke::All, genesis_self_check_invocation.zomes(),);
+    }
+
+    #[test]
+    fn genesis_self_check_invocation_fn_components() {
+        let genesis_self_check_invocation =
+            GenesisSelfCheckInvocationFixturator::new(fixt::Unpredictable)
+                .next()
+                .unwrap();
+
+        let mut expected = vec!["genesis_self_check"];
+        for fn_component in genesis_self_check_invocation.fn_components() {
+            assert_eq!(fn_component, expected.pop().unwrap());
+        }
+    }
+
+    #[test]
+    fn genesis_self_check_invocation_host_input() {
+        let genesis_self_check_invocation = GenesisSelfCheckInvocationFixturator::new(fixt::Empty)
+            .next()
+            .unwrap();
+
+        let host_input = genesis_self_check_invocation.clone().host_input().unwrap();
+
+        assert_eq!(
+            host_input,
+            ExternInput::new(
+                SerializedBytes::try_from(&genesis_self_check_invocation.data).unwrap()
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+mod slow_tests {
+
+    use super::GenesisSelfCheckResult;
+    use crate::core::ribosome::RibosomeT;
+    use crate::fixt::curve::Zomes;
+    use crate::fixt::GenesisSelfCheckHostAccessFixturator;
+    use crate::fixt::GenesisSelfCheckInvocationFixturator;
+    use crate::fixt::WasmRibosomeFixturator;
+    use holochain_serialized_bytes::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_genesis_self_check_unimplemented() {
+        let host_access = GenesisSelfCheckHostAccessFixturator::new(fixt::Unpredictable)
+            .next()
+            .unwrap();
+        let ribosome = WasmRibosomeFixturator::new(Zomes(vec![TestWasm::Foo]))
+            .next()
+            .unwrap();
+        let mut genesis_self_check_invocation =
+            GenesisSelfCheckInvocationFixturator::new(fixt::Empty)
+                .next()
+                .unwrap();
+        genesis_self_check_invocation.dna_def = ribosome.dna_file.dna.clone();
+
+        let result = ribosome
+            .run_genesis_self_check(host_access, genesis_self_check_invocation)
+            .unwrap();
+        assert_eq!(result, GenesisSelfCheckResult::Valid,);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_genesis_self_check_implemented_valid() {
+        let host_access = GenesisSelfCheckHostAccessFixturator::new(fixt::Unpredictable)
+            .next()
+            .unwrap();
+        let ribosome = WasmRibosomeFixturator::new(Zomes(vec![TestWasm::GenesisSelfCheck]))
+            .next()
+            .unwrap();
+        let mut genesis_self_check_invocation =
+            GenesisSelfCheckInvocationFixturator::new(fixt::Empty)
+                .next()
+                .unwrap();
+        genesis_self_check_invocation.dna_def = ribosome.dna_file.dna.clone();
+        genesis_self_check_invocation.data.membrane_proof =
+            Some(SerializedBytes::from(UnsafeBytes::from(vec![0])));
+
+        let result = ribosome
+            .run_genesis_self_check(host_access, genesis_self_check_invocation)
+            .unwrap();
+        assert_eq!(result, GenesisSelfCheckResult::Valid,);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_genesis_self_check_implemented_invalid() {
+        let host_access = GenesisSelfCheckHostAccessFixturator::new(fixt::Unpredictable)
+            .next()
+            .unwrap();
+        let ribosome = WasmRibosomeFixturator::new(Zomes(vec![TestWasm::GenesisSelfCheck]))
+            .next()
+            .unwrap();
+        let mut genesis_self_check_invocation =
+            GenesisSelfCheckInvocationFixturator::new(fixt::Empty)
+                .next()
+                .unwrap();
+        genesis_self_check_invocation.dna_def = ribosome.dna_file.dna.clone();
+        genesis_self_check_invocation.data.membrane_proof = None;
+
+        let result = ribosome
+            .run_genesis_self_check(host_access, genesis_self_check_invocation)
+            .unwrap();
+        assert_eq!(
+            result,
+            GenesisSelfCheckResult::Invalid(
+                TestWasm::GenesisSelfCheck.into(),
+                "no membrane proof".into()
+            ),
+        );
+    }
+}
@@ -6,6 +6,7 @@ pub mod capability_grants;
 pub mod capability_info;
 pub mod create;
 pub mod create_link;
+pub mod create_x25519_keypair;
 pub mod debug;
 pub mod decrypt;
 pub mod delete;
@@ -5,11 +5,25 @@ use holochain_zome_types::CapabilityClaimsInput;
 use holochain_zome_types::CapabilityClaimsOutput;
 use std::sync::Arc;
 
-/// lists all the local claims filtered by tag
+/// lists all the local claims filtered by grantor and tag
 pub fn capability_claims(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: CapabilityClaimsInput,
+    call_context: Arc<CallContext>,
+    input: CapabilityClaimsInput,
 ) -> RibosomeResult<CapabilityClaimsOutput> {
-    unimplemented!();
+    let (grantor, tag) = input.into_inner();
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let claims = call_context
+            .host_access
+            .workspace()
+            .read()
+            .await
+            .source_chain
+            .cap_claims()?
+            .into_iter()
+            .filter(|claim| grantor.as_ref().map_or(true, |g| claim.grantor() == g))
+            .filter(|claim| tag.as_ref().map_or(true, |t| claim.tag() == t))
+            .collect();
+        Ok(CapabilityClaimsOutput::new(claims))
+    })
 }
@@ -9,10 +9,23 @@ use std::sync::Arc;
 /// this is only the current grants as per local CRUD
 pub fn capability_grants(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: CapabilityGrantsInput,
+    call_context: Arc<CallContext>,
+    input: CapabilityGrantsInput,
 ) -> RibosomeResult<CapabilityGrantsOutput> {
-    unimplemented!();
+    let tag = input.into_inner();
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let grants = call_context
+            .host_access
+            .workspace()
+            .read()
+            .await
+            .source_chain
+            .live_cap_grants()?
+            .into_iter()
+            .filter(|(_, grant)| tag.as_ref().map_or(true, |tag| &grant.tag == tag))
+            .collect();
+        Ok(CapabilityGrantsOutput::new(grants))
+    })
 }
 
 #[cfg(test)]
@@ -93,6 +106,87 @@ pub mod wasm_test {
         assert_eq!(entry_secret, secret,);
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_capability_grants_claims_and_info() {
+        holochain_types::observability::test_run().ok();
+        // test workspace boilerplate
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        let grants: CapabilityGrantsOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "cap_grants", ());
+        assert!(grants.into_inner().is_empty());
+
+        // a committed grant is listed
+        let secret: CapSecret =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "cap_secret", ());
+        let header: HeaderHash = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Capability,
+            "transferable_cap_grant",
+            secret
+        );
+        let grants: CapabilityGrantsOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "cap_grants", ());
+        match &grants.into_inner()[..] {
+            [(grant_header, grant)] => {
+                assert_eq!(grant_header, &header);
+                assert_eq!(grant.access, CapAccess::Transferable { secret });
+            }
+            grants => panic!("expected one grant but found {:?}", grants),
+        }
+
+        // a rolled grant replaces the original
+        let rolled_header: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "roll_cap_grant", header);
+        let grants: CapabilityGrantsOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "cap_grants", ());
+        assert_eq!(
+            grants
+                .into_inner()
+                .into_iter()
+                .map(|(header, _)| header)
+                .collect::<Vec<_>>(),
+            vec![rolled_header.clone()],
+        );
+
+        // a deleted grant is gone
+        let _: HeaderHash = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Capability,
+            "delete_cap_grant",
+            rolled_header
+        );
+        let grants: CapabilityGrantsOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "cap_grants", ());
+        assert!(grants.into_inner().is_empty());
+
+        // claims are listed
+        let claim = CapClaim::new("tag".into(), fake_agent_pubkey_2(), secret);
+        let _: HeaderHash = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Capability,
+            "accept_cap_claim",
+            claim.clone()
+        );
+        let claims: CapabilityClaimsOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "cap_claims", ());
+        assert_eq!(claims.into_inner(), vec![claim]);
+
+        // the author calling their own zome is let in by their authorship
+        let info: CapInfo =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "cap_info", ());
+        assert_eq!(info.grant, CapGrant::ChainAuthor(info.provenance.clone()));
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn ribosome_authorized_call() {
         // /////////
@@ -105,6 +199,7 @@ pub mod wasm_test {
                 uuid: "c2f5ccfb-42b4-4927-a32c-60a642265c5a".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::Capability.into()].into(),
+                rate_limits: Default::default(),
             },
             vec![TestWasm::Capability.into()],
         )
@@ -1,6 +1,10 @@
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostAccess;
 use crate::core::ribosome::RibosomeT;
+use crate::core::ribosome::ZomeCallHostAccess;
+use holochain_zome_types::capability::CapGrant;
+use holochain_zome_types::capability::CapInfo;
 use holochain_zome_types::CapabilityInfoInput;
 use holochain_zome_types::CapabilityInfoOutput;
 use std::sync::Arc;
@@ -9,8 +13,24 @@ use std::sync::Arc;
 /// also return who is originated the call (pubkey)
 pub fn capability_info(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     _input: CapabilityInfoInput,
 ) -> RibosomeResult<CapabilityInfoOutput> {
-    unimplemented!();
+    if let HostAccess::ZomeCall(ZomeCallHostAccess {
+        cap_info: Some(cap_info),
+        ..
+    }) = &call_context.host_access
+    {
+        return Ok(CapabilityInfoOutput::new(cap_info.clone()));
+    }
+
+    // callbacks are run by the conductor on behalf of the chain author
+    let author = tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let lock = call_context.host_access.workspace().read().await;
+        lock.source_chain.agent_pubkey()
+    })?;
+    Ok(CapabilityInfoOutput::new(CapInfo::new(
+        CapGrant::ChainAuthor(author.clone()),
+        author,
+    )))
 }
@@ -290,6 +290,7 @@ pub mod wasm_test {
                 uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::MultipleCalls.into()].into(),
+                rate_limits: Default::default(),
             },
             vec![TestWasm::MultipleCalls.into()],
         )
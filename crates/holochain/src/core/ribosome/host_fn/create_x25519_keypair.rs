@@ -0,0 +1,28 @@
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::RibosomeT;
+use crate::core::workflow::call_zome_workflow::CallZomeWorkspace;
+use holochain_keystore::keystore_actor::KeystoreSenderExt;
+use holochain_zome_types::CreateX25519KeypairInput;
+use holochain_zome_types::CreateX25519KeypairOutput;
+use std::sync::Arc;
+
+pub fn create_x25519_keypair(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    _input: CreateX25519KeypairInput,
+) -> RibosomeResult<CreateX25519KeypairOutput> {
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let key = call_context
+            .host_access
+            .keystore()
+            .create_x25519_keypair()
+            .await?;
+        // the key belongs to this cell's agent from now on,
+        // which is written with the rest of the workspace
+        let mut guard = call_context.host_access.workspace().write().await;
+        let workspace: &mut CallZomeWorkspace = &mut guard;
+        workspace.x25519_keys.add(&key)?;
+        Ok(CreateX25519KeypairOutput::new(key))
+    })
+}
@@ -1,14 +1,79 @@
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
+use holochain_keystore::keystore_actor::KeystoreSenderExt;
+use holochain_zome_types::bytes::Bytes;
 use holochain_zome_types::DecryptInput;
 use holochain_zome_types::DecryptOutput;
 use std::sync::Arc;
 
 pub fn decrypt(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: DecryptInput,
+    call_context: Arc<CallContext>,
+    input: DecryptInput,
 ) -> RibosomeResult<DecryptOutput> {
-    unimplemented!();
+    let input = input.into_inner();
+    Ok(DecryptOutput::new(
+        tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+            // the keystore is shared by the whole conductor so only
+            // decrypt as a recipient this cell's agent created
+            if !call_context
+                .host_access
+                .workspace()
+                .read()
+                .await
+                .x25519_keys
+                .contains(&input.recipient)?
+            {
+                return Err(RibosomeError::ForeignX25519Key(input.recipient));
+            }
+            Ok(call_context.host_access.keystore().decrypt(input).await?)
+        })?
+        .map(Bytes::from),
+    ))
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use super::*;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::WasmRibosomeFixturator;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use holochain_zome_types::x_salsa20_poly1305::{Decrypt, Encrypt};
+    use matches::assert_matches;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn decrypt_rejects_foreign_key() {
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let workspace = crate::core::workflow::CallZomeWorkspace::new(env.clone().into()).unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock;
+
+        // keys in the keystore that another cell created
+        let recipient = host_access.keystore.create_x25519_keypair().await.unwrap();
+        let sender = host_access.keystore.create_x25519_keypair().await.unwrap();
+        let encrypted = host_access
+            .keystore
+            .encrypt(Encrypt::new(sender, recipient, vec![1, 2, 3]))
+            .await
+            .unwrap();
+
+        let ribosome = WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![]))
+            .next()
+            .unwrap();
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.host_access = host_access.into();
+
+        let output = decrypt(
+            Arc::new(ribosome),
+            Arc::new(call_context),
+            DecryptInput::new(Decrypt::new(recipient, sender, encrypted)),
+        );
+        assert_matches!(output, Err(RibosomeError::ForeignX25519Key(key)) if key == recipient);
+    }
 }
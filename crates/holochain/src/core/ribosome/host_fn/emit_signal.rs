@@ -11,8 +11,7 @@ pub fn emit_signal(
     input: EmitSignalInput,
 ) -> RibosomeResult<EmitSignalOutput> {
     let cell_id = call_context.host_access().cell_id().clone();
-    let bytes = input.into_inner();
-    let signal = Signal::App(cell_id, bytes);
+    let signal = Signal::App(cell_id, call_context.zome_name(), input.into_inner());
     call_context.host_access().signal_tx().send(signal)?;
     Ok(EmitSignalOutput::new(()))
 }
@@ -1,14 +1,127 @@
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
+use holochain_keystore::keystore_actor::KeystoreSenderExt;
 use holochain_zome_types::EncryptInput;
 use holochain_zome_types::EncryptOutput;
 use std::sync::Arc;
 
 pub fn encrypt(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: EncryptInput,
+    call_context: Arc<CallContext>,
+    input: EncryptInput,
 ) -> RibosomeResult<EncryptOutput> {
-    unimplemented!();
+    let input = input.into_inner();
+    Ok(EncryptOutput::new(
+        tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+            // the keystore is shared by the whole conductor so only
+            // encrypt as a sender this cell's agent created
+            if !call_context
+                .host_access
+                .workspace()
+                .read()
+                .await
+                .x25519_keys
+                .contains(&input.sender)?
+            {
+                return Err(RibosomeError::ForeignX25519Key(input.sender));
+            }
+            Ok(call_context.host_access.keystore().encrypt(input).await?)
+        })?,
+    ))
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use super::*;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::WasmRibosomeFixturator;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::bytes::Bytes;
+    use holochain_zome_types::x_salsa20_poly1305::{Decrypt, Encrypt};
+    use holochain_zome_types::{CreateX25519KeypairOutput, DecryptInput, DecryptOutput};
+    use matches::assert_matches;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_encrypt_decrypt_test() {
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace =
+            crate::core::workflow::CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        let alice: CreateX25519KeypairOutput = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::XSalsa20Poly1305,
+            "create_x25519_keypair",
+            ()
+        );
+        let alice = alice.into_inner();
+        let bob: CreateX25519KeypairOutput = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::XSalsa20Poly1305,
+            "create_x25519_keypair",
+            ()
+        );
+        let bob = bob.into_inner();
+
+        // the created keys belong to this cell
+        assert!(workspace_lock
+            .read()
+            .await
+            .x25519_keys
+            .contains(&alice)
+            .unwrap());
+
+        let data = vec![1_u8, 2, 3];
+        let encrypted: EncryptOutput = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::XSalsa20Poly1305,
+            "encrypt",
+            EncryptInput::new(Encrypt::new(alice, bob, data.clone()))
+        );
+        let decrypted: DecryptOutput = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::XSalsa20Poly1305,
+            "decrypt",
+            DecryptInput::new(Decrypt::new(bob, alice, encrypted.into_inner()))
+        );
+        assert_eq!(decrypted.into_inner(), Some(Bytes::from(data)));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn encrypt_rejects_foreign_key() {
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let workspace = crate::core::workflow::CallZomeWorkspace::new(env.clone().into()).unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock;
+
+        // keys in the keystore that another cell created
+        let foreign = host_access.keystore.create_x25519_keypair().await.unwrap();
+        let recipient = host_access.keystore.create_x25519_keypair().await.unwrap();
+
+        let ribosome = WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![]))
+            .next()
+            .unwrap();
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.host_access = host_access.into();
+
+        let output = encrypt(
+            Arc::new(ribosome),
+            Arc::new(call_context),
+            EncryptInput::new(Encrypt::new(foreign, recipient, vec![1, 2, 3])),
+        );
+        assert_matches!(output, Err(RibosomeError::ForeignX25519Key(key)) if key == foreign);
+    }
 }
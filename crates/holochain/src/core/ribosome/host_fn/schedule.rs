@@ -1,14 +1,31 @@
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
+use crate::core::state::schedule::ScheduledFn;
+use crate::core::workflow::call_zome_workflow::CallZomeWorkspace;
+use holochain_types::Timestamp;
 use holochain_zome_types::ScheduleInput;
 use holochain_zome_types::ScheduleOutput;
 use std::sync::Arc;
 
+/// schedule a function in the calling zome to be called later by the conductor
 pub fn schedule(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: ScheduleInput,
+    call_context: Arc<CallContext>,
+    input: ScheduleInput,
 ) -> RibosomeResult<ScheduleOutput> {
-    unimplemented!()
+    let scheduled_fn = ScheduledFn::from_schedule(
+        call_context.zome_name(),
+        input.into_inner(),
+        Timestamp::now(),
+    );
+
+    // the schedule is written with the rest of the workspace
+    // so it only persists if the zome call succeeds
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let mut guard = call_context.host_access.workspace().write().await;
+        let workspace: &mut CallZomeWorkspace = &mut guard;
+        workspace.schedules.schedule(scheduled_fn)?;
+        Ok(ScheduleOutput::new(()))
+    })
 }
@@ -1,8 +1,9 @@
 use super::{
     guest_callback::{
-        entry_defs::EntryDefsHostAccess, init::InitHostAccess,
-        migrate_agent::MigrateAgentHostAccess, post_commit::PostCommitHostAccess,
-        validate::ValidateHostAccess, validation_package::ValidationPackageHostAccess,
+        entry_defs::EntryDefsHostAccess, genesis_self_check::GenesisSelfCheckHostAccess,
+        init::InitHostAccess, migrate_agent::MigrateAgentHostAccess,
+        post_commit::PostCommitHostAccess, validate::ValidateHostAccess,
+        validation_package::ValidationPackageHostAccess,
     },
     host_fn::get_agent_activity::get_agent_activity,
     HostAccess, ZomeCallHostAccess,
@@ -11,6 +12,8 @@ use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckInvocation;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult;
 use crate::core::ribosome::guest_callback::init::InitInvocation;
 use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
@@ -33,6 +36,7 @@ use crate::core::ribosome::host_fn::capability_grants::capability_grants;
 use crate::core::ribosome::host_fn::capability_info::capability_info;
 use crate::core::ribosome::host_fn::create::create;
 use crate::core::ribosome::host_fn::create_link::create_link;
+use crate::core::ribosome::host_fn::create_x25519_keypair::create_x25519_keypair;
 use crate::core::ribosome::host_fn::debug::debug;
 use crate::core::ribosome::host_fn::decrypt::decrypt;
 use crate::core::ribosome::host_fn::delete::delete;
@@ -67,7 +71,9 @@ use holochain_types::dna::{
     DnaFile,
 };
 use holochain_wasmer_host::prelude::*;
+use holochain_zome_types::capability::{CapGrant, CapInfo, ZomeCallCapGrant};
 use holochain_zome_types::entry_def::EntryDefsCallbackResult;
+use holochain_zome_types::genesis::GenesisSelfCheckCallbackResult;
 use holochain_zome_types::init::InitCallbackResult;
 use holochain_zome_types::migrate_agent::MigrateAgentCallbackResult;
 use holochain_zome_types::post_commit::PostCommitCallbackResult;
@@ -78,7 +84,7 @@ use holochain_zome_types::zome::FunctionName;
 use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::CallbackResult;
 use holochain_zome_types::ZomeCallResponse;
-use holochain_zome_types::{header::ZomeId, ExternOutput};
+use holochain_zome_types::{header::ZomeId, ExternInput, ExternOutput};
 use std::sync::Arc;
 
 /// Path to the wasm cache path
@@ -196,6 +202,10 @@ impl WasmRibosome {
             ns.insert("__sign", func!(invoke_host_function!(sign)));
             ns.insert("__decrypt", func!(invoke_host_function!(decrypt)));
             ns.insert("__encrypt", func!(invoke_host_function!(encrypt)));
+            ns.insert(
+                "__create_x25519_keypair",
+                func!(invoke_host_function!(create_x25519_keypair)),
+            );
         } else {
             ns.insert(
                 "__verify_signature",
@@ -204,6 +214,10 @@ impl WasmRibosome {
             ns.insert("__sign", func!(invoke_host_function!(unreachable)));
             ns.insert("__decrypt", func!(invoke_host_function!(unreachable)));
             ns.insert("__encrypt", func!(invoke_host_function!(unreachable)));
+            ns.insert(
+                "__create_x25519_keypair",
+                func!(invoke_host_function!(unreachable)),
+            );
         }
 
         if let HostFnAccess {
@@ -442,23 +456,45 @@ impl RibosomeT for WasmRibosome {
         host_access: ZomeCallHostAccess,
         invocation: ZomeCallInvocation,
     ) -> RibosomeResult<ZomeCallResponse> {
-        Ok(if invocation.is_authorized(&host_access)? {
-            // make a copy of these for the error handling below
-            let zome_name = invocation.zome_name.clone();
-            let fn_name = invocation.fn_name.clone();
-
-            let guest_output: ExternOutput = match self
-                .call_iterator(host_access.into(), self.clone(), invocation)
-                .next()?
-            {
-                Some(result) => result.1,
-                None => return Err(RibosomeError::ZomeFnNotExists(zome_name, fn_name)),
-            };
-
-            ZomeCallResponse::Ok(guest_output)
-        } else {
-            ZomeCallResponse::Unauthorized
-        })
+        let grant = match invocation.authorized_grant(&host_access)? {
+            Some(grant) => grant,
+            None => return Ok(ZomeCallResponse::Unauthorized),
+        };
+
+        // make a copy of these for the error handling below
+        let zome_name = invocation.zome_name.clone();
+        let fn_name = invocation.fn_name.clone();
+
+        // a grant may fix some of the arguments its functions are called with
+        let invocation = match &grant {
+            CapGrant::RemoteAgent(ZomeCallCapGrant { curry_payloads, .. }) => {
+                let payload = curry_payloads.curry(
+                    &(zome_name.clone(), fn_name.clone()),
+                    invocation.payload.into_inner(),
+                )?;
+                ZomeCallInvocation {
+                    payload: ExternInput::new(payload),
+                    ..invocation
+                }
+            }
+            CapGrant::ChainAuthor(_) => invocation,
+        };
+
+        // let the zome see which grant let this call in
+        let host_access = ZomeCallHostAccess {
+            cap_info: Some(CapInfo::new(grant, invocation.provenance.clone())),
+            ..host_access
+        };
+
+        let guest_output: ExternOutput = match self
+            .call_iterator(host_access.into(), self.clone(), invocation)
+            .next()?
+        {
+            Some(result) => result.1,
+            None => return Err(RibosomeError::ZomeFnNotExists(zome_name, fn_name)),
+        };
+
+        Ok(ZomeCallResponse::Ok(guest_output))
     }
 
     fn run_validate(
@@ -501,6 +537,14 @@ impl RibosomeT for WasmRibosome {
         do_callback!(self, access, invocation, MigrateAgentCallbackResult)
     }
 
+    fn run_genesis_self_check(
+        &self,
+        access: GenesisSelfCheckHostAccess,
+        invocation: GenesisSelfCheckInvocation,
+    ) -> RibosomeResult<GenesisSelfCheckResult> {
+        do_callback!(self, access, invocation, GenesisSelfCheckCallbackResult)
+    }
+
     fn run_validation_package(
         &self,
         access: ValidationPackageHostAccess,
@@ -3,15 +3,17 @@
 //! - App-defined signals are produced via the `emit_signal` host function.
 //! - System-defined signals are produced in various places in the system
 
+use holo_hash::{DhtOpHash, HeaderHash};
 use holochain_serialized_bytes::prelude::*;
-use holochain_types::{cell::CellId, impl_from};
+use holochain_types::{app::InstalledAppId, cell::CellId, impl_from};
+use holochain_zome_types::{signal::AppSignal, zome::ZomeName};
 
 /// A Signal is some information emitted from within Holochain out through
 /// an Interface
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
 pub enum Signal {
-    /// Signal from a Cell, generated by `emit_signal`
-    App(CellId, SerializedBytes),
+    /// Signal from a zome in a Cell, generated by `emit_signal`
+    App(CellId, ZomeName, AppSignal),
     /// System-defined signals
     System(SystemSignal),
 }
@@ -19,13 +21,113 @@ pub enum Signal {
 /// A Signal which originates from within the Holochain system, as opposed to
 /// from within a Cell
 ///
-/// TODO, decide what these will be. For instance, maybe there is a
-/// DataAvailable signal for doing async network requests
+/// These let UIs react to conductor lifecycle and DHT events without polling.
+/// They are sent across app interfaces only, as admin interfaces don't
+/// carry signals.
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
 pub enum SystemSignal {
-    /// Since we have no real system signals, we use a test signal for testing
-    /// TODO: replace instances of this with something real
+    /// A signal for testing the signal plumbing
     Test(String),
+    /// Genesis has completed for a Cell and its source chain is ready to use
+    GenesisComplete(CellId),
+    /// An App was activated
+    AppActivated(InstalledAppId),
+    /// An App was deactivated and its Cells stopped
+    AppDeactivated(InstalledAppId),
+    /// An op authored by this Cell has been validated by as many
+    /// authorities as its entry type requires
+    OpReceiptsReached {
+        /// The Cell which authored the op
+        cell_id: CellId,
+        /// The op which was validated
+        op_hash: DhtOpHash,
+        /// The number of valid receipts held for the op
+        receipt_count: u32,
+    },
+    /// An op authored by this Cell was rejected by a validating authority
+    OpRejected {
+        /// The Cell which authored the op
+        cell_id: CellId,
+        /// The op which was rejected
+        op_hash: DhtOpHash,
+    },
+    /// A zome's `post_commit` callback failed for headers
+    /// that are already on the Cell's source chain.
+    /// Anything the callback wrote is discarded
+    PostCommitFailed {
+        /// The Cell which committed the headers
+        cell_id: CellId,
+        /// The zome whose callback failed
+        zome_name: ZomeName,
+        /// The headers the callback failed for
+        headers: Vec<HeaderHash>,
+        /// Why the callback failed
+        reason: String,
+    },
+    /// A Cell joined the network space for its DNA
+    NetworkJoined(CellId),
+    /// A Cell left the network space for its DNA
+    NetworkLeft(CellId),
+}
+
+impl Signal {
+    /// The Cell this signal came from, if it is about a particular Cell
+    pub fn cell_id(&self) -> Option<&CellId> {
+        match self {
+            Signal::App(cell_id, _, _) => Some(cell_id),
+            Signal::System(signal) => signal.cell_id(),
+        }
+    }
+
+    /// The zome which emitted this signal, if it is an app signal
+    pub fn zome_name(&self) -> Option<&ZomeName> {
+        match self {
+            Signal::App(_, zome_name, _) => Some(zome_name),
+            Signal::System(_) => None,
+        }
+    }
+
+    /// The discriminator for this kind of signal, if it has one.
+    /// App signals have whatever type the zome gave them,
+    /// system signals are named after their variant.
+    pub fn signal_type(&self) -> Option<&str> {
+        match self {
+            Signal::App(_, _, signal) => signal.signal_type.as_deref(),
+            Signal::System(signal) => Some(signal.signal_type()),
+        }
+    }
+}
+
+impl SystemSignal {
+    /// The name of this kind of system signal
+    pub fn signal_type(&self) -> &'static str {
+        match self {
+            SystemSignal::Test(_) => "test",
+            SystemSignal::GenesisComplete(_) => "genesis_complete",
+            SystemSignal::AppActivated(_) => "app_activated",
+            SystemSignal::AppDeactivated(_) => "app_deactivated",
+            SystemSignal::OpReceiptsReached { .. } => "op_receipts_reached",
+            SystemSignal::OpRejected { .. } => "op_rejected",
+            SystemSignal::PostCommitFailed { .. } => "post_commit_failed",
+            SystemSignal::NetworkJoined(_) => "network_joined",
+            SystemSignal::NetworkLeft(_) => "network_left",
+        }
+    }
+
+    /// The Cell this signal is about, if it is about a particular Cell
+    pub fn cell_id(&self) -> Option<&CellId> {
+        match self {
+            SystemSignal::GenesisComplete(cell_id)
+            | SystemSignal::NetworkJoined(cell_id)
+            | SystemSignal::NetworkLeft(cell_id)
+            | SystemSignal::OpReceiptsReached { cell_id, .. }
+            | SystemSignal::OpRejected { cell_id, .. }
+            | SystemSignal::PostCommitFailed { cell_id, .. } => Some(cell_id),
+            SystemSignal::Test(_)
+            | SystemSignal::AppActivated(_)
+            | SystemSignal::AppDeactivated(_) => None,
+        }
+    }
 }
 
 pub fn test_signal(s: &str) -> Signal {
@@ -0,0 +1,117 @@
+//! Persisted count of how many times each use-limited CapGrant has been used.
+//!
+//! Grants are keyed by the hash of their entry, so an update to a grant with
+//! new limits starts counting again from zero.
+//!
+//! Uses are written straight to the database rather than through the buffer,
+//! so that concurrent zome calls each see the others' uses.
+
+use holo_hash::EntryHash;
+use holochain_state::{
+    buffer::{KvBufFresh, KvBufUsed, KvStore, KvStoreT},
+    db::CAP_GRANT_USES,
+    env::EnvironmentWrite,
+    error::{DatabaseError, DatabaseResult},
+    prelude::{BufferedStore, EnvironmentRead, GetDb, Readable, WriteManager, Writer},
+};
+
+/// The store of CapGrant use counts in a cell
+pub struct CapGrantUsesBuf(KvBufFresh<EntryHash, u32>);
+
+impl CapGrantUsesBuf {
+    /// Create a new buffer for the CapGrantUses database
+    pub fn new(env: EnvironmentRead) -> DatabaseResult<Self> {
+        let db = env.get_db(&*CAP_GRANT_USES)?;
+        Ok(Self(KvBufFresh::new(env, db)))
+    }
+
+    /// The number of times a grant has been used
+    pub fn uses<R: Readable>(&self, r: &R, grant: &EntryHash) -> DatabaseResult<u32> {
+        // read through the reader we were given rather than a fresh one
+        let used: &KvBufUsed<EntryHash, u32> = &self.0;
+        Ok(used.get(r, grant)?.unwrap_or(0))
+    }
+
+    /// Count another use of a grant if it has been used fewer than `max_uses` times.
+    /// Returns false, without counting, if the grant is used up.
+    ///
+    /// The check and the count happen in a single write transaction, so no
+    /// matter how many calls race to use a grant it is never used more than
+    /// `max_uses` times.
+    pub fn use_grant(
+        env: &EnvironmentWrite,
+        grant: &EntryHash,
+        max_uses: u32,
+    ) -> DatabaseResult<bool> {
+        let store: KvStore<EntryHash, u32> = KvStore::new(env.get_db(&*CAP_GRANT_USES)?);
+        env.guard().with_commit(|writer| {
+            let uses = store.get(writer, grant)?.unwrap_or(0);
+            if uses >= max_uses {
+                return Ok(false);
+            }
+            store.put(writer, grant, &(uses + 1))?;
+            Ok(true)
+        })
+    }
+
+    /// Access the underlying environment
+    pub fn env(&self) -> &EnvironmentRead {
+        self.0.env()
+    }
+}
+
+impl BufferedStore for CapGrantUsesBuf {
+    type Error = DatabaseError;
+
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
+        self.0.flush_to_txn_ref(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_state::{env::ReadManager, test_utils::test_cell_env};
+    use holochain_types::test_utils::fake_entry_hash;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn uses_are_counted_up_to_the_limit() -> DatabaseResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let grant = fake_entry_hash(1);
+        let other = fake_entry_hash(2);
+
+        assert!(CapGrantUsesBuf::use_grant(&env, &grant, 2)?);
+        assert!(CapGrantUsesBuf::use_grant(&env, &grant, 2)?);
+        // the grant is used up
+        assert!(!CapGrantUsesBuf::use_grant(&env, &grant, 2)?);
+
+        let buf = CapGrantUsesBuf::new(env.clone().into())?;
+        let env_ref = env.guard();
+        let reader = env_ref.reader()?;
+        assert_eq!(buf.uses(&reader, &grant)?, 2);
+        assert_eq!(buf.uses(&reader, &other)?, 0);
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn concurrent_uses_never_exceed_the_limit() -> DatabaseResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let grant = fake_entry_hash(1);
+
+        let calls = (0..2).map(|_| {
+            let env = env.clone();
+            let grant = grant.clone();
+            tokio::task::spawn_blocking(move || CapGrantUsesBuf::use_grant(&env, &grant, 1))
+        });
+        let mut allowed = 0;
+        for call in futures::future::join_all(calls).await {
+            if call.unwrap()? {
+                allowed += 1;
+            }
+        }
+        assert_eq!(allowed, 1);
+        Ok(())
+    }
+}
@@ -6,7 +6,7 @@
 
 use super::{
     element_buf::ElementBuf,
-    metadata::{ChainItemKey, LinkMetaKey, MetadataBuf, MetadataBufT},
+    metadata::{choose_update, ChainItemKey, LinkMetaKey, MetadataBuf, MetadataBufT, SysMetaVal},
 };
 use crate::core::workflow::integrate_dht_ops_workflow::integrate_single_metadata;
 use either::Either;
@@ -30,11 +30,13 @@ use holochain_types::{
     entry::option_entry_hashed,
     link::{GetLinksResponse, WireLinkMetaKey},
     metadata::{EntryDhtStatus, MetadataSet, TimedHeaderHash},
+    warrant::{SignedWarrant, SignedWarrantExt},
     EntryHashed, HeaderHashed,
 };
 use holochain_zome_types::{
     element::SignedHeader,
-    header::HeaderType,
+    entry::GetStrategy,
+    header::{Header, HeaderType},
     link::Link,
     metadata::{Details, ElementDetails, EntryDetails},
     query::ChainQueryFilter,
@@ -46,9 +48,14 @@ use std::collections::{BTreeMap, BTreeSet};
 use tracing::*;
 use tracing_futures::Instrument;
 
+/// The most updates a get will follow when looking for the latest update
+pub const MAX_UPDATE_HOPS: usize = 32;
+
 #[cfg(test)]
 mod authored_test;
 #[cfg(test)]
+mod meta_test;
+#[cfg(test)]
 mod network_tests;
 
 #[cfg(all(test, outdated_tests))]
@@ -313,7 +320,9 @@ where
         &mut self,
         agent_activity: AgentActivity,
     ) -> CascadeResult<()> {
-        let cache_data = ok_or_return!(self.cache_data.as_mut());
+        if self.cache_data.is_none() {
+            return Ok(());
+        }
         let AgentActivity {
             agent,
             // Cache the chain status in the metadata
@@ -326,8 +335,12 @@ where
             highest_observed,
             valid_activity,
             rejected_activity,
-            ..
+            // Cache any warrants that were signed by their author
+            // and whose proof holds up
+            warrants,
         } = agent_activity;
+        let warrants = self.verified_warrants(&agent, warrants).await?;
+        let cache_data = ok_or_return!(self.cache_data.as_mut());
         match valid_activity {
             ChainItems::Full(headers) => {
                 let hashes = headers
@@ -381,9 +394,49 @@ where
                 .meta
                 .register_activity_observed(&agent, highest_observed)?;
         }
+        for warrant in warrants {
+            cache_data.meta.register_warrant(warrant);
+        }
         Ok(())
     }
 
+    /// The warrants about this agent that are signed by their author
+    /// and whose proof holds up against the headers it is about
+    async fn verified_warrants(
+        &mut self,
+        agent: &AgentPubKey,
+        warrants: Vec<SignedWarrant>,
+    ) -> CascadeResult<Vec<SignedWarrant>> {
+        let mut verified = Vec::new();
+        for warrant in warrants {
+            if &warrant.warrant.warrantee != agent {
+                continue;
+            }
+            if let Err(e) = warrant.validate().await {
+                warn!(msg = "Dropping a warrant with an invalid signature", ?e);
+                continue;
+            }
+            let mut headers = Vec::new();
+            for (hash, _) in warrant.warrant.proof.signed_headers() {
+                if let Some(shh) = self
+                    .retrieve_header(hash.clone(), GetOptions::default())
+                    .await?
+                {
+                    headers.push(shh.header().clone());
+                }
+            }
+            match warrant.verify_proof(&headers).await {
+                Ok(true) => verified.push(warrant),
+                Ok(false) => warn!(msg = "Dropping a warrant with an invalid proof", ?warrant),
+                Err(e) => warn!(
+                    msg = "Dropping a warrant whose proof couldn't be checked",
+                    ?e
+                ),
+            }
+        }
+        Ok(verified)
+    }
+
     fn update_stores(&mut self, element_status: ElementStatus) -> CascadeResult<()> {
         let cache_data = ok_or_return!(self.cache_data.as_mut());
         let ElementStatus { element, status } = element_status;
@@ -454,6 +507,121 @@ where
         Ok(())
     }
 
+    /// Register the hashes from a remote [MetadataSet] in the cache.
+    /// Links are not registered because the full [CreateLink] is needed,
+    /// use `fetch_links` for those.
+    ///
+    /// An authority could send us anything so each header is retrieved
+    /// and only registered if it is signed by its author and references the basis.
+    /// The validation status is only registered if the basis header is too.
+    ///
+    /// [CreateLink]: holochain_zome_types::header::CreateLink
+    async fn put_meta_in_cache(
+        &mut self,
+        basis: AnyDhtHash,
+        metadata: MetadataSet,
+    ) -> CascadeResult<()> {
+        if self.cache_data.is_none() {
+            return Ok(());
+        }
+        let MetadataSet {
+            headers,
+            invalid_headers,
+            deletes,
+            updates,
+            links: _,
+            link_removes,
+            validation_status,
+            entry_dht_status: _,
+        } = metadata;
+        type ToValue = fn(TimedHeaderHash) -> SysMetaVal;
+        let claimed = match *basis.hash_type() {
+            AnyDht::Entry => vec![
+                (headers, SysMetaVal::NewEntry as ToValue),
+                (invalid_headers, SysMetaVal::RejectedNewEntry as ToValue),
+                (deletes, SysMetaVal::Delete as ToValue),
+                (updates, SysMetaVal::Update as ToValue),
+            ],
+            AnyDht::Header => vec![
+                (deletes, SysMetaVal::Delete as ToValue),
+                (updates, SysMetaVal::Update as ToValue),
+                (link_removes, SysMetaVal::DeleteLink as ToValue),
+            ],
+        };
+        let mut values = Vec::new();
+        for (hashes, to_value) in claimed {
+            for timed in hashes {
+                let header = match self.retrieve_signed_header(timed.header_hash).await? {
+                    Some(header) => header,
+                    None => continue,
+                };
+                let value = to_value(header.clone().into());
+                if meta_references_basis(&value, &header, &basis) {
+                    values.push(value);
+                } else {
+                    warn!(
+                        msg = "Dropping remote metadata that doesn't reference its basis",
+                        ?basis,
+                        ?value
+                    );
+                }
+            }
+        }
+        let validation_status = match *basis.hash_type() {
+            AnyDht::Header if !validation_status.is_empty() => {
+                match self.retrieve_signed_header(basis.clone().into()).await? {
+                    Some(_) => validation_status,
+                    None => BTreeSet::new(),
+                }
+            }
+            _ => BTreeSet::new(),
+        };
+
+        let cache_data = ok_or_return!(self.cache_data.as_mut());
+        match *basis.hash_type() {
+            AnyDht::Entry => {
+                let entry_hash: EntryHash = basis.into();
+                for value in values {
+                    cache_data
+                        .meta
+                        .register_raw_on_entry(entry_hash.clone(), value)?;
+                }
+            }
+            AnyDht::Header => {
+                let header_hash: HeaderHash = basis.into();
+                for value in values {
+                    cache_data
+                        .meta
+                        .register_raw_on_header(header_hash.clone(), value);
+                }
+                for status in validation_status {
+                    cache_data
+                        .meta
+                        .register_validation_status(header_hash.clone(), status);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Retrieve a header and return it if it is signed by its author
+    async fn retrieve_signed_header(
+        &mut self,
+        hash: HeaderHash,
+    ) -> CascadeResult<Option<HeaderHashed>> {
+        let shh = ok_or_return!(
+            self.retrieve_header(hash, GetOptions::default()).await?,
+            None
+        );
+        match shh.validate().await {
+            Ok(()) => Ok(Some(shh.into_header_and_signature().0)),
+            Err(e) => {
+                warn!(msg = "Dropping a header with an invalid signature", ?e);
+                Ok(None)
+            }
+        }
+    }
+
     #[instrument(skip(self, hashes, options))]
     /// Exactly the same as fetch_elements_via_entry
     /// except the network is cloned and a task is spawned
@@ -465,7 +633,7 @@ where
     ) -> CascadeResult<()> {
         // Network needs mut access for calls which we can't share across
         // threads so we need to clone.
-        let network = ok_or_return!(self.network.clone());
+        let network = ok_or_return!(self.network.clone().filter(|_| !options.local_only));
 
         // Spawn a task to run in parallel for each entry.
         // This works because we don't need to use self and therefor
@@ -500,7 +668,7 @@ where
         hash: HeaderHash,
         options: GetOptions,
     ) -> CascadeResult<()> {
-        let network = ok_or_return!(self.network.as_mut());
+        let network = ok_or_return!(self.network.as_mut().filter(|_| !options.local_only));
         let results = network.get(hash.into(), options).await?;
         // Search through the returns for the first delete
         for response in results.into_iter() {
@@ -555,7 +723,7 @@ where
     ) -> CascadeResult<()> {
         // Network needs mut access for calls which we can't share across
         // threads so we need to clone.
-        let network = ok_or_return!(self.network.clone());
+        let network = ok_or_return!(self.network.clone().filter(|_| !options.local_only));
 
         // Spawn a task to run in parallel for each entry.
         // This works because we don't need to use self and therefor
@@ -591,7 +759,7 @@ where
         hash: EntryHash,
         options: GetOptions,
     ) -> CascadeResult<()> {
-        let network = ok_or_return!(self.network.as_mut());
+        let network = ok_or_return!(self.network.as_mut().filter(|_| !options.local_only));
         let results = network
             .get(hash.clone().into(), options.clone())
             .instrument(debug_span!("fetch_element_via_entry::network_get"))
@@ -603,15 +771,21 @@ where
         Ok(())
     }
 
-    // TODO: Remove when used
-    #[allow(dead_code)]
-    async fn fetch_meta(
+    /// Fetch the metadata on a basis from the network
+    /// and merge it into the cache
+    #[instrument(skip(self, options))]
+    pub async fn fetch_meta(
         &mut self,
         basis: AnyDhtHash,
         options: GetMetaOptions,
     ) -> CascadeResult<Vec<MetadataSet>> {
         let network = ok_or_return!(self.network.as_mut(), vec![]);
-        Ok(network.get_meta(basis.clone(), options).await?)
+        let all_metadata = network.get_meta(basis.clone(), options).await?;
+        for metadata in &all_metadata {
+            self.put_meta_in_cache(basis.clone(), metadata.clone())
+                .await?;
+        }
+        Ok(all_metadata)
     }
 
     #[instrument(skip(self, options))]
@@ -962,9 +1136,14 @@ where
         options: GetOptions,
     ) -> CascadeResult<Option<EntryDetails>> {
         debug!("in get entry details");
-        // Update the cache from the network
-        self.fetch_element_via_entry(entry_hash.clone(), options.clone())
-            .await?;
+        // Update the cache from the network unless
+        // only the content is wanted and we already have it
+        if options.strategy == GetStrategy::Latest
+            || self.get_entry_local_raw(&entry_hash)?.is_none()
+        {
+            self.fetch_element_via_entry(entry_hash.clone(), options.clone())
+                .await?;
+        }
 
         // Get the entry and metadata
         self.create_entry_details(entry_hash).await
@@ -1067,9 +1246,13 @@ where
         options: GetOptions,
     ) -> CascadeResult<Option<ElementDetails>> {
         debug!("in get header details");
-        // Network
-        self.fetch_element_via_header(header_hash.clone(), options)
-            .await?;
+        // Network, unless only the content is wanted and we already have it
+        if options.strategy == GetStrategy::Latest
+            || self.get_element_local_raw(&header_hash)?.is_none()
+        {
+            self.fetch_element_via_header(header_hash.clone(), options)
+                .await?;
+        }
 
         // Get the element and the metadata
         self.create_element_details(header_hash)
@@ -1357,19 +1540,150 @@ where
         }
     }
 
+    #[instrument(skip(self, options))]
+    /// Follows the live updates on an entry or header to the end of the
+    /// update chain, fetching the latest data for each hop on the way.
+    /// See [choose_update] for how forks and cycles are handled.
+    /// Gives up after [MAX_UPDATE_HOPS] hops and returns the furthest
+    /// hash reached, so a long chain can't keep a get fetching forever.
+    async fn find_latest_update(
+        &mut self,
+        hash: AnyDhtHash,
+        mut options: GetOptions,
+    ) -> CascadeResult<AnyDhtHash> {
+        // Entry gets only return the updates with all the metadata
+        options.all_live_headers_with_metadata = true;
+        let mut visited = HashSet::new();
+        let mut current = hash;
+        loop {
+            match *current.hash_type() {
+                AnyDht::Entry => {
+                    self.fetch_element_via_entry(current.clone().into(), options.clone())
+                        .await?
+                }
+                AnyDht::Header => {
+                    self.fetch_element_via_header(current.clone().into(), options.clone())
+                        .await?
+                }
+            }
+            visited.insert(current.clone());
+            match self.next_update(&current, &visited)? {
+                Some(_) if visited.len() > MAX_UPDATE_HOPS => {
+                    warn!(
+                        "Stopped following updates after {} hops at {:?}",
+                        MAX_UPDATE_HOPS, current
+                    );
+                    return Ok(current);
+                }
+                Some(next) => current = next,
+                None => return Ok(current),
+            }
+        }
+    }
+
+    /// Choose the next hop in an update chain from the live updates
+    /// held in any of the local databases
+    fn next_update(
+        &self,
+        hash: &AnyDhtHash,
+        visited: &HashSet<AnyDhtHash>,
+    ) -> CascadeResult<Option<AnyDhtHash>> {
+        fn get_updates<P: PrefixType, M: MetadataBufT<P>, R: Readable>(
+            db: &DbPair<M, P>,
+            r: &R,
+            hash: &AnyDhtHash,
+        ) -> DatabaseResult<Vec<TimedHeaderHash>> {
+            db.meta.get_updates(r, hash.clone())?.collect()
+        }
+        fn is_deleted<P: PrefixType, M: MetadataBufT<P>, R: Readable>(
+            db: &DbPair<M, P>,
+            r: &R,
+            hash: &HeaderHash,
+        ) -> DatabaseResult<bool> {
+            Ok(db
+                .meta
+                .get_deletes_on_header(r, hash.clone())?
+                .next()?
+                .is_some())
+        }
+
+        let env = ok_or_return!(self.env.as_ref(), None);
+        let authored_data = self.authored_data.as_ref();
+        let integrated_data = self.integrated_data.as_ref();
+        let cache_data = self.cache_data.as_ref().map(DbPair::from);
+        fresh_reader!(env, |r| {
+            // Gather the updates from every database
+            let mut updates = BTreeSet::new();
+            if let Some(db) = authored_data {
+                updates.extend(get_updates(db, &r, hash)?);
+            }
+            if let Some(db) = integrated_data {
+                updates.extend(get_updates(db, &r, hash)?);
+            }
+            if let Some(db) = &cache_data {
+                updates.extend(get_updates(db, &r, hash)?);
+            }
+
+            // Only follow updates that aren't deleted or invalid
+            let mut live_updates = Vec::with_capacity(updates.len());
+            for update in updates {
+                let hash = &update.header_hash;
+                let mut is_live = true;
+                if let Some(db) = authored_data {
+                    is_live &= !is_deleted(db, &r, hash)?;
+                }
+                if let Some(db) = integrated_data {
+                    is_live &= !is_deleted(db, &r, hash)?;
+                }
+                if let Some(db) = &cache_data {
+                    is_live &= !is_deleted(db, &r, hash)?
+                        && db.meta.get_validation_status(&r, hash)?.is_valid();
+                }
+                if is_live {
+                    live_updates.push(update);
+                }
+            }
+
+            choose_update(live_updates, visited, |update| {
+                Ok(match *hash.hash_type() {
+                    AnyDht::Header => Some(update.header_hash.clone().into()),
+                    // Entries move on to the entry the update created
+                    AnyDht::Entry => self
+                        .get_header_local_raw_with_sig(&update.header_hash)?
+                        .and_then(|shh| match shh.header() {
+                            Header::Update(u) => Some(u.entry_hash.clone().into()),
+                            _ => None,
+                        }),
+                })
+            })
+        })
+    }
+
     #[instrument(skip(self))]
     /// Updates the cache with the latest network authority data
     /// and returns what is in the cache.
     /// This gives you the latest possible picture of the current dht state.
     /// Data from your zome call is also added to the cache.
+    /// If only the content is wanted then this is the same as [Cascade::retrieve].
     pub async fn dht_get(
         &mut self,
         hash: AnyDhtHash,
-        options: GetOptions,
+        mut options: GetOptions,
     ) -> CascadeResult<Option<Element>> {
-        match *hash.hash_type() {
-            AnyDht::Entry => self.dht_get_entry(hash.into(), options).await,
-            AnyDht::Header => self.dht_get_header(hash.into(), options).await,
+        let hash = if options.follow_updates {
+            let latest = self.find_latest_update(hash, options.clone()).await?;
+            // The latest data has just been fetched
+            options.local_only = true;
+            latest
+        } else {
+            hash
+        };
+        match options.strategy {
+            GetStrategy::Latest => match *hash.hash_type() {
+                AnyDht::Entry => self.dht_get_entry(hash.into(), options).await,
+                AnyDht::Header => self.dht_get_header(hash.into(), options).await,
+            },
+            GetStrategy::Content => self.retrieve(hash, options).await,
         }
     }
 
@@ -1380,6 +1694,11 @@ where
         mut options: GetOptions,
     ) -> CascadeResult<Option<Details>> {
         options.all_live_headers_with_metadata = true;
+        let hash = if options.follow_updates {
+            self.find_latest_update(hash, options.clone()).await?
+        } else {
+            hash
+        };
         match *hash.hash_type() {
             AnyDht::Entry => Ok(self
                 .get_entry_details(hash.into(), options)
@@ -1654,8 +1973,15 @@ where
         hashes: Vec<(u32, HeaderHash)>,
     ) -> CascadeResult<AgentActivity<Element>> {
         let cache_data = ok_or_return!(self.cache_data.as_ref(), AgentActivity::empty(&agent));
+        let env = ok_or_return!(self.env.as_ref(), AgentActivity::empty(&agent));
         // Now try getting the latest activity from cache
         let highest_observed = cache_data.meta.get_activity_observed(&agent)?;
+        let warrants = fresh_reader!(env, |r| {
+            cache_data
+                .meta
+                .get_warrants(&r, &agent)?
+                .collect::<Vec<_>>()
+        })?;
         match cache_data.meta.get_activity_status(&agent)? {
             Some(status) => Ok(AgentActivity {
                 agent,
@@ -1663,6 +1989,7 @@ where
                 rejected_activity: ChainItems::NotRequested,
                 status,
                 highest_observed,
+                warrants,
             }),
             // If we don't have any status then we must return an empty chain
             None => Ok(AgentActivity {
@@ -1671,6 +1998,7 @@ where
                 rejected_activity: ChainItems::NotRequested,
                 status: ChainStatus::Empty,
                 highest_observed,
+                warrants,
             }),
         }
     }
@@ -1918,6 +2246,33 @@ where
     }
 }
 
+/// Does the header behind this metadata actually reference the basis
+fn meta_references_basis(value: &SysMetaVal, header: &Header, basis: &AnyDhtHash) -> bool {
+    let referenced: Option<AnyDhtHash> = match (value, header, *basis.hash_type()) {
+        (SysMetaVal::NewEntry(_), _, AnyDht::Entry)
+        | (SysMetaVal::RejectedNewEntry(_), _, AnyDht::Entry) => header
+            .entry_data()
+            .map(|(entry_hash, _)| entry_hash.clone().into()),
+        (SysMetaVal::Delete(_), Header::Delete(d), AnyDht::Entry) => {
+            Some(d.deletes_entry_address.clone().into())
+        }
+        (SysMetaVal::Delete(_), Header::Delete(d), AnyDht::Header) => {
+            Some(d.deletes_address.clone().into())
+        }
+        (SysMetaVal::Update(_), Header::Update(u), AnyDht::Entry) => {
+            Some(u.original_entry_address.clone().into())
+        }
+        (SysMetaVal::Update(_), Header::Update(u), AnyDht::Header) => {
+            Some(u.original_header_address.clone().into())
+        }
+        (SysMetaVal::DeleteLink(_), Header::DeleteLink(d), AnyDht::Header) => {
+            Some(d.link_add_address.clone().into())
+        }
+        _ => None,
+    };
+    referenced.as_ref() == Some(basis)
+}
+
 impl<'a, M: MetadataBufT> From<&'a DbPairMut<'a, M>> for DbPair<'a, M> {
     fn from(n: &'a DbPairMut<'a, M>) -> Self {
         Self {
@@ -0,0 +1,79 @@
+use super::*;
+use crate::fixt::SignatureFixturator;
+use ::fixt::prelude::*;
+use holo_hash::fixt::EntryHashFixturator;
+use holochain_keystore::{KeystoreSender, KeystoreSenderExt};
+use holochain_state::{
+    fresh_reader_test,
+    test_utils::{test_cell_env, test_keystore},
+};
+use holochain_types::fixt::*;
+
+async fn delete(
+    keystore: &KeystoreSender,
+    author: &AgentPubKey,
+    entry_hash: &EntryHash,
+) -> SignedHeaderHashed {
+    let mut delete = fixt!(Delete);
+    delete.author = author.clone();
+    delete.deletes_entry_address = entry_hash.clone();
+    SignedHeaderHashed::new(keystore, HeaderHashed::from_content_sync(delete.into()))
+        .await
+        .unwrap()
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn only_verified_remote_meta_is_cached() {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let keystore = test_keystore();
+    let author = keystore
+        .clone()
+        .generate_sign_keypair_from_pure_entropy()
+        .await
+        .unwrap();
+    let entry_hash = fixt!(EntryHash);
+
+    let valid = delete(&keystore, &author, &entry_hash).await;
+    // Deletes a different entry
+    let other_basis = delete(&keystore, &author, &fixt!(EntryHash)).await;
+    // Not signed by its author
+    let (header, _) = delete(&keystore, &author, &entry_hash)
+        .await
+        .into_header_and_signature();
+    let forged = SignedHeaderHashed::with_presigned(header, fixt!(Signature));
+    // Not held by us or anyone else
+    let missing = delete(&keystore, &author, &entry_hash).await;
+
+    let mut element_cache = ElementBuf::cache(env.clone().into()).unwrap();
+    let mut meta_cache = MetadataBuf::cache(env.clone().into()).unwrap();
+    for shh in vec![valid.clone(), other_basis.clone(), forged.clone()] {
+        element_cache.put(shh, None).unwrap();
+    }
+    let metadata = MetadataSet {
+        deletes: vec![&valid, &other_basis, &forged, &missing]
+            .into_iter()
+            .map(|shh| shh.header_hashed().clone().into())
+            .collect(),
+        ..Default::default()
+    };
+
+    Cascade::empty()
+        .with_cache(DbPairMut {
+            element: &mut element_cache,
+            meta: &mut meta_cache,
+        })
+        .put_meta_in_cache(entry_hash.clone().into(), metadata)
+        .await
+        .unwrap();
+
+    let deletes: Vec<_> = fresh_reader_test!(env, |r| meta_cache
+        .get_deletes_on_entry(&r, entry_hash.clone())
+        .unwrap()
+        .collect()
+        .unwrap());
+    assert_eq!(
+        deletes,
+        vec![TimedHeaderHash::from(valid.header_hashed().clone())]
+    );
+}
@@ -5,7 +5,7 @@ use crate::{
     core::{
         state::{
             element_buf::ElementBuf,
-            metadata::{MetadataBuf, MetadataBufT},
+            metadata::{MetadataBuf, MetadataBufT, SysMetaVal},
         },
         workflow::integrate_dht_ops_workflow::integrate_single_metadata,
         workflow::produce_dht_ops_workflow::dht_op_light::error::DhtOpConvertResult,
@@ -49,6 +49,7 @@ use holochain_types::{
 use holochain_wasm_test_utils::TestWasm;
 use holochain_zome_types::{
     element::SignedHeaderHashed,
+    entry::GetStrategy,
     header::*,
     link::Link,
     metadata::{Details, EntryDhtStatus},
@@ -184,6 +185,7 @@ async fn get_from_another_agent() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            rate_limits: Default::default(),
         },
         vec![TestWasm::Create.into()],
     )
@@ -224,6 +226,7 @@ async fn get_from_another_agent() {
         race_timeout_ms: None,
         follow_redirects: false,
         all_live_headers_with_metadata: false,
+        ..Default::default()
     };
 
     // Bob store element
@@ -335,6 +338,7 @@ async fn get_links_from_another_agent() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            rate_limits: Default::default(),
         },
         vec![TestWasm::Create.into()],
     )
@@ -490,6 +494,123 @@ impl Shutdown {
     }
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn get_options_choose_how_data_is_found() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let test_network = test_network(None, None).await;
+
+    let (element_fixt_store, _) = generate_fixt_store().await;
+    let (header_hash, element) = element_fixt_store.into_iter().next().unwrap();
+
+    // The element is in the cache but it has been deleted
+    let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+    let (shh, e) = element.clone().into_inner();
+    workspace
+        .element_cache
+        .put(shh, option_entry_hashed(e))
+        .unwrap();
+    workspace.meta_cache.register_raw_on_header(
+        header_hash.clone(),
+        SysMetaVal::Delete(TimedHeaderHash::from(fixt!(HeaderHash))),
+    );
+
+    let mut cascade = workspace.cascade(test_network.cell_network());
+
+    // The latest data shows the element is deleted
+    let options = GetOptions {
+        local_only: true,
+        ..Default::default()
+    };
+    let r = cascade
+        .dht_get(header_hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(r, None);
+
+    // Only the content is wanted so the delete doesn't matter
+    let options = GetOptions {
+        strategy: GetStrategy::Content,
+        ..options
+    };
+    let r = cascade
+        .dht_get(header_hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(r, Some(element));
+
+    // Nothing is found for data that isn't held locally
+    let r = cascade
+        .dht_get(fixt!(EntryHash).into(), options)
+        .await
+        .unwrap();
+    assert_eq!(r, None);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn get_follows_updates() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let test_network = test_network(None, None).await;
+
+    // An entry is created and then updated
+    let create = fixt!(Create);
+    let create = HeaderHashed::from_content_sync(Header::Create(create));
+    let create_hash = create.as_hash().clone();
+    let mut update = fixt!(Update);
+    update.original_header_address = create_hash.clone();
+    update.original_entry_address = create.entry_hash().cloned().unwrap();
+    let update = HeaderHashed::from_content_sync(Header::Update(update));
+    let update_hash = update.as_hash().clone();
+
+    // Both are in the cache
+    let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+    let elements = vec![create, update]
+        .into_iter()
+        .map(|h| {
+            Element::new(
+                SignedHeaderHashed::with_presigned(h, fixt!(Signature)),
+                None,
+            )
+        })
+        .collect::<Vec<_>>();
+    for element in &elements {
+        let (shh, _) = element.clone().into_inner();
+        workspace.element_cache.put(shh, None).unwrap();
+    }
+    for op in produce_op_lights_from_elements(elements.iter().collect()).unwrap() {
+        integrate_single_metadata(op, &workspace.element_cache, &mut workspace.meta_cache).unwrap();
+    }
+
+    let mut cascade = workspace.cascade(test_network.cell_network());
+    let options = GetOptions {
+        local_only: true,
+        ..Default::default()
+    };
+
+    // The requested header is returned by default
+    let r = cascade
+        .dht_get(create_hash.clone().into(), options.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(r.header_address(), &create_hash);
+
+    // The update is returned when following updates
+    let options = GetOptions {
+        follow_updates: true,
+        ..options
+    };
+    let r = cascade
+        .dht_get(create_hash.into(), options)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(r.header_address(), &update_hash);
+}
+
 /*
 /// Run a test network handler which accepts two data sources to draw from.
 /// It only handles Get and GetMeta requests.
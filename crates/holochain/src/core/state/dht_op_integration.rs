@@ -11,6 +11,7 @@ use holochain_state::{
     prelude::{BufferedStore, EnvironmentRead, GetDb, Readable},
 };
 use holochain_types::{dht_op::DhtOpLight, validate::ValidationStatus, Timestamp};
+use holochain_zome_types::entry_def::RequiredValidations;
 
 /// Database type for AuthoredDhtOps
 /// Buffer for accessing [DhtOp]s that you authored and finding the amount of validation receipts
@@ -26,21 +27,33 @@ pub struct AuthoredDhtOpsValue {
     pub op: DhtOpLight,
     /// Validation receipts received
     pub receipt_count: u32,
+    /// Validation receipts needed before the op stops being published
+    #[serde(default = "default_required_receipt_count")]
+    pub required_receipt_count: u32,
     /// Time last published, None if never published
     pub last_publish_time: Option<Timestamp>,
 }
 
 impl AuthoredDhtOpsValue {
-    /// Create a new value from a DhtOpLight with no receipts and no timestamp
+    /// Create a new value from a DhtOpLight with no receipts and no timestamp.
+    /// The default number of receipts is required until the health check
+    /// has looked up the entry def of the op.
     pub fn from_light(op: DhtOpLight) -> Self {
         Self {
             op,
             receipt_count: 0,
+            required_receipt_count: default_required_receipt_count(),
             last_publish_time: None,
         }
     }
 }
 
+/// Ops stored before the required count was recorded need the default
+/// number of receipts.
+fn default_required_receipt_count() -> u32 {
+    u8::from(RequiredValidations::default()) as u32
+}
+
 /// Database type for IntegrationLimbo: the queue of ops ready to be integrated.
 pub type IntegrationLimboStore = KvBufFresh<IntegrationLimboKey, IntegrationLimboValue>;
 
@@ -97,6 +110,10 @@ pub struct IntegrationLimboValue {
     pub validation_status: ValidationStatus,
     /// The op
     pub op: DhtOpLight,
+    /// Send a validation receipt to the author once the op is integrated.
+    /// Ops stored before this was recorded don't send one.
+    #[serde(default)]
+    pub send_receipt: bool,
 }
 
 impl IntegratedDhtOpsBuf {
@@ -77,6 +77,15 @@ impl ElementBuf<RejectedPrefix> {
     }
 }
 
+impl ElementBuf<AbandonedPrefix> {
+    /// Create a element buf for all elements that have been abandoned.
+    /// Only the headers of abandoned elements are kept so there is
+    /// no access to private entries.
+    pub fn abandoned(env: EnvironmentRead) -> DatabaseResult<Self> {
+        ElementBuf::new_vault(env, false)
+    }
+}
+
 impl ElementBuf<AuthoredPrefix> {
     /// Create a element buf for all authored elements.
     /// This reuses the database but is the data is completely separate.
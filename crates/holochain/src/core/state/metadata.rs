@@ -28,6 +28,7 @@ use holochain_zome_types::{
     query::ChainStatus,
     query::HighestObserved,
     validate::ValidationStatus,
+    warrant::SignedWarrant,
 };
 use holochain_zome_types::{link::LinkTag, Header};
 use std::{collections::HashSet, fmt::Debug};
@@ -57,6 +58,34 @@ mod sys_meta;
 #[cfg(test)]
 mod mock;
 
+/// Choose which update to follow next in an update chain.
+///
+/// When the chain forks the oldest update is followed (ordered by timestamp
+/// then hash) so everyone holding the same metadata ends up in the same place.
+/// Updates that lead back to something already `visited` are skipped,
+/// which stops cycles in entry update chains from looping forever.
+/// `updated_to` maps an update to the hash it moves the chain on to,
+/// or `None` if that isn't known.
+pub fn choose_update<T, E, F>(
+    mut updates: Vec<TimedHeaderHash>,
+    visited: &HashSet<T>,
+    mut updated_to: F,
+) -> Result<Option<T>, E>
+where
+    T: Eq + std::hash::Hash,
+    F: FnMut(&TimedHeaderHash) -> Result<Option<T>, E>,
+{
+    updates.sort();
+    for update in updates.iter() {
+        if let Some(next) = updated_to(update)? {
+            if !visited.contains(&next) {
+                return Ok(Some(next));
+            }
+        }
+    }
+    Ok(None)
+}
+
 /// Trait for the [MetadataBuf], needed for mocking
 ///
 /// Unfortunately this cannot be automocked because of the lifetimes required
@@ -152,6 +181,10 @@ where
     /// Useful for knowing if we can serve a header from our element vault
     fn deregister_rejected_element_header(&mut self, header: HeaderHash) -> DatabaseResult<()>;
 
+    /// Registers an abandoned [Header] when a StoreElement is processed.
+    /// Useful for knowing if we can serve a header from our abandoned store
+    fn register_abandoned_element_header(&mut self, header: &Header) -> DatabaseResult<()>;
+
     /// Registers a published [Header] on the authoring agent's public key
     fn register_activity(
         &mut self,
@@ -211,6 +244,12 @@ where
     /// Deregister the highest observed sequence number on an agents chain
     fn deregister_activity_observed(&mut self, agent: &AgentPubKey) -> DatabaseResult<()>;
 
+    /// Registers a [SignedWarrant] on the warrantee's public key
+    fn register_warrant(&mut self, warrant: SignedWarrant);
+
+    /// Deregister a [SignedWarrant] on the warrantee's public key
+    fn deregister_warrant(&mut self, warrant: SignedWarrant);
+
     /// Registers a [Header::Update] on the referenced [Header] or [Entry]
     fn register_update(&mut self, update: header::Update) -> DatabaseResult<()>;
 
@@ -288,6 +327,13 @@ where
     fn get_activity_observed(&self, agent: &AgentPubKey)
         -> DatabaseResult<Option<HighestObserved>>;
 
+    /// Get all the warrants issued against this agent
+    fn get_warrants<'r, R: Readable>(
+        &'r self,
+        r: &'r R,
+        agent: &AgentPubKey,
+    ) -> DatabaseResult<Box<dyn FallibleIterator<Item = SignedWarrant, Error = DatabaseError> + '_>>;
+
     /// Returns all the hashes of [Update] headers registered on an [Entry]
     fn get_updates<'r, R: Readable>(
         &'r self,
@@ -325,10 +371,12 @@ where
         header_hash: &HeaderHash,
     ) -> DatabaseResult<DisputedStatus>;
 
-    /// Finds the redirect path and returns the final [Entry]
+    /// Follows the live [Update]s on an [Entry] and returns the final [Entry].
+    /// See [choose_update] for how forks and cycles are handled.
     fn get_canonical_entry_hash(&self, entry_hash: EntryHash) -> DatabaseResult<EntryHash>;
 
-    /// Finds the redirect path and returns the final [Header]
+    /// Follows the live [Update]s on a [Header] and returns the final [Header].
+    /// See [choose_update] for how forks and cycles are handled.
     fn get_canonical_header_hash(&self, header_hash: HeaderHash) -> DatabaseResult<HeaderHash>;
 
     /// Returns all the link remove headers attached to a link add header
@@ -338,7 +386,7 @@ where
         link_add: HeaderHash,
     ) -> DatabaseResult<Box<dyn FallibleIterator<Item = TimedHeaderHash, Error = DatabaseError> + '_>>;
 
-    /// Finds if there is a valid or rejected StoreElement for this header
+    /// Finds if there is a valid, rejected or abandoned StoreElement for this header
     fn has_any_registered_store_element(&self, hash: &HeaderHash) -> DatabaseResult<bool>;
 
     /// Finds if there is a valid StoreElement for this header
@@ -471,6 +519,43 @@ where
         Ok(())
     }
 
+    /// The updates on a hash which haven't been deleted or found invalid
+    fn get_live_updates<R: Readable>(
+        &self,
+        r: &R,
+        hash: AnyDhtHash,
+    ) -> DatabaseResult<Vec<TimedHeaderHash>> {
+        self.get_updates(r, hash)?
+            .filter(|update| {
+                Ok(self
+                    .get_deletes_on_header(r, update.header_hash.clone())?
+                    .next()?
+                    .is_none()
+                    && self
+                        .get_validation_status(r, &update.header_hash)?
+                        .is_valid())
+            })
+            .collect()
+    }
+
+    /// The [Entry] an [Update] header created
+    fn get_entry_redirect<R: Readable>(
+        &self,
+        r: &R,
+        update: &HeaderHash,
+    ) -> DatabaseResult<Option<EntryHash>> {
+        fallible_iterator::convert(
+            self.system_meta
+                .get(r, &SysMetaKey::from(update.clone()).into())?,
+        )
+        .find_map(|v| {
+            Ok(match v {
+                SysMetaVal::EntryRedirect(entry_hash) => Some(entry_hash),
+                _ => None,
+            })
+        })
+    }
+
     #[instrument(skip(self))]
     fn update_entry_dht_status(&mut self, basis: EntryHash) -> DatabaseResult<()> {
         let status = fresh_reader!(self.env, |r| self.get_headers(&r, basis.clone())?.find_map(
@@ -782,9 +867,21 @@ where
             .delete(MiscMetaKey::rejected_store_element(&hash).into())
     }
 
+    fn register_abandoned_element_header(&mut self, header: &Header) -> DatabaseResult<()> {
+        self.misc_meta.put(
+            MiscMetaKey::abandoned_store_element(&HeaderHash::with_data_sync(header)).into(),
+            MiscMetaValue::new_store_element(),
+        )
+    }
+
     fn register_update(&mut self, update: header::Update) -> DatabaseResult<()> {
         let header_hash = update.original_header_address.clone();
         let entry_hash = update.original_entry_address.clone();
+        let update_hash = HeaderHash::with_data_sync(&Header::Update(update.clone()));
+        self.register_raw_on_header(
+            update_hash,
+            SysMetaVal::EntryRedirect(update.entry_hash.clone()),
+        );
         self.register_header_on_basis(header_hash, update.clone())?;
         self.register_header_on_basis(entry_hash, update)
     }
@@ -792,6 +889,11 @@ where
     fn deregister_update(&mut self, update: header::Update) -> DatabaseResult<()> {
         let header_hash = update.original_header_address.clone();
         let entry_hash = update.original_entry_address.clone();
+        let update_hash = HeaderHash::with_data_sync(&Header::Update(update.clone()));
+        self.deregister_raw_on_header(
+            update_hash,
+            SysMetaVal::EntryRedirect(update.entry_hash.clone()),
+        );
         self.deregister_header_on_basis(header_hash, update.clone())?;
         self.deregister_header_on_basis(entry_hash, update)
     }
@@ -1137,6 +1239,18 @@ where
         ))
     }
 
+    fn register_warrant(&mut self, warrant: SignedWarrant) {
+        let key: SysMetaKey = warrant.warrant.warrantee.clone().into();
+        self.system_meta
+            .insert(key.into(), SysMetaVal::Warrant(warrant));
+    }
+
+    fn deregister_warrant(&mut self, warrant: SignedWarrant) {
+        let key: SysMetaKey = warrant.warrant.warrantee.clone().into();
+        self.system_meta
+            .delete(key.into(), SysMetaVal::Warrant(warrant));
+    }
+
     fn get_activity_status(&self, agent: &AgentPubKey) -> DatabaseResult<Option<ChainStatus>> {
         let key = MiscMetaKey::chain_status(&agent).into();
         Ok(fresh_reader!(self.env, |r| self.misc_meta.get(&r, &key))?
@@ -1152,6 +1266,26 @@ where
             .map(MiscMetaValue::chain_observed))
     }
 
+    fn get_warrants<'r, R: Readable>(
+        &'r self,
+        r: &'r R,
+        agent: &AgentPubKey,
+    ) -> DatabaseResult<Box<dyn FallibleIterator<Item = SignedWarrant, Error = DatabaseError> + '_>>
+    {
+        Ok(Box::new(
+            fallible_iterator::convert(
+                self.system_meta
+                    .get(r, &SysMetaKey::from(agent.clone()).into())?,
+            )
+            .filter_map(|h| {
+                Ok(match h {
+                    SysMetaVal::Warrant(w) => Some(w),
+                    _ => None,
+                })
+            }),
+        ))
+    }
+
     // TODO: For now this is only checking for deletes
     // Once the validation is finished this should check for that as well
     fn get_dht_status<'r, R: Readable>(
@@ -1185,12 +1319,40 @@ where
         .into())
     }
 
-    fn get_canonical_entry_hash(&self, _entry_hash: EntryHash) -> DatabaseResult<EntryHash> {
-        todo!("Cannot implement until redirects are implemented")
+    fn get_canonical_entry_hash(&self, entry_hash: EntryHash) -> DatabaseResult<EntryHash> {
+        fresh_reader!(self.env, |r| {
+            let mut visited = HashSet::new();
+            let mut current = entry_hash;
+            loop {
+                visited.insert(current.clone());
+                let updates = self.get_live_updates(&r, current.clone().into())?;
+                let next = choose_update(updates, &visited, |update| {
+                    self.get_entry_redirect(&r, &update.header_hash)
+                })?;
+                match next {
+                    Some(next) => current = next,
+                    None => return Ok(current),
+                }
+            }
+        })
     }
 
-    fn get_canonical_header_hash(&self, _header_hash: HeaderHash) -> DatabaseResult<HeaderHash> {
-        todo!("Cannot implement until redirects are implemented")
+    fn get_canonical_header_hash(&self, header_hash: HeaderHash) -> DatabaseResult<HeaderHash> {
+        fresh_reader!(self.env, |r| {
+            let mut visited = HashSet::new();
+            let mut current = header_hash;
+            loop {
+                visited.insert(current.clone());
+                let updates = self.get_live_updates(&r, current.clone().into())?;
+                let next = choose_update(updates, &visited, |update| {
+                    Ok(Some(update.header_hash.clone()))
+                })?;
+                match next {
+                    Some(next) => current = next,
+                    None => return Ok(current),
+                }
+            }
+        })
     }
 
     fn get_link_removes_on_link_add<'r, R: Readable>(
@@ -1220,6 +1382,9 @@ where
                 || self
                     .misc_meta
                     .contains(&r, &MiscMetaKey::rejected_store_element(hash).into())?
+                || self
+                    .misc_meta
+                    .contains(&r, &MiscMetaKey::abandoned_store_element(hash).into())?
         ))
     }
 
@@ -78,6 +78,11 @@ pub enum SysMetaVal {
     CustomPackage(HeaderHash),
     /// Validation Status
     ValidationStatus(ValidationStatus),
+    /// The [Entry] an [Update] header created.
+    /// Registered on the [Update] so entry update chains can be followed.
+    EntryRedirect(EntryHash),
+    /// A warrant issued against the agent whose key this is registered on
+    Warrant(SignedWarrant),
 }
 
 // #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -218,6 +223,12 @@ impl From<SysMetaVal> for HeaderHash {
             SysMetaVal::ValidationStatus(_) => {
                 unreachable!("Tried to get the header hash from a validation status")
             }
+            SysMetaVal::EntryRedirect(_) => {
+                unreachable!("Tried to get the header hash from an entry redirect")
+            }
+            SysMetaVal::Warrant(_) => {
+                unreachable!("Tried to get the header hash from a warrant")
+            }
         }
     }
 }
@@ -48,6 +48,12 @@ impl PrefixType for RejectedStoreElementPrefix {
     const PREFIX: u8 = 0x5;
 }
 
+#[derive(PartialOrd, Clone, Ord, PartialEq, Eq, Debug)]
+pub struct AbandonedStoreElementPrefix;
+impl PrefixType for AbandonedStoreElementPrefix {
+    const PREFIX: u8 = 0x6;
+}
+
 impl<P: PrefixType> MiscMetaKey<P> {
     /// Create a new prefix bytes key
     pub fn new<I: IntoIterator<Item = u8>>(bytes: I) -> Self {
@@ -107,6 +113,16 @@ impl MiscMetaKey<RejectedStoreElementPrefix> {
     }
 }
 
+impl MiscMetaKey<AbandonedStoreElementPrefix> {
+    /// Create an abandoned store element key
+    pub fn abandoned_store_element(hash: &HeaderHash) -> MiscMetaKey<AbandonedStoreElementPrefix> {
+        let bytes: SerializedBytes = hash
+            .try_into()
+            .expect("Header Hash can't fail to serialize");
+        MiscMetaKey::new(bytes.bytes().iter().copied())
+    }
+}
+
 impl MiscMetaKey<ChainItemPrefix> {
     /// Create an chain item key
     pub fn chain_item(key: &ChainItemKey) -> MiscMetaKey<ChainItemPrefix> {
@@ -17,6 +17,7 @@ mock! {
         fn register_rejected_header(&mut self, new_entry_header: NewEntryHeader) -> DatabaseResult<()>;
         fn register_element_header(&mut self, header: &Header) -> DatabaseResult<()>;
         fn register_rejected_element_header(&mut self, header: &Header) -> DatabaseResult<()>;
+        fn register_abandoned_element_header(&mut self, header: &Header) -> DatabaseResult<()>;
         fn register_activity(
             &mut self,
             header: &Header,
@@ -42,6 +43,8 @@ mock! {
             observed: HighestObserved,
         ) -> DatabaseResult<()>;
         fn deregister_activity_observed(&mut self, agent: &AgentPubKey) -> DatabaseResult<()>;
+        fn register_warrant(&mut self, warrant: SignedWarrant);
+        fn deregister_warrant(&mut self, warrant: SignedWarrant);
         fn register_update(&mut self, update: header::Update) -> DatabaseResult<()>;
         fn register_delete(&mut self, delete: header::Delete) -> DatabaseResult<()>;
         fn deregister_header(&mut self, new_entry_header: NewEntryHeader) -> DatabaseResult<()>;
@@ -101,6 +104,10 @@ mock! {
         fn get_activity_status(&self, agent: &AgentPubKey) -> DatabaseResult<Option<ChainStatus>>;
         fn get_activity_observed(&self, agent: &AgentPubKey)
         -> DatabaseResult<Option<HighestObserved>>;
+        fn get_warrants(
+            &self,
+            agent: &AgentPubKey,
+        ) -> DatabaseResult<Box<dyn FallibleIterator<Item = SignedWarrant, Error = DatabaseError>>>;
         fn get_updates(
             &self,
             hash: AnyDhtHash,
@@ -230,6 +237,15 @@ impl MetadataBufT for MockMetadataBuf {
         self.get_activity_observed(agent)
     }
 
+    fn get_warrants<'r, R: Readable>(
+        &'r self,
+        _r: &'r R,
+        agent: &AgentPubKey,
+    ) -> DatabaseResult<Box<dyn FallibleIterator<Item = SignedWarrant, Error = DatabaseError> + '_>>
+    {
+        self.get_warrants(agent)
+    }
+
     fn get_updates<'r, R: Readable>(
         &'r self,
         _reader: &'r R,
@@ -308,6 +324,10 @@ impl MetadataBufT for MockMetadataBuf {
         self.deregister_rejected_element_header(hash)
     }
 
+    fn register_abandoned_element_header(&mut self, header: &Header) -> DatabaseResult<()> {
+        self.register_abandoned_element_header(header)
+    }
+
     fn register_activity(
         &mut self,
         header: &Header,
@@ -353,6 +373,12 @@ impl MetadataBufT for MockMetadataBuf {
     fn deregister_activity_observed(&mut self, agent: &AgentPubKey) -> DatabaseResult<()> {
         self.deregister_activity_observed(agent)
     }
+    fn register_warrant(&mut self, warrant: SignedWarrant) {
+        self.register_warrant(warrant)
+    }
+    fn deregister_warrant(&mut self, warrant: SignedWarrant) {
+        self.deregister_warrant(warrant)
+    }
 
     fn register_update(&mut self, update: header::Update) -> DatabaseResult<()> {
         self.register_update(update)
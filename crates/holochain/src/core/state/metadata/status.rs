@@ -23,6 +23,11 @@ impl DisputedStatus {
         }
     }
 
+    /// All the statuses that have been registered
+    pub fn statuses(&self) -> impl Iterator<Item = &ValidationStatus> {
+        self.status.iter()
+    }
+
     /// Resolves the status considering an absence
     /// of status as valid.
     pub fn is_valid(&self) -> bool {
@@ -107,7 +107,6 @@ mod tests {
     }
 
     #[tokio::test(threaded_scheduler)]
-    #[ignore = "can't be tested until redirects are implemented"]
     /// Test that a header can be redirected a single hop
     async fn test_redirect_header_one_hop() -> anyhow::Result<()> {
         let test_env = test_cell_env();
@@ -132,7 +131,6 @@ mod tests {
     }
 
     #[tokio::test(threaded_scheduler)]
-    #[ignore = "can't be tested until redirects are implemented"]
     /// Test that a header can be redirected three hops
     async fn test_redirect_header_three_hops() -> anyhow::Result<()> {
         let test_env = test_cell_env();
@@ -174,7 +172,6 @@ mod tests {
     }
 
     #[tokio::test(threaded_scheduler)]
-    #[ignore = "can't be tested until redirects are implemented"]
     /// Test that an entry can be redirected a single hop
     async fn test_redirect_entry_one_hop() -> anyhow::Result<()> {
         let test_env = test_cell_env();
@@ -207,7 +204,6 @@ mod tests {
     }
 
     #[tokio::test(threaded_scheduler)]
-    #[ignore = "can't be tested until redirects are implemented"]
     /// Test that an entry can be redirected three hops
     async fn test_redirect_entry_three_hops() -> anyhow::Result<()> {
         let test_env = test_cell_env();
@@ -221,24 +217,24 @@ mod tests {
                 .1
                 .into_inner()
                 .1;
-            let (update1, _) = test_update(
+            let (update1, header1) = test_update(
                 header_hash,
                 fx.entry_hash(),
                 original_entry.clone(),
                 &mut fx,
             )
             .await;
-            let (update2, _) = test_update(
-                update1.original_header_address.clone(),
+            let (update2, header2) = test_update(
+                header1.into_hash(),
                 fx.entry_hash(),
-                original_entry.clone(),
+                update1.entry_hash.clone(),
                 &mut fx,
             )
             .await;
             let (update3, _) = test_update(
-                update2.original_header_address.clone(),
+                header2.into_hash(),
                 fx.entry_hash(),
-                original_entry.clone(),
+                update2.entry_hash.clone(),
                 &mut fx,
             )
             .await;
@@ -255,7 +251,6 @@ mod tests {
     }
 
     #[tokio::test(threaded_scheduler)]
-    #[ignore = "can't be tested until redirects are implemented"]
     /// Test that a header can be redirected a single hop
     async fn test_redirect_header_and_entry() -> anyhow::Result<()> {
         let test_env = test_cell_env();
@@ -272,8 +267,7 @@ mod tests {
             let (update_header, expected_header) =
                 test_update(header_hash, fx.entry_hash(), fx.entry_hash(), &mut fx).await;
 
-            let original_entry_1 = fx.entry_hash();
-            let header_hash = test_create(original_entry_1.clone(), &mut fx)
+            let header_hash = test_create(original_entry.clone(), &mut fx)
                 .await
                 .1
                 .into_inner()
@@ -293,7 +287,7 @@ mod tests {
             let original_header_hash = update_header.original_header_address;
             let canonical_header_hash =
                 buf.get_canonical_header_hash(original_header_hash.clone())?;
-            let canonical_entry_hash = buf.get_canonical_entry_hash(original_entry_1)?;
+            let canonical_entry_hash = buf.get_canonical_entry_hash(original_entry)?;
 
             assert_eq!(&canonical_header_hash, expected_header.as_hash());
             assert_eq!(canonical_entry_hash, expected_entry_hash);
@@ -301,6 +295,76 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(threaded_scheduler)]
+    /// Test that forks follow the oldest live update and cycles end the chain
+    async fn test_redirect_entry_fork_and_cycle() -> anyhow::Result<()> {
+        let test_env = test_cell_env();
+        let arc = test_env.env();
+        let mut fx = TestFixtures::new();
+        {
+            let mut buf = MetadataBuf::vault(arc.clone().into())?;
+            let original_entry = fx.entry_hash();
+            let header_hash = test_create(original_entry.clone(), &mut fx)
+                .await
+                .1
+                .into_inner()
+                .1;
+
+            // Two updates fork the entry
+            let (update1, header1) = test_update(
+                header_hash.clone(),
+                fx.entry_hash(),
+                original_entry.clone(),
+                &mut fx,
+            )
+            .await;
+            let (update2, header2) = test_update(
+                header_hash,
+                fx.entry_hash(),
+                original_entry.clone(),
+                &mut fx,
+            )
+            .await;
+            buf.register_update(update1.clone())?;
+            buf.register_update(update2.clone())?;
+            let (oldest, newest) = if TimedHeaderHash::from(header1.clone())
+                < TimedHeaderHash::from(header2.clone())
+            {
+                ((update1, header1), (update2, header2))
+            } else {
+                ((update2, header2), (update1, header1))
+            };
+            assert_eq!(
+                buf.get_canonical_entry_hash(original_entry.clone())?,
+                oldest.0.entry_hash
+            );
+
+            // Deleting the oldest update moves the chain onto the other fork
+            let (delete, _) =
+                test_delete(oldest.1.into_hash(), oldest.0.entry_hash.clone(), &mut fx).await;
+            buf.register_delete(delete)?;
+            assert_eq!(
+                buf.get_canonical_entry_hash(original_entry.clone())?,
+                newest.0.entry_hash
+            );
+
+            // An update back to the original entry is a cycle and isn't followed
+            let (back, _) = test_update(
+                newest.1.into_hash(),
+                original_entry.clone(),
+                newest.0.entry_hash.clone(),
+                &mut fx,
+            )
+            .await;
+            buf.register_update(back)?;
+            assert_eq!(
+                buf.get_canonical_entry_hash(original_entry)?,
+                newest.0.entry_hash
+            );
+        }
+        Ok(())
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn add_entry_get_headers() {
         let test_env = test_cell_env();
@@ -0,0 +1,290 @@
+//! Persisted store of zome functions which have been scheduled to be called
+//! by the conductor at a later time.
+
+use fallible_iterator::FallibleIterator;
+use holochain_serialized_bytes::prelude::*;
+use holochain_state::{
+    buffer::KvBufFresh,
+    db::SCHEDULED_FNS,
+    error::{DatabaseError, DatabaseResult},
+    key::BufKey,
+    prelude::{BufferedStore, EnvironmentRead, GetDb, Readable, Writer},
+};
+use holochain_types::{Timestamp, TimestampKey};
+use holochain_zome_types::schedule::Schedule;
+use holochain_zome_types::zome::{FunctionName, ZomeName};
+use std::time::Duration;
+
+/// The shortest interval a function can repeat at.
+/// Shorter intervals, including zero, are raised to this so a repeating
+/// function can't keep the scheduler permanently busy.
+pub const MIN_SCHEDULE_REPEAT: Duration = Duration::from_secs(1);
+
+/// A zome function waiting to be called by the scheduler
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledFn {
+    /// The zome which scheduled the function
+    pub zome_name: ZomeName,
+    /// The function to call
+    pub fn_name: FunctionName,
+    /// The payload to call the function with
+    pub payload: SerializedBytes,
+    /// When the function is next due to be called
+    pub due: Timestamp,
+    /// The interval to reschedule the function at after every call
+    pub repeat: Option<Duration>,
+}
+
+impl ScheduledFn {
+    /// Create the scheduled function for a [Schedule] requested by a zome at `now`
+    pub fn from_schedule(zome_name: ZomeName, schedule: Schedule, now: Timestamp) -> Self {
+        let Schedule {
+            fn_name,
+            payload,
+            delay,
+            repeat,
+        } = schedule;
+        Self {
+            zome_name,
+            fn_name,
+            payload,
+            due: timestamp_after(now, delay),
+            repeat: repeat.map(|repeat| repeat.max(MIN_SCHEDULE_REPEAT)),
+        }
+    }
+
+    /// The same function due again one interval after `now`,
+    /// or None if this function does not repeat
+    pub fn next(&self, now: Timestamp) -> Option<Self> {
+        self.repeat.map(|repeat| Self {
+            due: timestamp_after(now, repeat),
+            ..self.clone()
+        })
+    }
+}
+
+/// Add a [Duration] to a [Timestamp], saturating at the end of time
+fn timestamp_after(t: Timestamp, d: Duration) -> Timestamp {
+    let t: chrono::DateTime<chrono::Utc> = t.into();
+    chrono::Duration::from_std(d)
+        .ok()
+        .and_then(|d| t.checked_add_signed(d))
+        .map(Timestamp::from)
+        .unwrap_or(Timestamp(i64::MAX, 0))
+}
+
+/// Key for the [ScheduleBuf].
+/// The due time comes first so that iterating the database returns
+/// functions in the order that they are due.
+/// The zome and function names follow, then a hash of the payload and
+/// repeat interval, so that different calls due at the same time do not
+/// overwrite each other. Only identical calls due at the same time share a key.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct ScheduledFnKey(Vec<u8>);
+
+impl From<&ScheduledFn> for ScheduledFnKey {
+    fn from(f: &ScheduledFn) -> Self {
+        let mut bytes = TimestampKey::from(f.due).as_ref().to_vec();
+        bytes.extend_from_slice(f.zome_name.0.as_bytes());
+        // Names can't contain null bytes so this separator is unambiguous
+        bytes.push(0);
+        bytes.extend_from_slice(f.fn_name.0.as_bytes());
+        bytes.push(0);
+        // The repeat is fixed length so it can't run into the payload
+        let mut call = match f.repeat {
+            Some(repeat) => {
+                let mut call = vec![1];
+                call.extend_from_slice(&repeat.as_millis().to_be_bytes());
+                call
+            }
+            None => vec![0],
+        };
+        call.extend_from_slice(f.payload.bytes());
+        bytes.extend(holo_hash::encode::blake2b_128(&call));
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for ScheduledFnKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl BufKey for ScheduledFnKey {
+    fn from_key_bytes_or_friendly_panic(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+}
+
+/// The store of zome functions scheduled to run in a cell
+pub struct ScheduleBuf(KvBufFresh<ScheduledFnKey, ScheduledFn>);
+
+impl ScheduleBuf {
+    /// Create a new buffer for the ScheduledFns database
+    pub fn new(env: EnvironmentRead) -> DatabaseResult<Self> {
+        let db = env.get_db(&*SCHEDULED_FNS)?;
+        Ok(Self(KvBufFresh::new(env, db)))
+    }
+
+    /// Add a function to the schedule
+    pub fn schedule(&mut self, scheduled_fn: ScheduledFn) -> DatabaseResult<()> {
+        self.0.put((&scheduled_fn).into(), scheduled_fn)
+    }
+
+    /// Remove a function from the schedule
+    pub fn unschedule(&mut self, scheduled_fn: &ScheduledFn) -> DatabaseResult<()> {
+        self.0.delete(scheduled_fn.into())
+    }
+
+    /// All the functions which are due to be called at or before `now`,
+    /// in the order they became due
+    pub fn due<R: Readable>(&self, r: &R, now: Timestamp) -> DatabaseResult<Vec<ScheduledFn>> {
+        self.0
+            .iter(r)?
+            .map(|(_, f)| Ok(f))
+            .take_while(|f| Ok(f.due <= now))
+            .collect()
+    }
+
+    /// The time at which the next function becomes due, if anything is scheduled
+    pub fn next_due<R: Readable>(&self, r: &R) -> DatabaseResult<Option<Timestamp>> {
+        Ok(self.0.iter(r)?.next()?.map(|(_, f)| f.due))
+    }
+
+    /// Access the underlying environment
+    pub fn env(&self) -> &EnvironmentRead {
+        self.0.env()
+    }
+}
+
+impl BufferedStore for ScheduleBuf {
+    type Error = DatabaseError;
+
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
+        self.0.flush_to_txn_ref(writer)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use holochain_state::{
+        env::{ReadManager, WriteManager},
+        test_utils::test_cell_env,
+    };
+
+    /// A function in the zome "zome" with an empty payload
+    pub fn scheduled_fn(fn_name: &str, due: Timestamp, repeat: Option<Duration>) -> ScheduledFn {
+        ScheduledFn {
+            zome_name: "zome".into(),
+            fn_name: fn_name.into(),
+            payload: SerializedBytes::try_from(()).unwrap(),
+            due,
+            repeat,
+        }
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn due_fns_are_returned_in_order() -> DatabaseResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let env_ref = env.guard();
+
+        let later = scheduled_fn("later", Timestamp(200, 0), None);
+        let sooner = scheduled_fn("sooner", Timestamp(100, 0), None);
+        let same_time = scheduled_fn("same_time", Timestamp(100, 0), None);
+        let future = scheduled_fn("future", Timestamp(300, 0), None);
+
+        {
+            let mut buf = ScheduleBuf::new(env.clone().into())?;
+            buf.schedule(later.clone())?;
+            buf.schedule(sooner.clone())?;
+            buf.schedule(same_time.clone())?;
+            buf.schedule(future.clone())?;
+            env_ref.with_commit(|writer| buf.flush_to_txn(writer))?;
+        }
+
+        let reader = env_ref.reader()?;
+        let buf = ScheduleBuf::new(env.clone().into())?;
+        assert_eq!(buf.due(&reader, Timestamp(50, 0))?, vec![]);
+        assert_eq!(
+            buf.due(&reader, Timestamp(250, 0))?,
+            vec![same_time, sooner.clone(), later]
+        );
+        assert_eq!(buf.next_due(&reader)?, Some(sooner.due));
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn same_fn_due_at_the_same_time_is_kept_per_call() -> DatabaseResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let env_ref = env.guard();
+
+        let first = scheduled_fn("same_fn", Timestamp(100, 0), None);
+        let other_payload = ScheduledFn {
+            payload: UnsafeBytes::from(vec![1]).into(),
+            ..first.clone()
+        };
+        let other_repeat = ScheduledFn {
+            repeat: Some(Duration::from_secs(10)),
+            ..first.clone()
+        };
+
+        {
+            let mut buf = ScheduleBuf::new(env.clone().into())?;
+            buf.schedule(first.clone())?;
+            buf.schedule(other_payload.clone())?;
+            buf.schedule(other_repeat.clone())?;
+            // Scheduling the same call again doesn't add another
+            buf.schedule(first.clone())?;
+            env_ref.with_commit(|writer| buf.flush_to_txn(writer))?;
+        }
+
+        let reader = env_ref.reader()?;
+        let buf = ScheduleBuf::new(env.clone().into())?;
+        let due = buf.due(&reader, Timestamp(100, 0))?;
+        assert_eq!(due.len(), 3);
+        assert!(due.contains(&first));
+        assert!(due.contains(&other_payload));
+        assert!(due.contains(&other_repeat));
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn repeating_fns_reschedule_from_now() {
+        let once = scheduled_fn("once", Timestamp(100, 0), None);
+        let repeating = scheduled_fn(
+            "repeating",
+            Timestamp(100, 0),
+            Some(Duration::from_secs(10)),
+        );
+
+        assert_eq!(once.next(Timestamp(105, 0)), None);
+        assert_eq!(
+            repeating.next(Timestamp(105, 0)).map(|f| f.due),
+            Some(Timestamp(115, 0))
+        );
+    }
+
+    #[test]
+    fn repeats_are_at_least_the_minimum_interval() {
+        let payload = SerializedBytes::try_from(()).unwrap();
+        let zero = ScheduledFn::from_schedule(
+            "zome".into(),
+            Schedule::repeating(
+                "zero",
+                payload,
+                Duration::from_secs(0),
+                Duration::from_secs(0),
+            ),
+            Timestamp(100, 0),
+        );
+        assert_eq!(zero.repeat, Some(MIN_SCHEDULE_REPEAT));
+        assert_eq!(
+            zero.next(Timestamp(100, 0)).map(|f| f.due),
+            Some(Timestamp(101, 0))
+        );
+    }
+}
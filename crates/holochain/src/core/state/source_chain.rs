@@ -3,18 +3,20 @@
 //! which would return Option in the SourceChainBuf, like getting the source chain head, or the AgentPubKey,
 //! cannot fail, so the function return types reflect that.
 
+use crate::core::state::cap_grant_uses::CapGrantUsesBuf;
 pub use error::*;
 use fallible_iterator::FallibleIterator;
 use holo_hash::*;
 use holochain_state::{buffer::BufferedStore, error::DatabaseResult, fresh_reader, prelude::*};
 use holochain_types::{prelude::*, EntryHashed};
 use holochain_zome_types::{
-    capability::{CapAccess, CapGrant, CapSecret, GrantedFunction},
+    capability::{CapAccess, CapClaim, CapGrant, CapSecret, GrantedFunction, ZomeCallCapGrant},
     element::Element,
     entry::{CapClaimEntry, Entry},
     header::{builder, EntryType, Header, HeaderBuilder, HeaderBuilderCommon, HeaderInner},
     query::ChainQueryFilter,
 };
+use parking_lot::{Mutex, MutexGuard};
 use shrinkwraprs::Shrinkwrap;
 pub use source_chain_buffer::*;
 use std::collections::HashSet;
@@ -26,7 +28,19 @@ mod source_chain_buffer;
 /// i.e. has undergone Genesis.
 #[derive(Shrinkwrap)]
 #[shrinkwrap(mutable)]
-pub struct SourceChain(pub SourceChainBuf);
+pub struct SourceChain(
+    #[shrinkwrap(main_field)] pub SourceChainBuf,
+    Mutex<CapIndex>,
+);
+
+/// The live CapGrants and CapClaims on the chain as of `head`, newest first,
+/// along with the hash of the header which created each one
+#[derive(Default)]
+struct CapIndex {
+    head: Option<HeaderHash>,
+    grants: Vec<(HeaderHash, ZomeCallCapGrant)>,
+    claims: Vec<(HeaderHash, CapClaim)>,
+}
 
 impl SourceChain {
     pub fn agent_pubkey(&self) -> SourceChainResult<AgentPubKey> {
@@ -92,6 +106,9 @@ impl SourceChain {
     /// @todo this is not particularly fast, there are several ways to speed this up in the future
     /// such as indexing secrets and prefixing cap grants in lmdb for direct lookup
     ///
+    /// Grants which have expired, or been used as many times as they allow according to `uses`,
+    /// are not valid.
+    ///
     /// NB: [B-01676] the entry must be persisted for this to work. Once we have a
     /// proper capability index DB, OR a proper iterator that respects the
     /// scratch space, that will no longer be the case.
@@ -100,11 +117,14 @@ impl SourceChain {
         check_function: &GrantedFunction,
         check_agent: &AgentPubKey,
         check_secret: Option<&CapSecret>,
+        uses: &CapGrantUsesBuf,
     ) -> SourceChainResult<Option<CapGrant>> {
+        let now: holochain_zome_types::timestamp::Timestamp = Timestamp::now().into();
+
         // most calls for most apps are going to be the local agent calling itself locally
         // for this case we want to short circuit without iterating the whole source chain
         let author_grant = CapGrant::from(self.agent_pubkey()?);
-        if author_grant.is_valid(check_function, check_agent, check_secret) {
+        if author_grant.is_valid(check_function, check_agent, check_secret, &now, 0) {
             return Ok(Some(author_grant));
         }
 
@@ -187,11 +207,18 @@ impl SourceChain {
             .filter(|entry| {
                 Ok(live_cap_grants.contains(entry.as_hash()))
             })
-            .filter_map(|entry| Ok(entry.as_cap_grant()))
-            // filter down to only the grants for this function
-            .filter(|grant| {
-                Ok(grant.is_valid(check_function, check_agent, check_secret))
+            .filter_map(|entry| Ok(entry.as_cap_grant().map(|grant| (entry.into_hash(), grant))))
+            // filter down to only the grants for this function that haven't expired or been used up
+            .filter(|(entry_hash, grant)| {
+                Ok(grant.is_valid(
+                    check_function,
+                    check_agent,
+                    check_secret,
+                    &now,
+                    uses.uses(&r, entry_hash)?,
+                ))
             })
+            .map(|(_, grant)| Ok(grant))
             // if there are still multiple grants, fold them down based on specificity
             // authorship > assigned > transferable > unrestricted
             .fold(None, |mut acc, grant| {
@@ -241,51 +268,97 @@ impl SourceChain {
         Ok(committed_valid_grant)
     }
 
-    // @todo bring all this back when we want to administer cap claims better
-    //         /// Fetch a CapClaim from the private entries.
-    //         ///
-    //         /// NB: [B-01676] the entry must be persisted for this to work. Once we have a
-    //         /// proper capability index DB, OR a proper iterator that respects the
-    //         /// scratch space, that will no longer be the case.
-    //         pub async fn get_persisted_cap_claim_by_secret(
-    //             &self,
-    //             query: &CapSecret,
-    //         ) -> SourceChainResult<Option<CapClaim>> {
-    //             let hashes_n_claims: Vec<_> = fresh_reader!(self.env(), |r| {
-    //                 self
-    //                 .0
-    //                 .elements()
-    //                 .private_entries()
-    //                 .expect(
-    //                     "SourceChainBuf must have access to private entries in order to access CapClaims",
-    //                 )
-    //                 .iter_fail(&r)?
-    //                 .filter_map(|entry| {
-    //                     if let (Entry::CapClaim(claim), entry_hash) = entry.into_inner() {
-    //                         Ok(Some((entry_hash, claim)))
-    //                     } else {
-    //                         Ok(None)
-    //                     }
-    //                 })
-    //                 .filter(|(_entry_hash, claim)| Ok(claim.secret() == query))
-    //                 .collect()
-    //             })?;
-    //
-    //             let answer = if hashes_n_claims.len() == 0 {
-    //                 None
-    //             } else if hashes_n_claims.len() == 1 {
-    //                 hashes_n_claims.first().map(|p| p.1.clone())
-    //             } else {
-    //                 // FIXME[B-01676]: we SHOULD iterate through the chain now to find the most
-    //                 // recent claim with this secret, in case it was updated.
-    //                 // This will be handled in the future with an index, for simple
-    //                 // lookup by secret
-    //                 todo!("Find proper claim or implement capability index")
-    //             };
-    //             Ok(answer)
-    //         }
-    //     }
-    // }
+    /// List the live CapGrants on the chain, newest first, along with the
+    /// hash of the header which created each one.
+    /// A grant is live if no later header on the chain updates or deletes it.
+    ///
+    /// Unlike [SourceChain::valid_cap_grant] this walks the chain itself, so it
+    /// also sees grants which have not been persisted yet.
+    pub fn live_cap_grants(&self) -> SourceChainResult<Vec<(HeaderHash, ZomeCallCapGrant)>> {
+        Ok(self.cap_index()?.grants.clone())
+    }
+
+    /// List the live CapClaims on the chain, newest first.
+    /// A claim is live if no later header on the chain updates or deletes it.
+    pub fn cap_claims(&self) -> SourceChainResult<Vec<CapClaim>> {
+        Ok(self
+            .cap_index()?
+            .claims
+            .iter()
+            .map(|(_, claim)| claim.clone())
+            .collect())
+    }
+
+    /// Bring the live grants and claims up to date with the chain head.
+    /// Only the headers added since the last time this was called are walked.
+    fn cap_index(&self) -> SourceChainResult<MutexGuard<'_, CapIndex>> {
+        let mut index = self.1.lock();
+        let head = self.0.chain_head();
+        if index.head.as_ref() == head {
+            return Ok(index);
+        }
+
+        // walking backwards means any update or delete is seen before the
+        // header it references
+        let mut references = HashSet::new();
+        let mut grants = Vec::new();
+        let mut claims = Vec::new();
+        let mut reached_index_head = false;
+        let mut iter = self.iter_back();
+        while let Some(shh) = iter.next()? {
+            if index.head.as_ref() == Some(shh.header_address()) {
+                reached_index_head = true;
+                break;
+            }
+            let (entry_type, entry_hash) = match shh.header() {
+                Header::Update(update) => {
+                    references.insert(update.original_header_address.clone());
+                    (&update.entry_type, &update.entry_hash)
+                }
+                Header::Delete(delete) => {
+                    references.insert(delete.deletes_address.clone());
+                    continue;
+                }
+                Header::Create(create) => (&create.entry_type, &create.entry_hash),
+                _ => continue,
+            };
+            match entry_type {
+                EntryType::CapGrant | EntryType::CapClaim => (),
+                _ => continue,
+            }
+            if references.contains(shh.header_address()) {
+                continue;
+            }
+            match self.0.get_entry(entry_hash)?.map(|e| e.into_content()) {
+                Some(Entry::CapGrant(grant)) => grants.push((shh.header_address().clone(), grant)),
+                Some(Entry::CapClaim(claim)) => claims.push((shh.header_address().clone(), claim)),
+                _ => (),
+            }
+        }
+
+        // the older grants and claims are still live unless one of the new
+        // headers updates or deletes them
+        if reached_index_head {
+            grants.extend(
+                index
+                    .grants
+                    .drain(..)
+                    .filter(|(header_hash, _)| !references.contains(header_hash)),
+            );
+            claims.extend(
+                index
+                    .claims
+                    .drain(..)
+                    .filter(|(header_hash, _)| !references.contains(header_hash)),
+            );
+        }
+        *index = CapIndex {
+            head: head.cloned(),
+            grants,
+            claims,
+        };
+        Ok(index)
+    }
 
     /// Query Headers in the source chain.
     /// This returns a Vec rather than an iterator because it is intended to be
@@ -307,7 +380,7 @@ impl SourceChain {
 
 impl From<SourceChainBuf> for SourceChain {
     fn from(buffer: SourceChainBuf) -> Self {
-        Self(buffer)
+        Self(buffer, Mutex::new(CapIndex::default()))
     }
 }
 
@@ -329,7 +402,7 @@ pub mod tests {
     use hdk3::prelude::*;
     use holochain_state::test_utils::test_cell_env;
     use holochain_types::test_utils::fake_dna_hash;
-    use holochain_zome_types::capability::{CapAccess, ZomeCallCapGrant};
+    use holochain_zome_types::capability::{CapAccess, CapLimits, ZomeCallCapGrant};
     use std::collections::HashSet;
 
     #[tokio::test(threaded_scheduler)]
@@ -339,15 +412,21 @@ pub mod tests {
         let secret = Some(CapSecretFixturator::new(Unpredictable).next().unwrap());
         let access = CapAccess::from(secret.unwrap());
 
-        // @todo curry
-        let _curry = CurryPayloadsFixturator::new(Empty).next().unwrap();
+        let curry = CurryPayloadsFixturator::new(Empty).next().unwrap();
         let function: GrantedFunction = ("foo".into(), "bar".into());
         let mut functions: GrantedFunctions = HashSet::new();
         functions.insert(function.clone());
-        let grant = ZomeCallCapGrant::new("tag".into(), access.clone(), functions.clone());
+        let grant = ZomeCallCapGrant::new(
+            "tag".into(),
+            access.clone(),
+            functions.clone(),
+            curry.clone(),
+            CapLimits::default(),
+        );
         let mut agents = AgentPubKeyFixturator::new(Predictable);
         let alice = agents.next().unwrap();
         let bob = agents.next().unwrap();
+        let uses = CapGrantUsesBuf::new(env.clone().into())?;
         {
             let mut store = SourceChainBuf::new(env.clone().into())?;
             store.genesis(fake_dna_hash(1), alice.clone(), None).await?;
@@ -358,13 +437,13 @@ pub mod tests {
         {
             let chain = SourceChain::new(env.clone().into())?;
             assert_eq!(
-                chain.valid_cap_grant(&function, &alice, secret.as_ref())?,
+                chain.valid_cap_grant(&function, &alice, secret.as_ref(), &uses)?,
                 Some(CapGrant::ChainAuthor(alice.clone())),
             );
 
             // bob should not match anything as the secret hasn't been committed yet
             assert_eq!(
-                chain.valid_cap_grant(&function, &bob, secret.as_ref())?,
+                chain.valid_cap_grant(&function, &bob, secret.as_ref(), &uses)?,
                 None
             );
         }
@@ -390,16 +469,21 @@ pub mod tests {
             // alice should find her own authorship with higher priority than the committed grant
             // even if she passes in the secret
             assert_eq!(
-                chain.valid_cap_grant(&function, &alice, secret.as_ref())?,
+                chain.valid_cap_grant(&function, &alice, secret.as_ref(), &uses)?,
                 Some(CapGrant::ChainAuthor(alice.clone())),
             );
 
             // bob should be granted with the committed grant as it matches the secret he passes to
             // alice at runtime
             assert_eq!(
-                chain.valid_cap_grant(&function, &bob, secret.as_ref())?,
+                chain.valid_cap_grant(&function, &bob, secret.as_ref(), &uses)?,
                 Some(grant.clone().into())
             );
+
+            assert_eq!(
+                chain.live_cap_grants()?,
+                vec![(original_header_address.clone(), grant.clone())]
+            );
         }
 
         // let's roll the secret and assign the grant to bob specifically
@@ -407,7 +491,13 @@ pub mod tests {
         assignees.insert(bob.clone());
         let updated_secret = Some(CapSecretFixturator::new(Unpredictable).next().unwrap());
         let updated_access = CapAccess::from((updated_secret.clone().unwrap(), assignees));
-        let updated_grant = ZomeCallCapGrant::new("tag".into(), updated_access.clone(), functions);
+        let updated_grant = ZomeCallCapGrant::new(
+            "tag".into(),
+            updated_access.clone(),
+            functions,
+            curry,
+            CapLimits::default(),
+        );
 
         let (updated_header_hash, updated_entry_hash) = {
             let mut chain = SourceChain::new(env.clone().into())?;
@@ -432,22 +522,28 @@ pub mod tests {
             // alice should find her own authorship with higher priority than the committed grant
             // even if she passes in the secret
             assert_eq!(
-                chain.valid_cap_grant(&function, &alice, secret.as_ref())?,
+                chain.valid_cap_grant(&function, &alice, secret.as_ref(), &uses)?,
                 Some(CapGrant::ChainAuthor(alice.clone())),
             );
             assert_eq!(
-                chain.valid_cap_grant(&function, &alice, updated_secret.as_ref())?,
+                chain.valid_cap_grant(&function, &alice, updated_secret.as_ref(), &uses)?,
                 Some(CapGrant::ChainAuthor(alice.clone())),
             );
 
             // bob MUST provide the updated secret as the old one is invalidated by the new one
             assert_eq!(
-                chain.valid_cap_grant(&function, &bob, secret.as_ref())?,
+                chain.valid_cap_grant(&function, &bob, secret.as_ref(), &uses)?,
                 None
             );
             assert_eq!(
-                chain.valid_cap_grant(&function, &bob, updated_secret.as_ref())?,
-                Some(updated_grant.into())
+                chain.valid_cap_grant(&function, &bob, updated_secret.as_ref(), &uses)?,
+                Some(updated_grant.clone().into())
+            );
+
+            // only the update is live
+            assert_eq!(
+                chain.live_cap_grants()?,
+                vec![(updated_header_hash.clone(), updated_grant)]
             );
         }
 
@@ -467,69 +563,177 @@ pub mod tests {
             let chain = SourceChain::new(env.clone().into())?;
             // alice should find her own authorship
             assert_eq!(
-                chain.valid_cap_grant(&function, &alice, secret.as_ref())?,
+                chain.valid_cap_grant(&function, &alice, secret.as_ref(), &uses)?,
                 Some(CapGrant::ChainAuthor(alice.clone())),
             );
             assert_eq!(
-                chain.valid_cap_grant(&function, &alice, updated_secret.as_ref())?,
+                chain.valid_cap_grant(&function, &alice, updated_secret.as_ref(), &uses)?,
                 Some(CapGrant::ChainAuthor(alice)),
             );
 
             // bob has no access
             assert_eq!(
-                chain.valid_cap_grant(&function, &bob, secret.as_ref())?,
+                chain.valid_cap_grant(&function, &bob, secret.as_ref(), &uses)?,
                 None
             );
             assert_eq!(
-                chain.valid_cap_grant(&function, &bob, updated_secret.as_ref())?,
+                chain.valid_cap_grant(&function, &bob, updated_secret.as_ref(), &uses)?,
                 None
             );
+
+            // and there are no live grants left
+            assert_eq!(chain.live_cap_grants()?, vec![]);
         }
 
         Ok(())
     }
 
-    // @todo bring all this back when we want to administer cap claims better
-    // #[tokio::test(threaded_scheduler)]
-    // async fn test_get_cap_claim() -> SourceChainResult<()> {
-    //     let test_env = test_cell_env();
-    //     let env = test_env.env();
-    //     let env = env.guard().await;
-    //     let secret = CapSecretFixturator::new(Unpredictable).next().unwrap();
-    //     let agent_pubkey = fake_agent_pubkey_1().into();
-    //     let claim = CapClaim::new("tag".into(), agent_pubkey, secret.clone());
-    //     {
-    //         let mut store = SourceChainBuf::new(env.clone().into(), &env).await?;
-    //         store
-    //             .genesis(fake_dna_hash(1), fake_agent_pubkey_1(), None)
-    //             .await?;
-    //         env.with_commit(|writer| store.flush_to_txn(writer))?;
-    //     }
-    //
-    //     {
-    //         let mut chain = SourceChain::new(env.clone().into(), &env).await?;
-    //         chain.put_cap_claim(claim.clone()).await?;
-    //
-    // // ideally the following would work, but it won't because currently
-    // // we can't get claims from the scratch space
-    // // this will be fixed once we add the capability index
-    //
-    // // assert_eq!(
-    // //     chain.get_persisted_cap_claim_by_secret(&secret)?,
-    // //     Some(claim.clone())
-    // // );
-    //
-    //         env.with_commit(|writer| chain.flush_to_txn(writer))?;
-    //     }
-    //
-    //     {
-    //         let chain = SourceChain::new(env.clone().into(), &env).await?;
-    //         assert_eq!(
-    //             chain.get_persisted_cap_claim_by_secret(&secret).await?,
-    //             Some(claim)
-    //         );
-    //     }
-    //
-    //     Ok(())
-    // }
+    #[tokio::test(threaded_scheduler)]
+    async fn test_get_limited_cap_grant() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let function: GrantedFunction = ("foo".into(), "bar".into());
+        let mut functions: GrantedFunctions = HashSet::new();
+        functions.insert(function.clone());
+        let mut agents = AgentPubKeyFixturator::new(Predictable);
+        let alice = agents.next().unwrap();
+        let bob = agents.next().unwrap();
+        let mut secrets = CapSecretFixturator::new(Unpredictable);
+        let once_secret = secrets.next().unwrap();
+        let expired_secret = secrets.next().unwrap();
+        let once_grant = ZomeCallCapGrant::new(
+            "once".into(),
+            once_secret.into(),
+            functions.clone(),
+            CurryPayloads::default(),
+            CapLimits::max_uses(1),
+        );
+        let expired_grant = ZomeCallCapGrant::new(
+            "expired".into(),
+            expired_secret.into(),
+            functions,
+            CurryPayloads::default(),
+            CapLimits::expires_at(Timestamp(0, 0).into()),
+        );
+        let uses = CapGrantUsesBuf::new(env.clone().into())?;
+
+        let once_entry_hash = {
+            let mut store = SourceChainBuf::new(env.clone().into())?;
+            store.genesis(fake_dna_hash(1), alice.clone(), None).await?;
+            let mut chain = SourceChain::from(store);
+            let (once_entry, once_entry_hash) =
+                EntryHashed::from_content_sync(Entry::CapGrant(once_grant.clone())).into_inner();
+            let (expired_entry, expired_entry_hash) =
+                EntryHashed::from_content_sync(Entry::CapGrant(expired_grant)).into_inner();
+            chain
+                .put(
+                    builder::Create {
+                        entry_type: EntryType::CapGrant,
+                        entry_hash: once_entry_hash.clone(),
+                    },
+                    Some(once_entry),
+                )
+                .await?;
+            chain
+                .put(
+                    builder::Create {
+                        entry_type: EntryType::CapGrant,
+                        entry_hash: expired_entry_hash,
+                    },
+                    Some(expired_entry),
+                )
+                .await?;
+            env.guard()
+                .with_commit(|writer| chain.flush_to_txn(writer))?;
+            once_entry_hash
+        };
+
+        let chain = SourceChain::new(env.clone().into())?;
+        // an expired grant is never valid
+        assert_eq!(
+            chain.valid_cap_grant(&function, &bob, Some(&expired_secret), &uses)?,
+            None
+        );
+        // a grant with uses left is valid
+        assert_eq!(
+            chain.valid_cap_grant(&function, &bob, Some(&once_secret), &uses)?,
+            Some(once_grant.into())
+        );
+        // until it has been used up
+        assert!(CapGrantUsesBuf::use_grant(&env, &once_entry_hash, 1)?);
+        assert_eq!(
+            chain.valid_cap_grant(&function, &bob, Some(&once_secret), &uses)?,
+            None
+        );
+        // the author is never limited
+        assert_eq!(
+            chain.valid_cap_grant(&function, &alice, Some(&once_secret), &uses)?,
+            Some(CapGrant::ChainAuthor(alice)),
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_get_cap_claims() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let secret = CapSecretFixturator::new(Unpredictable).next().unwrap();
+        let mut agents = AgentPubKeyFixturator::new(Predictable);
+        let alice = agents.next().unwrap();
+        let bob = agents.next().unwrap();
+        let claim = CapClaim::new("tag".into(), bob.clone(), secret);
+        let deleted_claim = CapClaim::new(
+            "deleted".into(),
+            bob,
+            CapSecretFixturator::new(Unpredictable).next().unwrap(),
+        );
+        {
+            let mut store = SourceChainBuf::new(env.clone().into())?;
+            store.genesis(fake_dna_hash(1), alice, None).await?;
+            env.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+
+        {
+            let mut chain = SourceChain::new(env.clone().into())?;
+            assert_eq!(chain.cap_claims()?, vec![]);
+            chain.put_cap_claim(claim.clone()).await?;
+
+            // claims in the scratch space are found
+            assert_eq!(chain.cap_claims()?, vec![claim.clone()]);
+
+            env.guard()
+                .with_commit(|writer| chain.flush_to_txn(writer))?;
+        }
+
+        {
+            let mut chain = SourceChain::new(env.clone().into())?;
+            assert_eq!(chain.cap_claims()?, vec![claim.clone()]);
+
+            let deleted_header_hash = chain.put_cap_claim(deleted_claim.clone()).await?;
+            assert_eq!(
+                chain.cap_claims()?,
+                vec![deleted_claim.clone(), claim.clone()]
+            );
+
+            // deleted claims are no longer listed
+            let header_builder = builder::Delete {
+                deletes_address: deleted_header_hash,
+                deletes_entry_address: EntryHash::with_data_sync(&Entry::CapClaim(deleted_claim)),
+            };
+            chain.put(header_builder, None).await?;
+            assert_eq!(chain.cap_claims()?, vec![claim.clone()]);
+
+            env.guard()
+                .with_commit(|writer| chain.flush_to_txn(writer))?;
+        }
+
+        {
+            let chain = SourceChain::new(env.clone().into())?;
+            assert_eq!(chain.cap_claims()?, vec![claim]);
+        }
+
+        Ok(())
+    }
 }
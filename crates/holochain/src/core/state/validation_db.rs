@@ -19,6 +19,16 @@ pub struct ValidationLimboStore(pub KvBufFresh<ValidationLimboKey, ValidationLim
 /// Key to the validation limbo
 pub type ValidationLimboKey = DhtOpHash;
 
+/// The number of times we will try to validate an op that is
+/// waiting on dependencies before it is abandoned.
+pub const MAX_VALIDATION_TRIES: u32 = 20;
+
+/// How long an op must have been waiting on dependencies before it
+/// can be abandoned, no matter how many times it has been tried.
+/// Retries can come round quickly so the tries alone don't give
+/// a dependency much time to arrive through gossip.
+pub const MIN_ABANDON_AGE_SECS: i64 = 60 * 60;
+
 /// A type for storing in databases that only need the hashes.
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct ValidationLimboValue {
@@ -36,6 +46,10 @@ pub struct ValidationLimboValue {
     pub num_tries: u32,
     /// The agent that sent you this op
     pub from_agent: Option<AgentPubKey>,
+    /// Send a validation receipt to the author once the op is integrated.
+    /// Ops stored before this was recorded don't send one.
+    #[serde(default)]
+    pub send_receipt: bool,
 }
 
 /// The status of a [DhtOp] in limbo
@@ -51,6 +65,15 @@ pub enum ValidationLimboStatus {
     AwaitingAppDeps(Vec<AnyDhtHash>),
 }
 
+impl ValidationLimboValue {
+    /// Has this op been tried enough times and for long enough
+    /// that we should stop waiting for its dependencies and abandon it
+    pub fn should_abandon(&self) -> bool {
+        self.num_tries >= MAX_VALIDATION_TRIES
+            && Timestamp::now().0 - self.time_added.0 >= MIN_ABANDON_AGE_SECS
+    }
+}
+
 impl ValidationLimboStore {
     /// Create a new Validation Limbo db
     pub fn new(env: EnvironmentRead) -> DatabaseResult<Self> {
@@ -58,3 +81,36 @@ impl ValidationLimboStore {
         Ok(Self(KvBufFresh::new(env, db)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixt::AnyDhtHashFixturator;
+    use ::fixt::prelude::*;
+    use chrono::{Duration, Utc};
+    use holo_hash::fixt::HeaderHashFixturator;
+
+    #[test]
+    fn abandon_needs_tries_and_age() {
+        let mut vlv = ValidationLimboValue {
+            status: ValidationLimboStatus::Pending,
+            op: DhtOpLight::RegisterAgentActivity(fixt!(HeaderHash), fixt!(AnyDhtHash)),
+            basis: fixt!(AnyDhtHash),
+            time_added: Timestamp::now(),
+            last_try: None,
+            num_tries: MAX_VALIDATION_TRIES,
+            from_agent: None,
+            send_receipt: false,
+        };
+        // Tried enough but too recently added
+        assert!(!vlv.should_abandon());
+
+        // Old enough but not tried enough
+        vlv.time_added = (Utc::now() - Duration::seconds(MIN_ABANDON_AGE_SECS)).into();
+        vlv.num_tries = MAX_VALIDATION_TRIES - 1;
+        assert!(!vlv.should_abandon());
+
+        vlv.num_tries = MAX_VALIDATION_TRIES;
+        assert!(vlv.should_abandon());
+    }
+}
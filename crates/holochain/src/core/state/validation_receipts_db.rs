@@ -20,6 +20,8 @@ use holochain_zome_types::signature::Signature;
 pub enum ValidationResult {
     /// Successful validation.
     Valid,
+    /// The op was found to be invalid.
+    Rejected,
     // TODO - fill out with additional options, which may (or may not) have content
     // Abandoned { .. },
     // Warrant { .. },
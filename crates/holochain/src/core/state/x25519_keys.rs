@@ -0,0 +1,105 @@
+//! Persisted record of the x25519 keypairs a cell's agent has created.
+//!
+//! The keystore holds the keys of every agent in the conductor, so this is
+//! how a cell knows which keys it may encrypt and decrypt with.
+
+use holochain_state::{
+    buffer::KvBufFresh,
+    db::X25519_KEYS,
+    error::{DatabaseError, DatabaseResult},
+    key::BufKey,
+    prelude::{BufferedStore, EnvironmentRead, GetDb, Writer},
+};
+use holochain_zome_types::x_salsa20_poly1305::{X25519PubKey, X25519_PUB_KEY_BYTES};
+
+/// Key for the [X25519KeysBuf]: the bytes of the public key
+#[derive(Debug, Clone, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct X25519KeysKey([u8; X25519_PUB_KEY_BYTES]);
+
+impl From<&X25519PubKey> for X25519KeysKey {
+    fn from(key: &X25519PubKey) -> Self {
+        let mut bytes = [0; X25519_PUB_KEY_BYTES];
+        bytes.copy_from_slice(key.as_ref());
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for X25519KeysKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl BufKey for X25519KeysKey {
+    fn from_key_bytes_or_friendly_panic(bytes: &[u8]) -> Self {
+        assert_eq!(
+            bytes.len(),
+            X25519_PUB_KEY_BYTES,
+            "X25519KeysKey needs to be {} bytes long, found {} bytes",
+            X25519_PUB_KEY_BYTES,
+            bytes.len()
+        );
+        let mut inner = [0; X25519_PUB_KEY_BYTES];
+        inner.copy_from_slice(bytes);
+        Self(inner)
+    }
+}
+
+/// The store of x25519 public keys belonging to a cell's agent
+pub struct X25519KeysBuf(KvBufFresh<X25519KeysKey, ()>);
+
+impl X25519KeysBuf {
+    /// Create a new buffer for the X25519Keys database
+    pub fn new(env: EnvironmentRead) -> DatabaseResult<Self> {
+        let db = env.get_db(&*X25519_KEYS)?;
+        Ok(Self(KvBufFresh::new(env, db)))
+    }
+
+    /// Record a key as belonging to the cell's agent
+    pub fn add(&mut self, key: &X25519PubKey) -> DatabaseResult<()> {
+        self.0.put(key.into(), ())
+    }
+
+    /// Does the key belong to the cell's agent?
+    pub fn contains(&self, key: &X25519PubKey) -> DatabaseResult<bool> {
+        self.0.contains(&key.into())
+    }
+
+    /// Access the underlying environment
+    pub fn env(&self) -> &EnvironmentRead {
+        self.0.env()
+    }
+}
+
+impl BufferedStore for X25519KeysBuf {
+    type Error = DatabaseError;
+
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
+        self.0.flush_to_txn_ref(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_state::{env::WriteManager, test_utils::test_cell_env};
+
+    #[tokio::test(threaded_scheduler)]
+    async fn keys_are_recorded_across_flushes() -> DatabaseResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let key = X25519PubKey::from([1; X25519_PUB_KEY_BYTES]);
+        let other = X25519PubKey::from([2; X25519_PUB_KEY_BYTES]);
+
+        {
+            let mut buf = X25519KeysBuf::new(env.clone().into())?;
+            buf.add(&key)?;
+            env.guard().with_commit(|writer| buf.flush_to_txn(writer))?;
+        }
+
+        let buf = X25519KeysBuf::new(env.clone().into())?;
+        assert!(buf.contains(&key)?);
+        assert!(!buf.contains(&other)?);
+        Ok(())
+    }
+}
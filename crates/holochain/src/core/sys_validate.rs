@@ -12,9 +12,9 @@ use fallible_iterator::FallibleIterator;
 use holochain_keystore::AgentPubKeyExt;
 use holochain_p2p::HolochainP2pCell;
 use holochain_state::{env::EnvironmentWrite, error::DatabaseResult, fresh_reader};
-use holochain_types::{dht_op::DhtOp, header::NewEntryHeaderRef, Entry};
+use holochain_types::{dht_op::DhtOp, dna::PublishRateLimits, header::NewEntryHeaderRef, Entry};
 use holochain_zome_types::{
-    element::ElementEntry, signature::Signature, validate::ValidationStatus,
+    element::ElementEntry, query::ChainFork, signature::Signature, validate::ValidationStatus,
 };
 use holochain_zome_types::{
     entry_def::{EntryDef, EntryVisibility},
@@ -47,6 +47,10 @@ pub const MAX_ENTRY_SIZE: usize = 16_000_000;
 /// fast lookup so they need to be small.
 pub const MAX_TAG_SIZE: usize = 400;
 
+/// 1 minute of clock drift is allowed between an author
+/// and the authorities checking their publish rate.
+pub const MAX_CLOCK_DRIFT_MS: i64 = 60_000;
+
 /// Verify the signature for this header
 pub async fn verify_header_signature(
     sig: &Signature,
@@ -105,14 +109,13 @@ pub async fn check_valid_if_dna(
     })
 }
 
-// TODO: I think this can be removed now as rollbacks are detected when inserting
-// metadata into the metadata buf.
-/// Check if there are other headers at this
-/// sequence number
+/// Check if there are other valid or pending headers
+/// from this author at this sequence number.
+/// Returns the fork if one is found.
 pub async fn check_chain_rollback(
     header: &Header,
     workspace: &SysValidationWorkspace,
-) -> SysValidationResult<()> {
+) -> SysValidationResult<Option<ChainFork>> {
     let header_hash = HeaderHash::with_data_sync(header);
     let k = ChainItemKey::AgentStatusSequence(
         header.author().clone(),
@@ -122,43 +125,142 @@ pub async fn check_chain_rollback(
     let env = workspace.meta_vault.env();
     // Check there are no conflicting chain items
     // at any valid or potentially valid stores.
-    let count = fresh_reader!(env, |r| {
-        let vault_count = workspace
+    let others = fresh_reader!(env, |r| {
+        let mut others = workspace
             .meta_vault
             .get_activity(&r, k.clone())?
             .filter(|thh| Ok(thh.header_hash != header_hash))
-            .count()?;
-        let pending_count = workspace
-            .meta_pending
-            .get_activity(&r, k.clone())?
-            .filter(|thh| Ok(thh.header_hash != header_hash))
-            .count()?;
-        DatabaseResult::Ok(vault_count + pending_count)
+            .collect::<Vec<_>>()?;
+        others.extend(
+            workspace
+                .meta_pending
+                .get_activity(&r, k.clone())?
+                .filter(|thh| Ok(thh.header_hash != header_hash))
+                .collect::<Vec<_>>()?,
+        );
+        DatabaseResult::Ok(others)
     })?;
 
-    // Ok or log warning
-    if count == 0 {
+    // The earliest header we saw at this position is the first side of the fork
+    match others.into_iter().min() {
+        None => Ok(None),
+        Some(first) => {
+            tracing::warn!(
+                "Chain fork detected at position {} for agent {:?} between headers {:?} and {:?}",
+                header.header_seq(),
+                header.author(),
+                first.header_hash,
+                header_hash,
+            );
+            Ok(Some(ChainFork {
+                fork_seq: header.header_seq(),
+                first_header: first.header_hash,
+                second_header: header_hash,
+            }))
+        }
+    }
+}
+
+/// Check this header doesn't take its author over the DNA's publish rate limits.
+/// `size` is the publish size of this header's entry and `others` are the
+/// timestamps and entry sizes of the author's other headers we know about.
+/// Only the headers within the window ending at this header are counted.
+pub fn check_spam(
+    header: &Header,
+    size: usize,
+    others: impl IntoIterator<Item = (Timestamp, usize)>,
+    rate_limits: &PublishRateLimits,
+) -> SysValidationResult<()> {
+    if !rate_limits.is_limited() {
         return Ok(());
+    }
+    let end: Timestamp = header.timestamp().into();
+    let start = rate_limits.window_start(end);
+    let (count, bytes) = others
+        .into_iter()
+        .filter(|(timestamp, _)| *timestamp > start && *timestamp <= end)
+        .fold((1, size as u64), |(count, bytes), (_, size)| {
+            (count + 1, bytes + size as u64)
+        });
+    let too_many_headers = rate_limits.max_headers.map_or(false, |max| count > max);
+    let too_many_bytes = rate_limits.max_bytes.map_or(false, |max| bytes > max);
+    if too_many_headers || too_many_bytes {
+        Err(ValidationOutcome::RateLimited(header.clone(), rate_limits.clone()).into())
     } else {
-        let s = tracing::warn_span!("agent_activity");
-        let _g = s.enter();
-        // TODO: implement real rollback detection once we know what that looks like
-        tracing::error!(
-            "Chain rollback detected at position {} for agent {:?} from header {:?}
-            There were {} headers at this position",
-            header.header_seq(),
-            header.author(),
-            header,
-            count,
-        );
+        Ok(())
     }
-    Ok(())
 }
 
-/// Placeholder for future spam check.
-/// Check header timestamps don't exceed MAX_PUBLISH_FREQUENCY
-pub async fn check_spam(_header: &Header) -> SysValidationResult<()> {
-    Ok(())
+/// Check this header isn't dated in the future, allowing for clock drift.
+/// The publish rate limits are windowed on header timestamps so a
+/// future dated header could be used to dodge them.
+/// Old headers are fine because they still reach authorities by gossip.
+/// This depends on the local clock so ops that fail it should be dropped,
+/// not rejected, so they can be accepted when they arrive again later.
+pub fn check_publish_time(
+    header: &Header,
+    rate_limits: &PublishRateLimits,
+    now: Timestamp,
+) -> SysValidationResult<()> {
+    if !rate_limits.is_limited() {
+        return Ok(());
+    }
+    let timestamp: Timestamp = header.timestamp().into();
+    let drift = chrono::Duration::milliseconds(MAX_CLOCK_DRIFT_MS);
+    let latest: Timestamp = (chrono::DateTime::<chrono::Utc>::from(now) + drift).into();
+    if timestamp > latest {
+        Err(ValidationOutcome::PublishTimeOutOfRange(header.clone()).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check this header doesn't take its author's chain over the
+/// DNA's header rate limit.
+/// Every header in the window before this one is found by following
+/// the previous header links back through the author's activity we hold,
+/// so the outcome only depends on the chain and not on what else we've seen.
+/// Entries aren't held with the activity so `max_bytes` isn't checked here.
+pub fn check_activity_rate(
+    header: &Header,
+    rate_limits: &PublishRateLimits,
+    workspace: &SysValidationWorkspace,
+) -> SysValidationResult<()> {
+    if rate_limits.max_headers.is_none() {
+        return Ok(());
+    }
+    let rate_limits = PublishRateLimits {
+        max_bytes: None,
+        ..rate_limits.clone()
+    };
+    let start = rate_limits.window_start(header.timestamp().into());
+    let mut others = Vec::new();
+    let mut prev_header = header.prev_header().cloned();
+    while let Some(hash) = prev_header {
+        let prev = match workspace.element_vault.get_header(&hash)? {
+            Some(prev) => prev,
+            None => workspace
+                .element_pending
+                .get_header(&hash)?
+                .ok_or_else(|| ValidationOutcome::NotHoldingDep(hash.into()))?,
+        };
+        let timestamp: Timestamp = prev.header().timestamp().into();
+        if timestamp <= start {
+            break;
+        }
+        others.push((timestamp, 0));
+        prev_header = prev.header().prev_header().cloned();
+    }
+    check_spam(header, 0, others, &rate_limits)
+}
+
+/// The number of bytes an entry counts towards the publish rate limits.
+/// Only app entries are counted, the other entry types are small.
+pub fn entry_publish_size(entry: Option<&Entry>) -> usize {
+    match entry {
+        Some(Entry::App(bytes)) => bytes.bytes().len(),
+        _ => 0,
+    }
 }
 
 /// Check previous header timestamp is before this header
@@ -467,9 +569,18 @@ impl IncomingDhtOpSender {
     ) -> SysValidationResult<()> {
         if let Some(op) = make_op(element) {
             let ops = vec![op];
-            incoming_dht_ops_workflow(&self.env, self.sys_validation_trigger, ops, None)
-                .await
-                .map_err(Box::new)?;
+            // We went looking for this op so it isn't counted
+            // towards its author's publish rate limits
+            incoming_dht_ops_workflow(
+                &self.env,
+                self.sys_validation_trigger,
+                ops,
+                None,
+                false,
+                None,
+            )
+            .await
+            .map_err(Box::new)?;
         }
         Ok(())
     }
@@ -9,7 +9,7 @@ use crate::{
 use holo_hash::{AnyDhtHash, HeaderHash};
 use holochain_keystore::KeystoreError;
 use holochain_state::error::DatabaseError;
-use holochain_types::cell::CellId;
+use holochain_types::{cell::CellId, dna::PublishRateLimits};
 use holochain_zome_types::signature::Signature;
 use holochain_zome_types::{
     header::{AppEntryType, EntryType},
@@ -112,6 +112,10 @@ pub enum ValidationOutcome {
     PrevHeaderError(#[from] PrevHeaderError),
     #[error("StoreEntry should not be gossiped for private entries")]
     PrivateEntry,
+    #[error("The header {0:?} is dated too far from now to check its publish rate")]
+    PublishTimeOutOfRange(Header),
+    #[error("The header {0:?} takes its author over the publish rate limits {1:?}")]
+    RateLimited(Header, PublishRateLimits),
     #[error("Update original EntryType: {0:?} doesn't match new EntryType {1:?}")]
     UpdateTypeMismatch(EntryType, EntryType),
     #[error("Signature {0:?} failed to verify for Header {1:?}")]
@@ -1,4 +1,5 @@
 use super::*;
+use crate::core::state::workspace::Workspace;
 use crate::{conductor::api::MockCellConductorApi, meta_mock};
 use ::fixt::prelude::*;
 use error::SysValidationError;
@@ -7,14 +8,14 @@ use holochain_keystore::AgentPubKeyExt;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_state::{env::EnvironmentRead, test_utils::test_cell_env};
 use holochain_types::{
-    dna::{DnaDef, DnaFile},
+    dna::{DnaDef, DnaFile, PublishRateLimits},
     fixt::*,
     observability,
     test_utils::fake_agent_pubkey_1,
     Timestamp,
 };
 use holochain_wasm_test_utils::TestWasm;
-use holochain_zome_types::Header;
+use holochain_zome_types::{element::SignedHeaderHashed, Header};
 use matches::assert_matches;
 use std::convert::TryFrom;
 
@@ -280,6 +281,7 @@ async fn check_app_entry_type_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::EntryDefs.into()].into(),
+            rate_limits: Default::default(),
         },
         vec![TestWasm::EntryDefs.into()],
     )
@@ -355,3 +357,187 @@ async fn check_entry_not_private_test() {
         ))
     );
 }
+
+#[tokio::test(threaded_scheduler)]
+async fn check_chain_rollback_test() {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+
+    let mut create = fixt!(Create);
+    create.header_seq = 5;
+    let first = Header::Create(create.clone());
+    create.entry_hash = fixt!(EntryHash);
+    let second = Header::Create(create.clone());
+    create.header_seq = 6;
+    let next = Header::Create(create);
+
+    workspace
+        .meta_vault
+        .register_activity(&first, ValidationStatus::Valid)
+        .unwrap();
+    env.guard()
+        .with_commit(|writer| workspace.flush_to_txn_ref(writer))
+        .unwrap();
+
+    // The same header or a header at another position is not a fork
+    assert_matches!(check_chain_rollback(&first, &workspace).await, Ok(None));
+    assert_matches!(check_chain_rollback(&next, &workspace).await, Ok(None));
+
+    // Another header at the same position is a fork
+    let expected = ChainFork {
+        fork_seq: 5,
+        first_header: HeaderHash::with_data_sync(&first),
+        second_header: HeaderHash::with_data_sync(&second),
+    };
+    let fork = check_chain_rollback(&second, &workspace)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(fork, expected);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn check_spam_test() {
+    let now = chrono::Utc::now();
+    let ago = |ms| -> Timestamp { (now - chrono::Duration::milliseconds(ms)).into() };
+    let mut header = fixt!(CreateLink);
+    header.timestamp = Timestamp::from(now).into();
+    let header: Header = header.into();
+
+    // No limits are never spam
+    let unlimited = PublishRateLimits::default();
+    let others = vec![(ago(1), 100); 100];
+    assert_matches!(check_spam(&header, 100, others, &unlimited), Ok(()));
+
+    // Only headers inside the window are counted
+    let rate_limits = PublishRateLimits {
+        window_ms: 1000,
+        max_headers: Some(2),
+        max_bytes: None,
+    };
+    let mut others = vec![(ago(100), 0), (ago(2000), 0), (ago(3000), 0)];
+    assert_matches!(check_spam(&header, 0, others.clone(), &rate_limits), Ok(()));
+    others.push((ago(500), 0));
+    assert_matches!(
+        check_spam(&header, 0, others, &rate_limits),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::RateLimited(_, _)
+        ))
+    );
+
+    // This header's size is counted with the others in the window
+    let rate_limits = PublishRateLimits {
+        window_ms: 1000,
+        max_headers: None,
+        max_bytes: Some(10),
+    };
+    let others = vec![(ago(100), 5), (ago(2000), 100)];
+    assert_matches!(check_spam(&header, 5, others.clone(), &rate_limits), Ok(()));
+    assert_matches!(
+        check_spam(&header, 6, others, &rate_limits),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::RateLimited(_, _)
+        ))
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn check_publish_time_test() {
+    let now = chrono::Utc::now();
+    let header_at = |ms: i64| -> Header {
+        let mut header = fixt!(CreateLink);
+        header.timestamp = Timestamp::from(now + chrono::Duration::milliseconds(ms)).into();
+        header.into()
+    };
+    let rate_limits = PublishRateLimits {
+        window_ms: 1000,
+        max_headers: Some(2),
+        max_bytes: None,
+    };
+    let now: Timestamp = now.into();
+
+    // Unlimited DNAs don't care when headers are dated
+    let unlimited = PublishRateLimits::default();
+    let future = header_at(MAX_CLOCK_DRIFT_MS * 2);
+    assert_matches!(check_publish_time(&future, &unlimited, now), Ok(()));
+
+    // Headers dated in the future fail
+    assert_matches!(
+        check_publish_time(&future, &rate_limits, now),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::PublishTimeOutOfRange(_)
+        ))
+    );
+    let drifted = header_at(MAX_CLOCK_DRIFT_MS / 2);
+    assert_matches!(check_publish_time(&drifted, &rate_limits, now), Ok(()));
+
+    // Old headers are fine because they still arrive by gossip
+    let old = header_at(-(MAX_CLOCK_DRIFT_MS * 2));
+    assert_matches!(check_publish_time(&old, &rate_limits, now), Ok(()));
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn check_activity_rate_test() {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+
+    let now = chrono::Utc::now();
+    let header_at = |ms: i64, prev_header: &HeaderHash| -> Header {
+        let mut header = fixt!(CreateLink);
+        header.timestamp = Timestamp::from(now + chrono::Duration::milliseconds(ms)).into();
+        header.prev_header = prev_header.clone();
+        header.into()
+    };
+    let signed = |header: &Header| {
+        SignedHeaderHashed::with_presigned(
+            HeaderHashed::from_content_sync(header.clone()),
+            fixt!(Signature),
+        )
+    };
+    let rate_limits = PublishRateLimits {
+        window_ms: 1000,
+        max_headers: Some(2),
+        max_bytes: None,
+    };
+
+    // A chain with one header before the window and two inside it
+    let old = header_at(-5000, &fixt!(HeaderHash));
+    let first = header_at(-500, &HeaderHash::with_data_sync(&old));
+    let second = header_at(-200, &HeaderHash::with_data_sync(&first));
+    workspace.element_vault.put(signed(&old), None).unwrap();
+    workspace.element_vault.put(signed(&first), None).unwrap();
+    workspace
+        .element_pending
+        .put(signed(&second), None)
+        .unwrap();
+    env.guard()
+        .with_commit(|writer| workspace.flush_to_txn_ref(writer))
+        .unwrap();
+
+    // Unlimited DNAs don't count headers
+    let unlimited = PublishRateLimits::default();
+    let next = header_at(0, &HeaderHash::with_data_sync(&second));
+    assert_matches!(check_activity_rate(&next, &unlimited, &workspace), Ok(()));
+
+    // Only the headers on this header's chain within the window are counted
+    assert_matches!(
+        check_activity_rate(&next, &rate_limits, &workspace),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::RateLimited(_, _)
+        ))
+    );
+    let fork = header_at(0, &HeaderHash::with_data_sync(&first));
+    assert_matches!(check_activity_rate(&fork, &rate_limits, &workspace), Ok(()));
+
+    // Validation waits for any missing headers in the window
+    let missing = header_at(-100, &HeaderHash::with_data_sync(&second));
+    let next = header_at(0, &HeaderHash::with_data_sync(&missing));
+    assert_matches!(
+        check_activity_rate(&next, &rate_limits, &workspace),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotHoldingDep(_)
+        ))
+    );
+}
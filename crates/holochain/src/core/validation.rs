@@ -2,12 +2,21 @@
 use std::convert::TryFrom;
 
 use derivative::Derivative;
-use holo_hash::DhtOpHash;
-use holochain_types::dht_op::DhtOp;
+use fallible_iterator::FallibleIterator;
+use holo_hash::{AgentPubKey, DhtOpHash};
+use holochain_p2p::HolochainP2pCellT;
+use holochain_state::{error::DatabaseResult, fresh_reader};
+use holochain_types::{
+    dht_op::DhtOp,
+    warrant::{SignedWarrant, SignedWarrantExt, WarrantProof},
+};
+use tracing::*;
 
 use super::{
     workflow::error::WorkflowResult, SourceChainError, SysValidationError, ValidationOutcome,
 };
+use crate::conductor::api::CellConductorApiT;
+use crate::core::state::metadata::{MetadataBuf, MetadataBufT};
 
 /// Exit early with either an outcome or an error
 pub enum OutcomeOrError<T, E> {
@@ -82,3 +91,68 @@ impl OutcomeOrError<ValidationOutcome, SysValidationError> {
         Err(SourceChainError::InvalidCommit(ValidationOutcome::try_from(self)?.to_string()).into())
     }
 }
+
+/// Sign a warrant against the author of some invalid activity
+/// and publish it to the warrantee's agent activity authorities.
+///
+/// Every op of an invalid header can be rejected, so issued warrants
+/// are registered in `issued` and a proof is only warranted once.
+///
+/// Failing to issue a warrant doesn't change the outcome of
+/// validation so any errors are only logged.
+pub async fn issue_warrant(
+    proof: WarrantProof,
+    warrantee: AgentPubKey,
+    issued: &mut MetadataBuf,
+    conductor_api: &impl CellConductorApiT,
+    network: &mut impl HolochainP2pCellT,
+) {
+    let author = conductor_api.cell_id().agent_pubkey().clone();
+    match already_issued(issued, &proof, &warrantee, &author) {
+        Ok(false) => (),
+        Ok(true) => return,
+        Err(e) => {
+            error!(
+                msg = "Failed to check for an issued warrant",
+                ?e,
+                ?warrantee
+            );
+            return;
+        }
+    }
+    let warrant = match SignedWarrant::new(
+        conductor_api.keystore(),
+        proof,
+        warrantee.clone(),
+        author,
+    )
+    .await
+    {
+        Ok(w) => w,
+        Err(e) => {
+            error!(msg = "Failed to sign a warrant", ?e, ?warrantee);
+            return;
+        }
+    };
+    match network
+        .publish_warrants(warrantee.clone(), vec![warrant.clone()], None)
+        .await
+    {
+        Ok(_) => issued.register_warrant(warrant),
+        Err(e) => warn!(msg = "Failed to publish a warrant", ?e, ?warrantee),
+    }
+}
+
+/// Has the author already issued a warrant with this proof against the warrantee?
+fn already_issued(
+    issued: &MetadataBuf,
+    proof: &WarrantProof,
+    warrantee: &AgentPubKey,
+    author: &AgentPubKey,
+) -> DatabaseResult<bool> {
+    fresh_reader!(issued.env(), |r| {
+        issued
+            .get_warrants(&r, warrantee)?
+            .any(|w| Ok(&w.warrant.author == author && &w.warrant.proof == proof))
+    })
+}
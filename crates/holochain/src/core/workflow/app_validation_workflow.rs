@@ -11,6 +11,9 @@ use super::{
 use crate::{
     conductor::api::CellConductorApiT,
     conductor::entry_def_store::get_entry_def,
+    core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckHostAccess,
+    core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckInvocation,
+    core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult,
     core::ribosome::guest_callback::validate_link::ValidateCreateLinkInvocation,
     core::ribosome::guest_callback::validate_link::ValidateDeleteLinkInvocation,
     core::ribosome::guest_callback::validate_link::ValidateLinkHostAccess,
@@ -41,6 +44,7 @@ use crate::{
             validation_db::{ValidationLimboStatus, ValidationLimboStore, ValidationLimboValue},
             workspace::{Workspace, WorkspaceResult},
         },
+        validation::issue_warrant,
         validation::DhtOpOrder,
         validation::OrderedOp,
     },
@@ -48,8 +52,9 @@ use crate::{
 use error::AppValidationResult;
 pub use error::*;
 use fallible_iterator::FallibleIterator;
-use holo_hash::{AgentPubKey, DhtOpHash};
+use holo_hash::{AgentPubKey, DhtOpHash, HeaderHash};
 use holochain_p2p::{actor::GetActivityOptions, HolochainP2pCell, HolochainP2pCellT};
+use holochain_serialized_bytes::SerializedBytes;
 use holochain_state::{
     buffer::{BufferedStore, KvBufFresh},
     db::{INTEGRATED_DHT_OPS, INTEGRATION_LIMBO},
@@ -63,6 +68,7 @@ use holochain_types::{
     dna::DnaFile,
     test_utils::which_agent,
     validate::ValidationStatus,
+    warrant::WarrantProof,
     Entry, HeaderHashed, Timestamp,
 };
 use holochain_zome_types::{
@@ -72,7 +78,7 @@ use holochain_zome_types::{
     entry_def::EntryDefId,
     header::AppEntryType,
     header::EntryType,
-    header::{CreateLink, DeleteLink, ZomeId},
+    header::{AgentValidationPkg, CreateLink, DeleteLink, ZomeId},
     query::ChainStatus,
     validate::RequiredValidationType,
     validate::ValidationPackage,
@@ -182,17 +188,38 @@ async fn app_validation_workflow_inner(
                         let iv = IntegrationLimboValue {
                             validation_status: ValidationStatus::Valid,
                             op: vlv.op,
+                            send_receipt: vlv.send_receipt,
                         };
                         workspace.put_int_limbo(hash, iv, op)?;
                     }
                     Outcome::AwaitingDeps(deps) => {
-                        vlv.status = ValidationLimboStatus::AwaitingAppDeps(deps);
-                        workspace.put_val_limbo(hash, vlv)?;
+                        if vlv.should_abandon() {
+                            workspace.abandon(hash, vlv, op)?;
+                        } else {
+                            vlv.status = ValidationLimboStatus::AwaitingAppDeps(deps);
+                            workspace.put_val_limbo(hash, vlv)?;
+                        }
                     }
-                    Outcome::Rejected(_) => {
+                    Outcome::Rejected(reason) => {
+                        let header = op.header();
+                        let proof = WarrantProof::InvalidHeader {
+                            header_hash: HeaderHash::with_data_sync(&header),
+                            signature: op.signature().clone(),
+                            reason,
+                        };
+                        let mut network = network.clone();
+                        issue_warrant(
+                            proof,
+                            header.author().clone(),
+                            &mut workspace.meta_cache,
+                            &conductor_api,
+                            &mut network,
+                        )
+                        .await;
                         let iv = IntegrationLimboValue {
                             op: vlv.op,
                             validation_status: ValidationStatus::Rejected,
+                            send_receipt: vlv.send_receipt,
                         };
                         workspace.put_int_limbo(hash, iv, op)?;
                     }
@@ -218,6 +245,15 @@ async fn validate_op(
     workspace: &mut AppValidationWorkspace,
     network: &HolochainP2pCell,
 ) -> AppValidationOutcome<Outcome> {
+    // Agents joining the network must pass the membrane check
+    // before the zomes' validation callbacks are run
+    if let DhtOp::StoreElement(_, Header::AgentValidationPkg(pkg), _) = &op {
+        match validate_agent_validation_pkg(pkg, conductor_api).await? {
+            Outcome::Accepted => (),
+            outcome => return Ok(outcome),
+        }
+    }
+
     // Get the workspace for the validation calls
     let workspace_lock = workspace.validation_workspace();
 
@@ -332,6 +368,40 @@ async fn validate_op(
     Ok(outcome)
 }
 
+/// Run the app's genesis self check against the
+/// membrane proof an agent joined the network with.
+async fn validate_agent_validation_pkg(
+    pkg: &AgentValidationPkg,
+    conductor_api: &impl CellConductorApiT,
+) -> AppValidationOutcome<Outcome> {
+    let dna_file = { conductor_api.get_this_dna().await };
+    let dna_file =
+        dna_file.ok_or_else(|| AppValidationError::DnaMissing(conductor_api.cell_id().clone()))?;
+    let ribosome = WasmRibosome::new(dna_file);
+    Ok(run_genesis_self_check(
+        pkg.author.clone(),
+        pkg.membrane_proof.clone(),
+        &ribosome,
+    )?)
+}
+
+pub fn run_genesis_self_check(
+    agent_key: AgentPubKey,
+    membrane_proof: Option<SerializedBytes>,
+    ribosome: &impl RibosomeT,
+) -> AppValidationResult<Outcome> {
+    let invocation = GenesisSelfCheckInvocation::new(
+        ribosome.dna_file().dna().clone(),
+        agent_key,
+        membrane_proof,
+    );
+    let result = ribosome.run_genesis_self_check(GenesisSelfCheckHostAccess, invocation)?;
+    match result {
+        GenesisSelfCheckResult::Valid => Ok(Outcome::Accepted),
+        GenesisSelfCheckResult::Invalid(_, reason) => Ok(Outcome::Rejected(reason)),
+    }
+}
+
 /// Get the [EntryDef] associated with this
 /// element if there is one.
 ///
@@ -1003,6 +1073,23 @@ impl AppValidationWorkspace {
         Ok(())
     }
 
+    /// Give up waiting on the dependencies of this op and
+    /// send it to integration as abandoned
+    fn abandon(
+        &mut self,
+        hash: DhtOpHash,
+        vlv: ValidationLimboValue,
+        op: DhtOp,
+    ) -> WorkflowResult<()> {
+        debug!(msg = "Abandoning op", ?hash, num_tries = vlv.num_tries);
+        let iv = IntegrationLimboValue {
+            op: vlv.op,
+            validation_status: ValidationStatus::Abandoned,
+            send_receipt: vlv.send_receipt,
+        };
+        self.put_int_limbo(hash, iv, op)
+    }
+
     /// Get a cascade over all local databases and the network
     fn full_cascade<Network: HolochainP2pCellT + Clone>(
         &mut self,
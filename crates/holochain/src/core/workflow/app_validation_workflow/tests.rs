@@ -44,6 +44,7 @@ async fn app_validation_workflow_test() {
                 TestWasm::Create.into(),
             ]
             .into(),
+            rate_limits: Default::default(),
         },
         vec![
             TestWasm::Validate.into(),
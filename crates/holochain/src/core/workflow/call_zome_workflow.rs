@@ -3,16 +3,22 @@ use super::{
 };
 use crate::conductor::interface::SignalBroadcaster;
 use crate::core::ribosome::error::RibosomeError;
+use crate::core::ribosome::guest_callback::post_commit::{
+    PostCommitHostAccess, PostCommitInvocation, PostCommitResult,
+};
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::ribosome::{error::RibosomeResult, RibosomeT, ZomeCallHostAccess};
+use crate::core::signal::SystemSignal;
 use crate::core::state::metadata::MetadataBufT;
 use crate::core::state::source_chain::SourceChainError;
 use crate::core::state::workspace::Workspace;
+use crate::core::sys_validate::{check_spam, entry_publish_size, SysValidationError};
 use crate::core::{
     queue_consumer::{OneshotWriter, TriggerSender},
     state::{
-        cascade::Cascade, element_buf::ElementBuf, metadata::MetadataBuf,
-        source_chain::SourceChain, workspace::WorkspaceResult,
+        cap_grant_uses::CapGrantUsesBuf, cascade::Cascade, element_buf::ElementBuf,
+        metadata::MetadataBuf, schedule::ScheduleBuf, source_chain::SourceChain,
+        workspace::WorkspaceResult, x25519_keys::X25519KeysBuf,
     },
 };
 use crate::{
@@ -21,15 +27,19 @@ use crate::{
 };
 pub use call_zome_workspace_lock::CallZomeWorkspaceLock;
 use either::Either;
+use holo_hash::HeaderHash;
 use holochain_keystore::KeystoreSender;
 use holochain_p2p::HolochainP2pCell;
-use holochain_state::prelude::*;
+use holochain_state::{env::EnvironmentWrite, prelude::*};
+use holochain_types::dna::PublishRateLimits;
 use holochain_types::element::Element;
+use holochain_types::Timestamp;
 use holochain_zome_types::entry::GetOptions;
 use holochain_zome_types::header::Header;
+use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::ZomeCallResponse;
 use std::sync::Arc;
-use tracing::instrument;
+use tracing::{error, instrument, warn};
 
 pub mod call_zome_workspace_lock;
 
@@ -53,40 +63,145 @@ pub struct CallZomeWorkflowArgs<Ribosome: RibosomeT, C: CellConductorApiT> {
     workspace_lock,
     network,
     keystore,
-    writer,
+    env,
     args,
-    trigger_produce_dht_ops
+    trigger_produce_dht_ops,
+    trigger_schedule
 ))]
 pub async fn call_zome_workflow<'env, Ribosome: RibosomeT, C: CellConductorApiT>(
     workspace_lock: CallZomeWorkspaceLock,
     network: HolochainP2pCell,
     keystore: KeystoreSender,
-    writer: OneshotWriter,
+    env: EnvironmentWrite,
     args: CallZomeWorkflowArgs<Ribosome, C>,
     mut trigger_produce_dht_ops: TriggerSender,
+    mut trigger_schedule: TriggerSender,
 ) -> WorkflowResult<ZomeCallInvocationResult> {
     let should_write = args.is_root_zome_call;
-    let result = call_zome_workflow_inner(workspace_lock.clone(), network, keystore, args).await?;
+    // Get the current head so we know what this call commits
+    let chain_head_start_len = workspace_lock.read().await.source_chain.len();
+    let result = call_zome_workflow_inner(
+        workspace_lock.clone(),
+        env.clone(),
+        network.clone(),
+        keystore.clone(),
+        &args,
+    )
+    .await?;
 
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    if should_write {
+    let committed = if should_write {
         let mut guard = workspace_lock.write().await;
         let workspace = &mut guard;
-        writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+        let committed = committed_headers(&workspace.source_chain, chain_head_start_len)?;
+        OneshotWriter::from(env.clone())
+            .with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+        committed
+    } else {
+        Vec::new()
+    };
+
+    // Let the zome know what it committed now that it is on the chain.
+    // The call has already succeeded so a failure here is only logged.
+    if !committed.is_empty() {
+        if let Err(e) = run_post_commit(committed, env, network, keystore, args).await {
+            error!(msg = "Could not run post_commit", ?e);
+        }
     }
 
     trigger_produce_dht_ops.trigger();
+    trigger_schedule.trigger();
 
     Ok(result)
 }
 
+/// The hashes of the headers added to the source chain after `start_len`.
+fn committed_headers(
+    source_chain: &SourceChain,
+    start_len: usize,
+) -> WorkflowResult<Vec<HeaderHash>> {
+    let mut committed = Vec::with_capacity(source_chain.len() - start_len);
+    let mut i = start_len;
+    while let Some(element) = source_chain.get_at_index(i as u32)? {
+        committed.push(element.header_address().clone());
+        i += 1;
+    }
+    Ok(committed)
+}
+
+/// Call the `post_commit` callback of the zome that was called with the
+/// headers it committed.
+/// This runs after the call's workspace has been flushed so the commits
+/// can't be undone. The callback gets a fresh workspace whose new elements
+/// are validated like any zome call's. The callback's writes are only
+/// flushed if it succeeds and they're valid, so a callback that fails
+/// part way through doesn't leave half its work on the chain.
+async fn run_post_commit<Ribosome: RibosomeT, C: CellConductorApiT>(
+    committed: Vec<HeaderHash>,
+    env: EnvironmentWrite,
+    network: HolochainP2pCell,
+    keystore: KeystoreSender,
+    args: CallZomeWorkflowArgs<Ribosome, C>,
+) -> WorkflowResult<()> {
+    let CallZomeWorkflowArgs {
+        ribosome,
+        invocation,
+        mut signal_tx,
+        conductor_api,
+        ..
+    } = args;
+    let zome_name = invocation.zome_name;
+    let cell_id = invocation.cell_id;
+    let workspace_lock = CallZomeWorkspaceLock::new(CallZomeWorkspace::new(env.clone().into())?);
+    let start_len = workspace_lock.read().await.source_chain.len();
+    let access = PostCommitHostAccess::new(
+        workspace_lock.clone(),
+        keystore,
+        network.clone(),
+        signal_tx.clone(),
+        conductor_api.clone().into_call_zome_handle(),
+        cell_id.clone(),
+    );
+    let post_commit = PostCommitInvocation::new(zome_name.clone(), committed.into());
+    let result = ribosome.run_post_commit(access, post_commit)?;
+
+    match result {
+        PostCommitResult::Success => {
+            validate_new_elements(
+                &workspace_lock,
+                start_len,
+                &network,
+                &ribosome,
+                &conductor_api,
+                &zome_name,
+            )
+            .await?;
+            let mut guard = workspace_lock.write().await;
+            let workspace = &mut guard;
+            OneshotWriter::from(env)
+                .with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+        }
+        PostCommitResult::Fail(headers, reason) => {
+            warn!(msg = "post_commit failed", ?zome_name, ?headers, %reason);
+            signal_tx.send_system(SystemSignal::PostCommitFailed {
+                cell_id,
+                zome_name,
+                headers: headers.0,
+                reason,
+            });
+        }
+    }
+    Ok(())
+}
+
 async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT, C: CellConductorApiT>(
     workspace_lock: CallZomeWorkspaceLock,
+    env: EnvironmentWrite,
     network: HolochainP2pCell,
     keystore: KeystoreSender,
-    args: CallZomeWorkflowArgs<Ribosome, C>,
+    args: &CallZomeWorkflowArgs<Ribosome, C>,
 ) -> WorkflowResult<ZomeCallInvocationResult> {
     let CallZomeWorkflowArgs {
         ribosome,
@@ -108,31 +223,59 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT, C: CellConductorApi
     let result = {
         let host_access = ZomeCallHostAccess::new(
             workspace_lock.clone(),
+            env,
             keystore,
             network.clone(),
-            signal_tx,
+            signal_tx.clone(),
             call_zome_handle,
             invocation.cell_id.clone(),
         );
-        ribosome.call_zome_function(host_access, invocation)
+        ribosome.call_zome_function(host_access, invocation.clone())
     };
     tracing::trace!(line = line!());
 
+    validate_new_elements(
+        &workspace_lock,
+        chain_head_start_len,
+        &network,
+        ribosome,
+        conductor_api,
+        &zome_name,
+    )
+    .await?;
+
+    Ok(result)
+}
+
+/// Check the elements added to the source chain after `start_len` by the
+/// zome `zome_name`, with sys validation, the publish rate limits and
+/// the zome's validation callbacks.
+/// Any invalid element fails the whole call.
+async fn validate_new_elements<Ribosome: RibosomeT, C: CellConductorApiT>(
+    workspace_lock: &CallZomeWorkspaceLock,
+    start_len: usize,
+    network: &HolochainP2pCell,
+    ribosome: &Ribosome,
+    conductor_api: &C,
+    zome_name: &ZomeName,
+) -> WorkflowResult<()> {
     let to_app_validate = {
         let mut workspace = workspace_lock.write().await;
         // Get the new head
         let chain_head_end_len = workspace.source_chain.len();
-        let new_elements_len = chain_head_end_len - chain_head_start_len;
+        let new_elements_len = chain_head_end_len - start_len;
 
         // collect all the elements we need to validate in wasm
         let mut to_app_validate: Vec<Element> = Vec::with_capacity(new_elements_len);
 
         // Has there been changes?
         if new_elements_len > 0 {
+            let rate_limits = &ribosome.dna_file().dna().rate_limits;
             // Loop forwards through all the new elements
-            let mut i = chain_head_start_len;
+            let mut i = start_len;
             while let Some(element) = workspace.source_chain.get_at_index(i as u32)? {
-                sys_validate_element(&element, &mut workspace, network.clone(), &conductor_api)
+                check_publish_rate(&element, i as u32, &workspace.source_chain, rate_limits)?;
+                sys_validate_element(&element, &mut workspace, network.clone(), conductor_api)
                     .await
                     // If the was en error exit
                     // If the validation failed, exit with an InvalidCommit
@@ -162,7 +305,7 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT, C: CellConductorApi
                         let mut cascade = workspace.cascade(network.clone());
                         let base_address = &link_add.base_address;
                         let base = cascade
-                            .retrieve_entry(base_address.clone(), GetOptions.into())
+                            .retrieve_entry(base_address.clone(), GetOptions::default().into())
                             .await
                             .map_err(RibosomeError::from)?
                             .ok_or_else(|| RibosomeError::ElementDeps(base_address.clone().into()))?
@@ -171,7 +314,7 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT, C: CellConductorApi
 
                         let target_address = &link_add.target_address;
                         let target = cascade
-                            .retrieve_entry(target_address.clone(), GetOptions.into())
+                            .retrieve_entry(target_address.clone(), GetOptions::default().into())
                             .await
                             .map_err(RibosomeError::from)?
                             .ok_or_else(|| {
@@ -188,7 +331,7 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT, C: CellConductorApi
                             link_add,
                             base,
                             target,
-                            &ribosome,
+                            ribosome,
                             workspace_lock.clone(),
                             network.clone(),
                         )?,
@@ -198,7 +341,7 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT, C: CellConductorApi
                     app_validation_workflow::run_delete_link_validation_callback(
                         zome_name.clone(),
                         delete_link.clone(),
-                        &ribosome,
+                        ribosome,
                         workspace_lock.clone(),
                         network.clone(),
                     )?,
@@ -207,10 +350,10 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT, C: CellConductorApi
                     app_validation_workflow::run_validation_callback_direct(
                         zome_name.clone(),
                         chain_element,
-                        &ribosome,
+                        ribosome,
                         workspace_lock.clone(),
                         network.clone(),
-                        &conductor_api,
+                        conductor_api,
                     )
                     .await?,
                 ),
@@ -245,7 +388,47 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT, C: CellConductorApi
         }
     }
 
-    Ok(result)
+    Ok(())
+}
+
+/// Check a new element doesn't take this agent over the DNA's publish
+/// rate limits, counting the elements before it on our source chain.
+fn check_publish_rate(
+    element: &Element,
+    index: u32,
+    source_chain: &SourceChain,
+    rate_limits: &PublishRateLimits,
+) -> WorkflowResult<()> {
+    if !rate_limits.is_limited() {
+        return Ok(());
+    }
+    let header = element.header();
+    let start = rate_limits.window_start(header.timestamp().into());
+
+    // Walk back along the chain until we leave the window
+    let mut others = Vec::new();
+    let mut i = index;
+    while i > 0 {
+        i -= 1;
+        match source_chain.get_at_index(i)? {
+            Some(prev) => {
+                let timestamp: Timestamp = prev.header().timestamp().into();
+                if timestamp <= start {
+                    break;
+                }
+                others.push((timestamp, entry_publish_size(prev.entry().as_option())));
+            }
+            None => break,
+        }
+    }
+
+    let size = entry_publish_size(element.entry().as_option());
+    match check_spam(header, size, others, rate_limits) {
+        Err(SysValidationError::ValidationOutcome(outcome)) => {
+            Err(SourceChainError::InvalidCommit(outcome.to_string()).into())
+        }
+        r => Ok(r?),
+    }
 }
 
 pub struct CallZomeWorkspace {
@@ -255,6 +438,9 @@ pub struct CallZomeWorkspace {
     pub meta_integrated: MetadataBuf<IntegratedPrefix>,
     pub element_cache: ElementBuf,
     pub meta_cache: MetadataBuf,
+    pub schedules: ScheduleBuf,
+    pub cap_grant_uses: CapGrantUsesBuf,
+    pub x25519_keys: X25519KeysBuf,
 }
 
 impl<'a> CallZomeWorkspace {
@@ -264,7 +450,10 @@ impl<'a> CallZomeWorkspace {
         let element_integrated = ElementBuf::vault(env.clone(), true)?;
         let meta_integrated = MetadataBuf::vault(env.clone())?;
         let element_cache = ElementBuf::cache(env.clone())?;
-        let meta_cache = MetadataBuf::cache(env)?;
+        let meta_cache = MetadataBuf::cache(env.clone())?;
+        let schedules = ScheduleBuf::new(env.clone())?;
+        let cap_grant_uses = CapGrantUsesBuf::new(env.clone())?;
+        let x25519_keys = X25519KeysBuf::new(env)?;
 
         Ok(CallZomeWorkspace {
             source_chain,
@@ -273,6 +462,9 @@ impl<'a> CallZomeWorkspace {
             meta_integrated,
             element_cache,
             meta_cache,
+            schedules,
+            cap_grant_uses,
+            x25519_keys,
         })
     }
 
@@ -311,6 +503,9 @@ impl Workspace for CallZomeWorkspace {
         self.meta_authored.flush_to_txn_ref(writer)?;
         self.element_cache.flush_to_txn_ref(writer)?;
         self.meta_cache.flush_to_txn_ref(writer)?;
+        self.schedules.flush_to_txn_ref(writer)?;
+        self.cap_grant_uses.flush_to_txn_ref(writer)?;
+        self.x25519_keys.flush_to_txn_ref(writer)?;
         Ok(())
     }
 }
@@ -319,6 +514,7 @@ impl Workspace for CallZomeWorkspace {
 pub mod tests {
     use super::*;
     use crate::conductor::{api::CellConductorApi, handle::MockConductorHandleT};
+    use crate::core::signal::Signal;
     use crate::core::{
         ribosome::MockRibosomeT,
         workflow::{error::WorkflowError, genesis_workflow::tests::fake_genesis},
@@ -332,6 +528,7 @@ pub mod tests {
     use holochain_types::{cell::CellId, observability, test_utils::fake_agent_pubkey_1};
     use holochain_wasm_test_utils::TestWasm;
     use holochain_zome_types::entry::Entry;
+    use holochain_zome_types::zome::ZomeName;
     use holochain_zome_types::ExternInput;
     use holochain_zome_types::ExternOutput;
     use matches::assert_matches;
@@ -346,6 +543,7 @@ pub mod tests {
         ribosome: Ribosome,
         invocation: ZomeCallInvocation,
     ) -> WorkflowResult<ZomeCallInvocationResult> {
+        let env = fixt!(EnvironmentWrite);
         let keystore = fixt!(KeystoreSender);
         let network = fixt!(HolochainP2pCell);
         let cell_id = CellId::new(ribosome.dna_file().dna_hash().clone(), fixt!(AgentPubKey));
@@ -358,7 +556,7 @@ pub mod tests {
             conductor_api,
             is_root_zome_call: true,
         };
-        call_zome_workflow_inner(workspace.into(), network, keystore, args).await
+        call_zome_workflow_inner(workspace.into(), env, network, keystore, &args).await
     }
 
     // 1.  Check if there is a Capability token secret in the parameters.
@@ -536,4 +734,75 @@ pub mod tests {
             .unwrap();
         // TODO: Check the workspace has changes
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn committed_headers_are_the_new_headers() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        // Genesis writes the dna, agent validation pkg and agent headers
+        fake_genesis(&mut workspace.source_chain).await.unwrap();
+        let expected = vec![
+            workspace.source_chain.get_at_index(1).unwrap().unwrap(),
+            workspace.source_chain.get_at_index(2).unwrap().unwrap(),
+        ]
+        .into_iter()
+        .map(|el| el.header_address().clone())
+        .collect::<Vec<_>>();
+
+        let committed = committed_headers(&workspace.source_chain, 1).unwrap();
+        assert_eq!(committed, expected);
+
+        // Nothing was committed after the head
+        let len = workspace.source_chain.len();
+        let committed = committed_headers(&workspace.source_chain, len).unwrap();
+        assert!(committed.is_empty());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn post_commit_failures_are_signalled() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let (tx, mut rx) = tokio::sync::broadcast::channel(10);
+
+        for wasm in vec![TestWasm::PostCommitSuccess, TestWasm::PostCommitFail] {
+            let ribosome = WasmRibosomeFixturator::new(curve::Zomes(vec![wasm]))
+                .next()
+                .unwrap();
+            let mut invocation =
+                crate::core::ribosome::ZomeCallInvocationFixturator::new(fixt::Unpredictable)
+                    .next()
+                    .unwrap();
+            invocation.zome_name = wasm.into();
+            let conductor_api = CellConductorApi::new(
+                Arc::new(MockConductorHandleT::new()),
+                invocation.cell_id.clone(),
+            );
+            let args = CallZomeWorkflowArgs {
+                invocation,
+                ribosome,
+                signal_tx: SignalBroadcaster::new(vec![tx.clone()]),
+                conductor_api,
+                is_root_zome_call: true,
+            };
+            run_post_commit(
+                vec![fixt!(HeaderHash)],
+                env.clone(),
+                fixt!(HolochainP2pCell),
+                fixt!(KeystoreSender),
+                args,
+            )
+            .await
+            .unwrap();
+        }
+
+        // Only the failing callback is signalled
+        assert_matches!(
+            rx.try_recv(),
+            Ok(Signal::System(SystemSignal::PostCommitFailed { zome_name, reason, .. }))
+                if zome_name == ZomeName::from(TestWasm::PostCommitFail) && reason == "empty header fail"
+        );
+        assert!(rx.try_recv().is_err());
+    }
 }
@@ -21,6 +21,7 @@ async fn direct_validation_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Update.into()].into(),
+            rate_limits: Default::default(),
         },
         vec![TestWasm::Update.into()],
     )
@@ -6,7 +6,9 @@ use super::{
     produce_dht_ops_workflow::dht_op_light::error::DhtOpConvertError,
 };
 use crate::{
-    conductor::{api::error::ConductorApiError, CellError},
+    conductor::{
+        api::error::ConductorApiError, entry_def_store::error::EntryDefStoreError, CellError,
+    },
     core::{
         queue_consumer::QueueTriggerClosedError,
         ribosome::error::RibosomeError,
@@ -19,6 +21,7 @@ use crate::{
 use holochain_p2p::HolochainP2pError;
 use holochain_state::error::DatabaseError;
 use holochain_types::{dht_op::error::DhtOpError, prelude::*};
+use holochain_zome_types::zome::ZomeName;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -29,6 +32,9 @@ pub enum WorkflowError {
     #[error("Agent is invalid: {0:?}")]
     AgentInvalid(AgentPubKey),
 
+    #[error("The {0} zome rejected the membrane proof in its genesis self check: {1}")]
+    GenesisSelfCheckInvalid(ZomeName, String),
+
     #[error("Conductor API error: {0}")]
     ConductorApi(#[from] Box<ConductorApiError>),
 
@@ -70,6 +76,9 @@ pub enum WorkflowError {
 
     #[error(transparent)]
     SysValidationError(#[from] SysValidationError),
+
+    #[error(transparent)]
+    EntryDefStoreError(#[from] EntryDefStoreError),
 }
 
 /// Internal type to handle running workflows
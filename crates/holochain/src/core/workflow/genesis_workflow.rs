@@ -11,6 +11,12 @@ use super::error::{WorkflowError, WorkflowResult};
 use crate::conductor::api::CellConductorApiT;
 use crate::core::{
     queue_consumer::OneshotWriter,
+    ribosome::{
+        guest_callback::genesis_self_check::{
+            GenesisSelfCheckHostAccess, GenesisSelfCheckInvocation, GenesisSelfCheckResult,
+        },
+        RibosomeT,
+    },
     state::{
         source_chain::SourceChainBuf,
         workspace::{Workspace, WorkspaceResult},
@@ -24,18 +30,19 @@ use tracing::*;
 
 /// The struct which implements the genesis Workflow
 #[derive(Constructor, Debug)]
-pub struct GenesisWorkflowArgs {
+pub struct GenesisWorkflowArgs<Ribosome: RibosomeT> {
     dna_file: DnaFile,
     agent_pubkey: AgentPubKey,
     membrane_proof: Option<SerializedBytes>,
+    ribosome: Ribosome,
 }
 
-#[instrument(skip(workspace, writer, api))]
-pub async fn genesis_workflow<'env, Api: CellConductorApiT>(
+#[instrument(skip(workspace, writer, api, args))]
+pub async fn genesis_workflow<'env, Api: CellConductorApiT, Ribosome: RibosomeT>(
     mut workspace: GenesisWorkspace,
     writer: OneshotWriter,
     api: Api,
-    args: GenesisWorkflowArgs,
+    args: GenesisWorkflowArgs<Ribosome>,
 ) -> WorkflowResult<()> {
     genesis_workflow_inner(&mut workspace, args, api).await?;
 
@@ -47,15 +54,16 @@ pub async fn genesis_workflow<'env, Api: CellConductorApiT>(
     Ok(())
 }
 
-async fn genesis_workflow_inner<Api: CellConductorApiT>(
+async fn genesis_workflow_inner<Api: CellConductorApiT, Ribosome: RibosomeT>(
     workspace: &mut GenesisWorkspace,
-    args: GenesisWorkflowArgs,
+    args: GenesisWorkflowArgs<Ribosome>,
     api: Api,
 ) -> WorkflowResult<()> {
     let GenesisWorkflowArgs {
         dna_file,
         agent_pubkey,
         membrane_proof,
+        ribosome,
     } = args;
 
     // TODO: this is a placeholder for a real DPKI request to show intent
@@ -68,6 +76,18 @@ async fn genesis_workflow_inner<Api: CellConductorApiT>(
         return Err(WorkflowError::AgentInvalid(agent_pubkey.clone()));
     }
 
+    // Let the app check the membrane proof before anything is committed
+    let invocation = GenesisSelfCheckInvocation::new(
+        dna_file.dna().clone(),
+        agent_pubkey.clone(),
+        membrane_proof.clone(),
+    );
+    if let GenesisSelfCheckResult::Invalid(zome_name, reason) =
+        ribosome.run_genesis_self_check(GenesisSelfCheckHostAccess, invocation)?
+    {
+        return Err(WorkflowError::GenesisSelfCheckInvalid(zome_name, reason));
+    }
+
     workspace
         .source_chain
         .genesis(
@@ -109,7 +129,7 @@ pub mod tests {
 
     use crate::{
         conductor::api::MockCellConductorApi,
-        core::{state::source_chain::SourceChain, SourceChainResult},
+        core::{ribosome::MockRibosomeT, state::source_chain::SourceChain, SourceChainResult},
     };
     use fallible_iterator::FallibleIterator;
     use holochain_state::test_utils::test_cell_env;
@@ -141,10 +161,15 @@ pub mod tests {
             let mut api = MockCellConductorApi::new();
             api.expect_sync_dpki_request()
                 .returning(|_, _| Ok("mocked dpki request response".to_string()));
+            let mut ribosome = MockRibosomeT::new();
+            ribosome
+                .expect_run_genesis_self_check()
+                .returning(|_, _| Ok(GenesisSelfCheckResult::Valid));
             let args = GenesisWorkflowArgs {
                 dna_file: dna.clone(),
                 agent_pubkey: agent_pubkey.clone(),
                 membrane_proof: None,
+                ribosome,
             };
             let _: () = genesis_workflow(workspace, arc.clone().into(), api, args).await?;
         }
@@ -165,12 +190,57 @@ pub mod tests {
 
             assert_matches!(
                 headers.as_slice(),
-                [Header::Create(_), Header::AgentValidationPkg(_), Header::Dna(_)]
+                [
+                    Header::Create(_),
+                    Header::AgentValidationPkg(_),
+                    Header::Dna(_)
+                ]
             );
         }
 
         Ok(())
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn genesis_self_check_failure_leaves_chain_empty() -> Result<(), anyhow::Error> {
+        observability::test_run()?;
+        let test_env = test_cell_env();
+        let arc = test_env.env();
+        let dna = fake_dna_file("a");
+        let agent_pubkey = fake_agent_pubkey_1();
+
+        {
+            let workspace = GenesisWorkspace::new(arc.clone().into()).await?;
+            let mut api = MockCellConductorApi::new();
+            api.expect_sync_dpki_request()
+                .returning(|_, _| Ok("mocked dpki request response".to_string()));
+            let mut ribosome = MockRibosomeT::new();
+            ribosome.expect_run_genesis_self_check().returning(|_, _| {
+                Ok(GenesisSelfCheckResult::Invalid(
+                    "zome".into(),
+                    "bad proof".into(),
+                ))
+            });
+            let args = GenesisWorkflowArgs {
+                dna_file: dna.clone(),
+                agent_pubkey: agent_pubkey.clone(),
+                membrane_proof: None,
+                ribosome,
+            };
+            let result = genesis_workflow(workspace, arc.clone().into(), api, args).await;
+            assert_matches!(
+                result,
+                Err(WorkflowError::GenesisSelfCheckInvalid(_, reason)) if reason == "bad proof"
+            );
+        }
+
+        {
+            let source_chain = SourceChainBuf::new(arc.clone().into())?;
+            assert!(source_chain.chain_head().is_none());
+        }
+
+        Ok(())
+    }
 }
 
 /* TODO: make doc-able
@@ -0,0 +1,296 @@
+//! # Health Check Workflow
+//!
+//! Periodically checks how well replicated the ops we have authored are.
+//!
+//! For every authored op the signed validation receipts we have been sent are
+//! counted and compared against the `required_validations` of the op's entry
+//! def (or the default when the op is not about an app entry).
+//! Ops which do not have enough receipts are made due for publishing again,
+//! regardless of when they were last published, and the publish workflow is
+//! triggered to push them back out to the network.
+
+use super::error::WorkflowResult;
+use crate::conductor::{api::CellConductorApiT, entry_def_store::get_entry_def_from_ids};
+use crate::core::{
+    queue_consumer::{OneshotWriter, TriggerSender},
+    state::{
+        dht_op_integration::{AuthoredDhtOpsStore, AuthoredDhtOpsValue},
+        element_buf::ElementBuf,
+        validation_receipts_db::ValidationReceiptsBuf,
+        workspace::{Workspace, WorkspaceResult},
+    },
+};
+use fallible_iterator::FallibleIterator;
+use holo_hash::*;
+use holochain_state::{
+    buffer::{BufferedStore, KvBufFresh},
+    db::AUTHORED_DHT_OPS,
+    fresh_reader,
+    prelude::*,
+};
+use holochain_types::dna::DnaFile;
+use holochain_zome_types::{
+    entry_def::RequiredValidations,
+    header::{AppEntryType, EntryType},
+};
+use std::collections::HashMap;
+use tracing::*;
+
+/// Database buffers required for checking the health of authored [DhtOp]s
+///
+/// [DhtOp]: holochain_types::dht_op::DhtOp
+pub struct HealthCheckWorkspace {
+    /// Database of authored DhtOps, with data about prior publishing
+    authored_dht_ops: AuthoredDhtOpsStore,
+    /// Receipts sent to us by the validators of our ops
+    validation_receipts: ValidationReceiptsBuf,
+    /// Element store for looking up the entry type of an op
+    elements: ElementBuf<AuthoredPrefix>,
+}
+
+/// Check the replication of every authored op and trigger the publish
+/// workflow if any of them need publishing again.
+#[instrument(skip(workspace, writer, conductor_api, trigger_publish))]
+pub async fn health_check_workflow<C: CellConductorApiT>(
+    mut workspace: HealthCheckWorkspace,
+    writer: OneshotWriter,
+    conductor_api: &C,
+    trigger_publish: &mut TriggerSender,
+) -> WorkflowResult<()> {
+    let under_replicated = health_check_workflow_inner(&mut workspace, conductor_api).await?;
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    // commit the workspace
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+
+    if under_replicated > 0 {
+        debug!(under_replicated, "Republishing under-replicated ops");
+        trigger_publish.trigger();
+    }
+
+    Ok(())
+}
+
+/// Update the receipt counts of the authored ops and make the ops which
+/// don't have enough receipts due for publishing.
+/// Returns the number of under-replicated ops.
+async fn health_check_workflow_inner<C: CellConductorApiT>(
+    workspace: &mut HealthCheckWorkspace,
+    conductor_api: &C,
+) -> WorkflowResult<usize> {
+    let env = workspace.elements.headers().env().clone();
+
+    let authored = fresh_reader!(env, |r| {
+        let validation_receipts = &workspace.validation_receipts;
+        workspace
+            .authored_dht_ops
+            .iter(&r)?
+            .map(|(k, v)| {
+                let op_hash = DhtOpHash::from_raw_39_panicky(k.to_vec());
+                let receipt_count = validation_receipts.count_valid(&r, &op_hash)? as u32;
+                Ok((op_hash, v, receipt_count))
+            })
+            .collect::<Vec<_>>()
+    })?;
+
+    let mut required = RequiredReceipts::new(conductor_api);
+    let mut under_replicated = 0;
+    for (op_hash, value, receipt_count) in authored {
+        let required_receipt_count = required.for_op(&workspace.elements, &value).await?;
+        let mut updated = AuthoredDhtOpsValue {
+            receipt_count,
+            required_receipt_count,
+            ..value.clone()
+        };
+        if receipt_count < required_receipt_count {
+            // Publish again as soon as the publish workflow runs
+            updated.last_publish_time = None;
+            under_replicated += 1;
+        }
+        if updated != value {
+            workspace.authored_dht_ops.put(op_hash, updated)?;
+        }
+    }
+    Ok(under_replicated)
+}
+
+/// Looks up how many validation receipts an op needs,
+/// caching the entry defs which are used along the way.
+struct RequiredReceipts<'a, C: CellConductorApiT> {
+    conductor_api: &'a C,
+    dna_file: Option<DnaFile>,
+    by_entry_type: HashMap<AppEntryType, u32>,
+}
+
+impl<'a, C: CellConductorApiT> RequiredReceipts<'a, C> {
+    fn new(conductor_api: &'a C) -> Self {
+        Self {
+            conductor_api,
+            dna_file: None,
+            by_entry_type: HashMap::new(),
+        }
+    }
+
+    async fn for_op(
+        &mut self,
+        elements: &ElementBuf<AuthoredPrefix>,
+        value: &AuthoredDhtOpsValue,
+    ) -> WorkflowResult<u32> {
+        let app_entry_type = match elements
+            .get_header(value.op.header_hash())?
+            .and_then(|shh| shh.header().entry_type().cloned())
+        {
+            Some(EntryType::App(aet)) => aet,
+            _ => return Ok(default_required()),
+        };
+        if let Some(required) = self.by_entry_type.get(&app_entry_type) {
+            return Ok(*required);
+        }
+
+        if self.dna_file.is_none() {
+            self.dna_file = self.conductor_api.get_this_dna().await;
+        }
+        let required = match &self.dna_file {
+            Some(dna_file) => get_entry_def_from_ids(
+                app_entry_type.zome_id(),
+                app_entry_type.id(),
+                dna_file,
+                self.conductor_api,
+            )
+            .await?
+            .map(|entry_def| u8::from(entry_def.required_validations) as u32)
+            .unwrap_or_else(default_required),
+            None => default_required(),
+        };
+        self.by_entry_type.insert(app_entry_type, required);
+        Ok(required)
+    }
+}
+
+fn default_required() -> u32 {
+    u8::from(RequiredValidations::default()) as u32
+}
+
+impl HealthCheckWorkspace {
+    pub fn new(env: EnvironmentRead) -> WorkspaceResult<Self> {
+        let db = env.get_db(&*AUTHORED_DHT_OPS)?;
+        let authored_dht_ops = KvBufFresh::new(env.clone(), db);
+        let validation_receipts = ValidationReceiptsBuf::new(&env)?;
+        let elements = ElementBuf::authored(env, false)?;
+        Ok(Self {
+            authored_dht_ops,
+            validation_receipts,
+            elements,
+        })
+    }
+}
+
+impl Workspace for HealthCheckWorkspace {
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> WorkspaceResult<()> {
+        self.authored_dht_ops.flush_to_txn_ref(writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conductor::api::MockCellConductorApi;
+    use crate::core::state::validation_receipts_db::{ValidationReceipt, ValidationResult};
+    use crate::fixt::CreateLinkFixturator;
+    use ::fixt::prelude::*;
+    use holochain_keystore::KeystoreSenderExt;
+    use holochain_state::{env::WriteManager, test_utils::test_cell_env};
+    use holochain_types::{
+        dht_op::{DhtOp, DhtOpHashed},
+        fixt::SignatureFixturator,
+        Timestamp,
+    };
+
+    #[tokio::test(threaded_scheduler)]
+    async fn under_replicated_ops_are_made_due_for_publishing() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let keystore = holochain_state::test_utils::test_keystore();
+        let last_publish_time = Some(Timestamp::now());
+
+        // Two recently published ops which aren't about app entries
+        let mut op_hashes = Vec::new();
+        {
+            let mut workspace = HealthCheckWorkspace::new(env.clone().into()).unwrap();
+            for _ in 0..2 {
+                let op = DhtOp::RegisterAddLink(fixt!(Signature), fixt!(CreateLink));
+                let light = op.to_light();
+                let op_hash = DhtOpHashed::from_content_sync(op).into_hash();
+                let value = AuthoredDhtOpsValue {
+                    last_publish_time,
+                    ..AuthoredDhtOpsValue::from_light(light)
+                };
+                workspace
+                    .authored_dht_ops
+                    .put(op_hash.clone(), value)
+                    .unwrap();
+                op_hashes.push(op_hash);
+            }
+            env.guard()
+                .with_commit(|writer| workspace.flush_to_txn(writer))
+                .unwrap();
+        }
+        let (saturated, under_replicated) = (op_hashes[0].clone(), op_hashes[1].clone());
+
+        // The first op has all the receipts it needs, the second only has two
+        {
+            let mut receipts = ValidationReceiptsBuf::new(&env).unwrap();
+            for (op_hash, count) in vec![(&saturated, default_required()), (&under_replicated, 2)] {
+                for _ in 0..count {
+                    let validator = keystore
+                        .clone()
+                        .generate_sign_keypair_from_pure_entropy()
+                        .await
+                        .unwrap();
+                    let receipt = ValidationReceipt {
+                        dht_op_hash: op_hash.clone(),
+                        validation_result: ValidationResult::Valid,
+                        validator,
+                    }
+                    .sign(&keystore)
+                    .await
+                    .unwrap();
+                    receipts.add_if_unique(receipt).unwrap();
+                }
+            }
+            env.guard()
+                .with_commit(|writer| receipts.flush_to_txn(writer))
+                .unwrap();
+        }
+
+        let conductor_api = MockCellConductorApi::new();
+        let (mut trigger_publish, mut publish_rx) = TriggerSender::new();
+        let workspace = HealthCheckWorkspace::new(env.clone().into()).unwrap();
+        health_check_workflow(
+            workspace,
+            env.clone().into(),
+            &conductor_api,
+            &mut trigger_publish,
+        )
+        .await
+        .unwrap();
+
+        let workspace = HealthCheckWorkspace::new(env.clone().into()).unwrap();
+        let saturated = workspace.authored_dht_ops.get(&saturated).unwrap().unwrap();
+        assert_eq!(saturated.receipt_count, default_required());
+        assert_eq!(saturated.last_publish_time, last_publish_time);
+
+        let under_replicated = workspace
+            .authored_dht_ops
+            .get(&under_replicated)
+            .unwrap()
+            .unwrap();
+        assert_eq!(under_replicated.receipt_count, 2);
+        assert_eq!(under_replicated.last_publish_time, None);
+
+        // The publish workflow was triggered
+        publish_rx.listen().await.unwrap();
+    }
+}
@@ -12,33 +12,46 @@ use crate::core::{
         dht_op_integration::{IntegratedDhtOpsStore, IntegrationLimboStore},
         element_buf::ElementBuf,
         metadata::MetadataBuf,
-        metadata::MetadataBufT,
+        metadata::{ChainItemKey, MetadataBufT},
         validation_db::{ValidationLimboStatus, ValidationLimboStore, ValidationLimboValue},
         workspace::{Workspace, WorkspaceResult},
     },
+    sys_validate::{check_publish_time, check_spam, entry_publish_size, SysValidationError},
 };
-use holo_hash::{AgentPubKey, DhtOpHash};
+use fallible_iterator::FallibleIterator;
+use holo_hash::{AgentPubKey, DhtOpHash, HeaderHash};
 use holochain_state::{
     buffer::BufferedStore,
     buffer::KvBufFresh,
     db::{INTEGRATED_DHT_OPS, INTEGRATION_LIMBO},
     env::EnvironmentWrite,
     error::DatabaseResult,
+    fresh_reader,
     prelude::{EnvironmentRead, GetDb, IntegratedPrefix, PendingPrefix, Writer},
 };
-use holochain_types::{dht_op::DhtOp, Timestamp};
+use holochain_types::{dht_op::DhtOp, dna::PublishRateLimits, Timestamp};
 use holochain_zome_types::query::HighestObserved;
+use std::collections::HashSet;
 use tracing::instrument;
 
 #[cfg(test)]
 mod test;
 
-#[instrument(skip(state_env, sys_validation_trigger, ops))]
+/// Add incoming ops to the validation limbo.
+///
+/// If `rate_limits` are given, ops dated in the future or from authors
+/// that are over the DNA's publish rate limits by the activity we hold
+/// are dropped without being validated.
+/// Both checks depend on our clock and what we've seen so far so
+/// these ops are not rejected and can be accepted if they arrive again.
+#[instrument(skip(state_env, sys_validation_trigger, ops, rate_limits))]
 pub async fn incoming_dht_ops_workflow(
     state_env: &EnvironmentWrite,
     mut sys_validation_trigger: TriggerSender,
     ops: Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
     from_agent: Option<AgentPubKey>,
+    request_validation_receipt: bool,
+    rate_limits: Option<&PublishRateLimits>,
 ) -> WorkflowResult<()> {
     // set up our workspace
     let mut workspace = IncomingDhtOpsWorkspace::new(state_env.clone().into())?;
@@ -48,9 +61,29 @@ pub async fn incoming_dht_ops_workflow(
         if !workspace.op_exists(&hash)? {
             tracing::debug!(?hash, ?op);
             if should_keep(&op).await? {
-                workspace
-                    .add_to_pending(hash, op, from_agent.clone())
-                    .await?;
+                let spam_check = match rate_limits {
+                    Some(rate_limits) => workspace.check_spam(&op, rate_limits),
+                    None => Ok(()),
+                };
+                match spam_check {
+                    Ok(()) => {
+                        workspace
+                            .add_to_pending(
+                                hash,
+                                op,
+                                from_agent.clone(),
+                                request_validation_receipt,
+                            )
+                            .await?
+                    }
+                    Err(SysValidationError::ValidationOutcome(outcome)) => {
+                        tracing::warn!(
+                            msg = "Dropping op because it failed the publish rate checks",
+                            ?outcome
+                        );
+                    }
+                    Err(e) => return Err(e.into()),
+                }
             } else {
                 tracing::warn!(
                     msg = "Dropping op because it failed counterfeit checks",
@@ -85,12 +118,15 @@ pub struct IncomingDhtOpsWorkspace {
     pub validation_limbo: ValidationLimboStore,
     pub element_pending: ElementBuf<PendingPrefix>,
     pub meta_pending: MetadataBuf<PendingPrefix>,
+    /// Read only integrated elements for sizing an author's activity
+    pub element_integrated: ElementBuf<IntegratedPrefix>,
     pub meta_integrated: MetadataBuf<IntegratedPrefix>,
 }
 
 impl Workspace for IncomingDhtOpsWorkspace {
     fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> WorkspaceResult<()> {
         self.validation_limbo.0.flush_to_txn_ref(writer)?;
+        self.integration_limbo.flush_to_txn_ref(writer)?;
         self.element_pending.flush_to_txn_ref(writer)?;
         self.meta_pending.flush_to_txn_ref(writer)?;
         self.meta_integrated.flush_to_txn_ref(writer)?;
@@ -111,6 +147,7 @@ impl IncomingDhtOpsWorkspace {
         let element_pending = ElementBuf::pending(env.clone())?;
         let meta_pending = MetadataBuf::pending(env.clone())?;
 
+        let element_integrated = ElementBuf::vault(env.clone(), true)?;
         let meta_integrated = MetadataBuf::vault(env)?;
 
         Ok(Self {
@@ -119,6 +156,7 @@ impl IncomingDhtOpsWorkspace {
             validation_limbo,
             element_pending,
             meta_pending,
+            element_integrated,
             meta_integrated,
         })
     }
@@ -128,6 +166,7 @@ impl IncomingDhtOpsWorkspace {
         hash: DhtOpHash,
         op: DhtOp,
         from_agent: Option<AgentPubKey>,
+        send_receipt: bool,
     ) -> DhtOpConvertResult<()> {
         let basis = op.dht_basis();
         let op_light = op.to_light();
@@ -158,11 +197,77 @@ impl IncomingDhtOpsWorkspace {
             last_try: None,
             num_tries: 0,
             from_agent,
+            send_receipt,
         };
         self.validation_limbo.put(hash, vlv)?;
         Ok(())
     }
 
+    /// Check the op isn't dated in the future and doesn't take its author
+    /// over the publish rate limits, counting the author's other activity
+    /// that we hold.
+    fn check_spam(
+        &self,
+        op: &DhtOp,
+        rate_limits: &PublishRateLimits,
+    ) -> Result<(), SysValidationError> {
+        if !rate_limits.is_limited() {
+            return Ok(());
+        }
+        let header = op.header();
+        check_publish_time(&header, rate_limits, Timestamp::now())?;
+        let header_hash = HeaderHash::with_data_sync(&header);
+        let start = rate_limits.window_start(header.timestamp().into());
+        let key = ChainItemKey::Agent(header.author().clone());
+        let activity = fresh_reader!(self.meta_pending.env(), |r| {
+            let mut activity = self
+                .meta_integrated
+                .get_activity(&r, key.clone())?
+                .filter(|thh| Ok(thh.timestamp > start))
+                .collect::<Vec<_>>()?;
+            activity.extend(
+                self.meta_pending
+                    .get_activity(&r, key)?
+                    .filter(|thh| Ok(thh.timestamp > start))
+                    .collect::<Vec<_>>()?,
+            );
+            DatabaseResult::Ok(activity)
+        })?;
+
+        // The same header can be held in both stores
+        let mut seen = HashSet::new();
+        let mut others = Vec::with_capacity(activity.len());
+        for thh in activity {
+            if thh.header_hash == header_hash || !seen.insert(thh.header_hash.clone()) {
+                continue;
+            }
+            let size = if rate_limits.max_bytes.is_some() {
+                self.held_entry_size(&thh.header_hash)?
+            } else {
+                0
+            };
+            others.push((thh.timestamp, size));
+        }
+        check_spam(&header, entry_publish_size(op.entry()), others, rate_limits)
+    }
+
+    /// The publish size of the entry on a header we hold, if we hold the entry
+    fn held_entry_size(&self, header_hash: &HeaderHash) -> DatabaseResult<usize> {
+        let header = match self.element_pending.get_header(header_hash)? {
+            Some(header) => Some(header),
+            None => self.element_integrated.get_header(header_hash)?,
+        };
+        let entry_hash = match header.as_ref().and_then(|h| h.header().entry_data()) {
+            Some((entry_hash, _)) => entry_hash.clone(),
+            None => return Ok(0),
+        };
+        let entry = match self.element_pending.get_entry(&entry_hash)? {
+            Some(entry) => Some(entry),
+            None => self.element_integrated.get_entry(&entry_hash)?,
+        };
+        Ok(entry_publish_size(entry.as_ref().map(|e| e.as_content())))
+    }
+
     pub fn op_exists(&self, hash: &DhtOpHash) -> DatabaseResult<bool> {
         Ok(self.integrated_dht_ops.contains(&hash)?
             || self.integration_limbo.contains(&hash)?
@@ -1,4 +1,5 @@
 use super::*;
+use crate::core::sys_validate::MAX_CLOCK_DRIFT_MS;
 use ::fixt::prelude::*;
 use holochain_keystore::AgentPubKeyExt;
 use holochain_types::{dht_op::DhtOp, fixt::*};
@@ -22,7 +23,7 @@ async fn incoming_ops_to_limbo() {
     let hash = DhtOpHash::with_data_sync(&op);
     let ops = vec![(hash.clone(), op.clone())];
 
-    incoming_dht_ops_workflow(&env, sys_validation_trigger.clone(), ops, None)
+    incoming_dht_ops_workflow(&env, sys_validation_trigger.clone(), ops, None, false, None)
         .await
         .unwrap();
     rx.listen().await.unwrap();
@@ -31,3 +32,65 @@ async fn incoming_ops_to_limbo() {
     let r = workspace.validation_limbo.get(&hash).unwrap().unwrap();
     assert_eq!(r.op, op_light);
 }
+
+#[tokio::test(threaded_scheduler)]
+async fn incoming_ops_over_rate_limits_are_dropped() {
+    let test_env = holochain_state::test_utils::test_cell_env();
+    let env = test_env.env();
+    let keystore = holochain_state::test_utils::test_keystore();
+    let (sys_validation_trigger, _rx) = TriggerSender::new();
+
+    let rate_limits = PublishRateLimits {
+        window_ms: 60_000,
+        max_headers: Some(1),
+        max_bytes: None,
+    };
+
+    let author = fake_agent_pubkey_1();
+    let mut ops = Vec::new();
+    for header_seq in 1..3 {
+        let mut header = fixt!(CreateLink);
+        header.author = author.clone();
+        header.header_seq = header_seq;
+        header.timestamp = Timestamp::now().into();
+        let header = Header::CreateLink(header);
+        let signature = author.sign(&keystore, &header).await.unwrap();
+        let op = DhtOp::RegisterAgentActivity(signature, header);
+        ops.push((DhtOpHash::with_data_sync(&op), op));
+    }
+    // A header dated in the future
+    let mut header = fixt!(CreateLink);
+    header.author = author.clone();
+    header.header_seq = 3;
+    header.timestamp = Timestamp::from(
+        chrono::Utc::now() + chrono::Duration::milliseconds(MAX_CLOCK_DRIFT_MS * 2),
+    )
+    .into();
+    let header = Header::CreateLink(header);
+    let signature = author.sign(&keystore, &header).await.unwrap();
+    let op = DhtOp::RegisterAgentActivity(signature, header);
+    ops.push((DhtOpHash::with_data_sync(&op), op));
+    let first = ops[0].0.clone();
+    let second = ops[1].0.clone();
+    let future = ops[2].0.clone();
+
+    incoming_dht_ops_workflow(
+        &env,
+        sys_validation_trigger,
+        ops,
+        None,
+        false,
+        Some(&rate_limits),
+    )
+    .await
+    .unwrap();
+
+    let workspace = IncomingDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    // The first header is within the limits so it's validated
+    assert!(workspace.validation_limbo.get(&first).unwrap().is_some());
+    assert!(workspace.integration_limbo.get(&first).unwrap().is_none());
+    // The second takes the author over the limits and the third is
+    // too far in the future so they're dropped and can arrive again
+    assert!(!workspace.op_exists(&second).unwrap());
+    assert!(!workspace.op_exists(&future).unwrap());
+}
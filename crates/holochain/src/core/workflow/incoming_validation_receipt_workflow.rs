@@ -0,0 +1,148 @@
+//! The workflow for validation receipts sent to us by the validators
+//! of the ops we have authored
+
+use super::error::WorkflowResult;
+use crate::conductor::interface::SignalBroadcaster;
+use crate::core::{
+    queue_consumer::OneshotWriter,
+    signal::SystemSignal,
+    state::{
+        dht_op_integration::AuthoredDhtOpsStore,
+        validation_receipts_db::{
+            SignedValidationReceipt, ValidationReceiptsBuf, ValidationResult,
+        },
+        workspace::{Workspace, WorkspaceResult},
+    },
+};
+use fallible_iterator::FallibleIterator;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_serialized_bytes::prelude::*;
+use holochain_state::{
+    buffer::{BufferedStore, KvBufFresh},
+    db::AUTHORED_DHT_OPS,
+    env::EnvironmentWrite,
+    error::DatabaseError,
+    fresh_reader,
+    prelude::*,
+};
+use holochain_types::cell::CellId;
+use tracing::*;
+
+#[cfg(test)]
+mod test;
+
+/// Store a validation receipt for one of our ops and update the op's receipt count.
+///
+/// Receipts which aren't signed by their validator or which are for ops we
+/// didn't author are dropped.
+/// A [SystemSignal] is sent when the op is rejected or when it has just
+/// reached the number of receipts it requires.
+#[instrument(skip(state_env, signal_tx, receipt))]
+pub async fn incoming_validation_receipt_workflow(
+    state_env: &EnvironmentWrite,
+    mut signal_tx: SignalBroadcaster,
+    cell_id: &CellId,
+    receipt: SerializedBytes,
+) -> WorkflowResult<()> {
+    let receipt = SignedValidationReceipt::try_from(receipt)?;
+    let validator = receipt.receipt.validator.clone();
+    let op_hash = receipt.receipt.dht_op_hash.clone();
+
+    // Check the receipt came from the validator it claims to
+    let valid_signature = validator
+        .verify_signature(&receipt.validator_signature, receipt.receipt.clone())
+        .await
+        .map_err(DatabaseError::from)?;
+    if !valid_signature {
+        warn!(
+            ?validator,
+            ?op_hash,
+            "Dropping receipt with an invalid signature"
+        );
+        return Ok(());
+    }
+
+    let mut workspace = IncomingValidationReceiptWorkspace::new(state_env.clone().into())?;
+
+    // Check this is an op we authored
+    let mut authored = match workspace.authored_dht_ops.get(&op_hash)? {
+        Some(authored) => authored,
+        None => {
+            warn!(
+                ?validator,
+                ?op_hash,
+                "Dropping receipt for an op we didn't author"
+            );
+            return Ok(());
+        }
+    };
+
+    // Ignore receipts we already have
+    let held = fresh_reader!(state_env, |r| workspace
+        .validation_receipts
+        .list_receipts(&r, &op_hash)?
+        .any(|held| Ok(held == receipt)))?;
+    if held {
+        return Ok(());
+    }
+
+    let validation_result = receipt.receipt.validation_result.clone();
+    let previous_count = authored.receipt_count;
+    if validation_result == ValidationResult::Valid {
+        authored.receipt_count += 1;
+    }
+    let receipt_count = authored.receipt_count;
+    let required_receipt_count = authored.required_receipt_count;
+    workspace.validation_receipts.add_if_unique(receipt)?;
+    workspace.authored_dht_ops.put(op_hash.clone(), authored)?;
+
+    // commit the workspace
+    let writer: OneshotWriter = state_env.clone().into();
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+
+    let cell_id = cell_id.clone();
+    match validation_result {
+        ValidationResult::Rejected => {
+            signal_tx.send_system(SystemSignal::OpRejected { cell_id, op_hash });
+        }
+        ValidationResult::Valid
+            if previous_count < required_receipt_count
+                && receipt_count >= required_receipt_count =>
+        {
+            signal_tx.send_system(SystemSignal::OpReceiptsReached {
+                cell_id,
+                op_hash,
+                receipt_count,
+            });
+        }
+        ValidationResult::Valid => (),
+    }
+
+    Ok(())
+}
+
+#[allow(missing_docs)]
+pub struct IncomingValidationReceiptWorkspace {
+    pub authored_dht_ops: AuthoredDhtOpsStore,
+    pub validation_receipts: ValidationReceiptsBuf,
+}
+
+impl Workspace for IncomingValidationReceiptWorkspace {
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> WorkspaceResult<()> {
+        self.authored_dht_ops.flush_to_txn_ref(writer)?;
+        self.validation_receipts.flush_to_txn_ref(writer)?;
+        Ok(())
+    }
+}
+
+impl IncomingValidationReceiptWorkspace {
+    pub fn new(env: EnvironmentRead) -> WorkspaceResult<Self> {
+        let db = env.get_db(&*AUTHORED_DHT_OPS)?;
+        let authored_dht_ops = KvBufFresh::new(env.clone(), db);
+        let validation_receipts = ValidationReceiptsBuf::new(&env)?;
+        Ok(Self {
+            authored_dht_ops,
+            validation_receipts,
+        })
+    }
+}
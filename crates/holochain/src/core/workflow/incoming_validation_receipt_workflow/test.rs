@@ -0,0 +1,134 @@
+use super::*;
+use crate::core::{
+    signal::Signal,
+    state::{dht_op_integration::AuthoredDhtOpsValue, validation_receipts_db::ValidationReceipt},
+};
+use crate::fixt::CreateLinkFixturator;
+use ::fixt::prelude::*;
+use holo_hash::{AgentPubKey, DhtOpHash};
+use holochain_keystore::{KeystoreSender, KeystoreSenderExt};
+use holochain_types::{
+    dht_op::{DhtOp, DhtOpHashed},
+    fixt::{CellIdFixturator, SignatureFixturator},
+    test_utils::fake_dht_op_hash,
+};
+use matches::assert_matches;
+use tokio::sync::broadcast;
+
+async fn receipt(
+    keystore: &KeystoreSender,
+    dht_op_hash: &DhtOpHash,
+    validation_result: ValidationResult,
+) -> SignedValidationReceipt {
+    let validator = keystore
+        .clone()
+        .generate_sign_keypair_from_pure_entropy()
+        .await
+        .unwrap();
+    ValidationReceipt {
+        dht_op_hash: dht_op_hash.clone(),
+        validation_result,
+        validator,
+    }
+    .sign(keystore)
+    .await
+    .unwrap()
+}
+
+fn receipt_count(env: &EnvironmentWrite, op_hash: &DhtOpHash) -> u32 {
+    let workspace = IncomingValidationReceiptWorkspace::new(env.clone().into()).unwrap();
+    workspace
+        .authored_dht_ops
+        .get(op_hash)
+        .unwrap()
+        .unwrap()
+        .receipt_count
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn receipts_are_verified_counted_and_signalled() {
+    let test_env = holochain_state::test_utils::test_cell_env();
+    let env = test_env.env();
+    let keystore = holochain_state::test_utils::test_keystore();
+    let cell_id = fixt!(CellId);
+    let (tx, mut rx) = broadcast::channel(10);
+    let signal_tx = SignalBroadcaster::new(vec![tx]);
+
+    // An authored op which needs two receipts
+    let op = DhtOp::RegisterAddLink(fixt!(Signature), fixt!(CreateLink));
+    let op_light = op.to_light();
+    let op_hash = DhtOpHashed::from_content_sync(op).into_hash();
+    {
+        let mut workspace = IncomingValidationReceiptWorkspace::new(env.clone().into()).unwrap();
+        let value = AuthoredDhtOpsValue {
+            required_receipt_count: 2,
+            ..AuthoredDhtOpsValue::from_light(op_light)
+        };
+        workspace
+            .authored_dht_ops
+            .put(op_hash.clone(), value)
+            .unwrap();
+        env.guard()
+            .with_commit(|writer| workspace.flush_to_txn(writer))
+            .unwrap();
+    }
+    let send = |receipt: SignedValidationReceipt| {
+        let env = env.clone();
+        let signal_tx = signal_tx.clone();
+        let cell_id = cell_id.clone();
+        async move {
+            incoming_validation_receipt_workflow(
+                &env,
+                signal_tx,
+                &cell_id,
+                receipt.try_into().unwrap(),
+            )
+            .await
+            .unwrap()
+        }
+    };
+
+    // A receipt signed by someone other than the validator is dropped
+    let mut forged = receipt(&keystore, &op_hash, ValidationResult::Valid).await;
+    let imposter: AgentPubKey = keystore
+        .clone()
+        .generate_sign_keypair_from_pure_entropy()
+        .await
+        .unwrap();
+    forged.validator_signature = imposter
+        .sign(&keystore, forged.receipt.clone())
+        .await
+        .unwrap();
+    send(forged).await;
+    assert_eq!(receipt_count(&env, &op_hash), 0);
+
+    // A receipt for an op we didn't author is dropped
+    send(receipt(&keystore, &fake_dht_op_hash(1), ValidationResult::Valid).await).await;
+    assert_eq!(receipt_count(&env, &op_hash), 0);
+
+    // Valid receipts are counted once each
+    let first = receipt(&keystore, &op_hash, ValidationResult::Valid).await;
+    send(first.clone()).await;
+    send(first).await;
+    assert_eq!(receipt_count(&env, &op_hash), 1);
+    assert!(rx.try_recv().is_err());
+
+    // Reaching the required count is signalled
+    send(receipt(&keystore, &op_hash, ValidationResult::Valid).await).await;
+    assert_eq!(receipt_count(&env, &op_hash), 2);
+    assert_matches!(
+        rx.try_recv(),
+        Ok(Signal::System(SystemSignal::OpReceiptsReached {
+            receipt_count: 2,
+            ..
+        }))
+    );
+
+    // Rejections are signalled and don't count
+    send(receipt(&keystore, &op_hash, ValidationResult::Rejected).await).await;
+    assert_eq!(receipt_count(&env, &op_hash), 2);
+    assert_matches!(
+        rx.try_recv(),
+        Ok(Signal::System(SystemSignal::OpRejected { .. }))
+    );
+}
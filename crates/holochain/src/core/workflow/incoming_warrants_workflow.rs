@@ -0,0 +1,147 @@
+//! The workflow for warrants published to us by the validators
+//! of an agent's activity
+
+use super::error::WorkflowResult;
+use crate::core::{
+    queue_consumer::OneshotWriter,
+    state::{
+        element_buf::ElementBuf,
+        metadata::{MetadataBuf, MetadataBufT},
+        workspace::{Workspace, WorkspaceResult},
+    },
+};
+use holo_hash::{AgentPubKey, HeaderHash};
+use holochain_state::{
+    buffer::BufferedStore,
+    env::{EnvironmentRead, EnvironmentWrite},
+    error::DatabaseResult,
+    prelude::*,
+};
+use holochain_types::warrant::{SignedWarrant, SignedWarrantExt, WarrantProof};
+use holochain_zome_types::Header;
+use tracing::*;
+
+#[cfg(test)]
+mod test;
+
+/// Store warrants about an agent whose activity we are an authority for.
+///
+/// Warrants which aren't signed by their author, which are about
+/// a different agent or whose proof we can't check against headers
+/// we hold are dropped.
+/// Invalid header warrants are only kept for headers we hold as rejected.
+#[instrument(skip(state_env, warrants))]
+pub async fn incoming_warrants_workflow(
+    state_env: &EnvironmentWrite,
+    agent: AgentPubKey,
+    warrants: Vec<SignedWarrant>,
+) -> WorkflowResult<()> {
+    let mut workspace = IncomingWarrantsWorkspace::new(state_env.clone().into())?;
+
+    for warrant in warrants {
+        if warrant.warrant.warrantee != agent {
+            warn!(?agent, ?warrant, "Dropping warrant about a different agent");
+            continue;
+        }
+        if let Err(e) = warrant.validate().await {
+            warn!(?agent, ?e, "Dropping warrant with an invalid signature");
+            continue;
+        }
+        let headers = match workspace.proof_headers(&warrant)? {
+            Some(headers) => headers,
+            None => {
+                warn!(
+                    ?agent,
+                    ?warrant,
+                    "Dropping warrant about headers we don't hold"
+                );
+                continue;
+            }
+        };
+        match warrant.verify_proof(&headers).await {
+            Ok(true) => (),
+            Ok(false) => {
+                warn!(?agent, ?warrant, "Dropping warrant with an invalid proof");
+                continue;
+            }
+            Err(e) => {
+                warn!(
+                    ?agent,
+                    ?e,
+                    "Dropping warrant whose proof couldn't be checked"
+                );
+                continue;
+            }
+        }
+        workspace.meta_vault.register_warrant(warrant);
+    }
+
+    // commit the workspace
+    let writer: OneshotWriter = state_env.clone().into();
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+
+    Ok(())
+}
+
+#[allow(missing_docs)]
+pub struct IncomingWarrantsWorkspace {
+    pub meta_vault: MetadataBuf,
+    pub element_vault: ElementBuf,
+    pub element_pending: ElementBuf<PendingPrefix>,
+    pub element_rejected: ElementBuf<RejectedPrefix>,
+}
+
+impl Workspace for IncomingWarrantsWorkspace {
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> WorkspaceResult<()> {
+        self.meta_vault.flush_to_txn_ref(writer)?;
+        Ok(())
+    }
+}
+
+impl IncomingWarrantsWorkspace {
+    pub fn new(env: EnvironmentRead) -> WorkspaceResult<Self> {
+        let meta_vault = MetadataBuf::vault(env.clone())?;
+        let element_vault = ElementBuf::vault(env.clone(), false)?;
+        let element_pending = ElementBuf::pending(env.clone())?;
+        let element_rejected = ElementBuf::rejected(env)?;
+        Ok(Self {
+            meta_vault,
+            element_vault,
+            element_pending,
+            element_rejected,
+        })
+    }
+
+    /// The headers a warrant's proof is about, if we hold all of them.
+    /// A header that failed validation must be held as rejected,
+    /// otherwise we'd take the warrant's word over our own validation.
+    fn proof_headers(&self, warrant: &SignedWarrant) -> DatabaseResult<Option<Vec<Header>>> {
+        let rejected_only = matches!(warrant.warrant.proof, WarrantProof::InvalidHeader { .. });
+        let mut headers = Vec::new();
+        for (hash, _) in warrant.warrant.proof.signed_headers() {
+            let header = if rejected_only {
+                self.element_rejected
+                    .get_header(hash)?
+                    .map(|h| h.header().clone())
+            } else {
+                self.get_header(hash)?
+            };
+            match header {
+                Some(header) => headers.push(header),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(headers))
+    }
+
+    fn get_header(&self, hash: &HeaderHash) -> DatabaseResult<Option<Header>> {
+        let header = match self.element_vault.get_header(hash)? {
+            Some(header) => Some(header),
+            None => match self.element_pending.get_header(hash)? {
+                Some(header) => Some(header),
+                None => self.element_rejected.get_header(hash)?,
+            },
+        };
+        Ok(header.map(|h| h.header().clone()))
+    }
+}
@@ -0,0 +1,152 @@
+use super::*;
+use ::fixt::prelude::*;
+use fallible_iterator::FallibleIterator;
+use holo_hash::fixt::HeaderHashFixturator;
+use holochain_keystore::{AgentPubKeyExt, KeystoreSender, KeystoreSenderExt};
+use holochain_state::fresh_reader_test;
+use holochain_types::{
+    element::{SignedHeaderHashed, SignedHeaderHashedExt},
+    fixt::*,
+    warrant::WarrantProof,
+    HeaderHashed,
+};
+
+async fn warrant(
+    keystore: &KeystoreSender,
+    header: &SignedHeaderHashed,
+    warrantee: &AgentPubKey,
+    author: &AgentPubKey,
+) -> SignedWarrant {
+    let proof = WarrantProof::InvalidHeader {
+        header_hash: header.header_address().clone(),
+        signature: header.signature().clone(),
+        reason: "invalid".into(),
+    };
+    SignedWarrant::new(keystore, proof, warrantee.clone(), author.clone())
+        .await
+        .unwrap()
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn warrants_are_verified_and_stored() {
+    let test_env = holochain_state::test_utils::test_cell_env();
+    let env = test_env.env();
+    let keystore = holochain_state::test_utils::test_keystore();
+    let author = keystore
+        .clone()
+        .generate_sign_keypair_from_pure_entropy()
+        .await
+        .unwrap();
+    let warrantee = keystore
+        .clone()
+        .generate_sign_keypair_from_pure_entropy()
+        .await
+        .unwrap();
+    let other = keystore
+        .clone()
+        .generate_sign_keypair_from_pure_entropy()
+        .await
+        .unwrap();
+
+    // A header by the warrantee that we rejected
+    let mut header = fixt!(Dna);
+    header.author = warrantee.clone();
+    let header = SignedHeaderHashed::new(
+        &keystore,
+        HeaderHashed::from_content_sync(Header::Dna(header)),
+    )
+    .await
+    .unwrap();
+    // A header by the warrantee that we validated
+    let mut valid_header = fixt!(CreateLink);
+    valid_header.author = warrantee.clone();
+    let valid_header = SignedHeaderHashed::new(
+        &keystore,
+        HeaderHashed::from_content_sync(Header::CreateLink(valid_header)),
+    )
+    .await
+    .unwrap();
+    let mut workspace = IncomingWarrantsWorkspace::new(env.clone().into()).unwrap();
+    workspace
+        .element_rejected
+        .put(header.clone(), None)
+        .unwrap();
+    workspace
+        .element_vault
+        .put(valid_header.clone(), None)
+        .unwrap();
+    env.guard()
+        .with_commit(|writer| {
+            workspace.element_rejected.flush_to_txn_ref(writer)?;
+            workspace.element_vault.flush_to_txn_ref(writer)
+        })
+        .unwrap();
+
+    let valid = warrant(&keystore, &header, &warrantee, &author).await;
+
+    // About a header we hold as valid
+    let not_rejected = warrant(&keystore, &valid_header, &warrantee, &author).await;
+
+    // The content no longer matches the signature
+    let mut forged = warrant(&keystore, &header, &warrantee, &author).await;
+    forged.warrant.proof = WarrantProof::InvalidHeader {
+        header_hash: header.header_address().clone(),
+        signature: header.signature().clone(),
+        reason: "forged".into(),
+    };
+
+    // About an agent we weren't sent warrants for
+    let wrong_agent = warrant(&keystore, &header, &other, &author).await;
+
+    // About a header we don't hold
+    let proof = WarrantProof::InvalidHeader {
+        header_hash: fixt!(HeaderHash),
+        signature: header.signature().clone(),
+        reason: "missing".into(),
+    };
+    let missing = SignedWarrant::new(&keystore, proof, warrantee.clone(), author.clone())
+        .await
+        .unwrap();
+
+    // The warrantee never signed the header this way
+    let proof = WarrantProof::InvalidHeader {
+        header_hash: header.header_address().clone(),
+        signature: other.sign(&keystore, header.header()).await.unwrap(),
+        reason: "unsigned".into(),
+    };
+    let unsigned = SignedWarrant::new(&keystore, proof, warrantee.clone(), author.clone())
+        .await
+        .unwrap();
+
+    incoming_warrants_workflow(
+        &env,
+        warrantee.clone(),
+        vec![
+            valid.clone(),
+            not_rejected,
+            forged,
+            wrong_agent,
+            missing,
+            unsigned,
+        ],
+    )
+    .await
+    .unwrap();
+
+    let workspace = IncomingWarrantsWorkspace::new(env.clone().into()).unwrap();
+    let stored: Vec<_> = fresh_reader_test!(env, |r| workspace
+        .meta_vault
+        .get_warrants(&r, &warrantee)
+        .unwrap()
+        .collect()
+        .unwrap());
+    assert_eq!(stored, vec![valid]);
+
+    let stored: Vec<SignedWarrant> = fresh_reader_test!(env, |r| workspace
+        .meta_vault
+        .get_warrants(&r, &other)
+        .unwrap()
+        .collect()
+        .unwrap());
+    assert!(stored.is_empty());
+}
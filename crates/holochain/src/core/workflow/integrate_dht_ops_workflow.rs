@@ -12,8 +12,9 @@ use crate::core::{
             IntegrationLimboValue,
         },
         element_buf::ElementBuf,
-        metadata::{MetadataBuf, MetadataBufT},
+        metadata::{ChainItemKey, MetadataBuf, MetadataBufT},
         validation_db::ValidationLimboStore,
+        validation_receipts_db::{ValidationReceipt, ValidationResult},
         workspace::{Workspace, WorkspaceResult},
     },
     validation::DhtOpOrder,
@@ -21,7 +22,8 @@ use crate::core::{
 };
 use error::WorkflowResult;
 use fallible_iterator::FallibleIterator;
-use holo_hash::{DhtOpHash, EntryHash, HeaderHash};
+use holo_hash::{AgentPubKey, DhtOpHash, EntryHash, HeaderHash};
+use holochain_p2p::{HolochainP2pCell, HolochainP2pCellT};
 use holochain_state::{
     buffer::BufferedStore,
     buffer::KvBufFresh,
@@ -38,7 +40,8 @@ use holochain_types::{
     Entry, EntryHashed, Timestamp,
 };
 use holochain_zome_types::{
-    element::ElementEntry, query::ChainHead, query::ChainStatus, signature::Signature,
+    element::ElementEntry, query::ChainFork, query::ChainHead, query::ChainStatus,
+    signature::Signature,
 };
 use holochain_zome_types::{element::SignedHeader, Header};
 use produce_dht_ops_workflow::dht_op_light::{
@@ -53,11 +56,12 @@ pub use disintegrate::*;
 mod disintegrate;
 mod tests;
 
-#[instrument(skip(workspace, writer, trigger_sys))]
+#[instrument(skip(workspace, writer, trigger_sys, network))]
 pub async fn integrate_dht_ops_workflow(
     mut workspace: IntegrateDhtOpsWorkspace,
     writer: OneshotWriter,
     trigger_sys: &mut TriggerSender,
+    network: &mut HolochainP2pCell,
 ) -> WorkflowResult<WorkComplete> {
     // one of many possible ways to access the env
     let env = workspace.elements.headers().env().clone();
@@ -85,6 +89,8 @@ pub async fn integrate_dht_ops_workflow(
     }
 
     let mut total_integrated: usize = 0;
+    // Receipts to send to the authors who asked for them
+    let mut receipts = Vec::new();
 
     // Try to process the queue over and over again, until we either exhaust
     // the queue, or we can no longer integrate anything in the queue.
@@ -101,10 +107,23 @@ pub async fn integrate_dht_ops_workflow(
                 value,
                 order,
             } = so;
+            let author = if value.send_receipt {
+                Some(op.header().author().clone())
+            } else {
+                None
+            };
             // Check validation status and put in correct dbs
             let outcome = integrate_single_dht_op(value.clone(), op, &mut workspace).await?;
             match outcome {
                 Outcome::Integrated(integrated) => {
+                    let validation_result = match integrated.validation_status {
+                        ValidationStatus::Valid => Some(ValidationResult::Valid),
+                        ValidationStatus::Rejected => Some(ValidationResult::Rejected),
+                        ValidationStatus::Abandoned => None,
+                    };
+                    if let (Some(author), Some(validation_result)) = (author, validation_result) {
+                        receipts.push((author, hash.clone(), validation_result));
+                    }
                     // TODO We could create a prefix for the integrated ops db
                     // and separate rejected ops from valid ops.
                     // Currently you need to check the IntegratedDhtOpsValue for
@@ -154,9 +173,37 @@ pub async fn integrate_dht_ops_workflow(
         trigger_sys.trigger();
     }
 
+    // let the authors know we hold their ops
+    for (author, dht_op_hash, validation_result) in receipts {
+        if let Err(e) = send_receipt(&env, network, author, dht_op_hash, validation_result).await {
+            warn!(?e, "Failed to send validation receipt");
+        }
+    }
+
     Ok(result)
 }
 
+/// Sign a validation receipt as this cell's agent and send it to the author.
+async fn send_receipt(
+    env: &EnvironmentRead,
+    network: &mut HolochainP2pCell,
+    author: AgentPubKey,
+    dht_op_hash: DhtOpHash,
+    validation_result: ValidationResult,
+) -> WorkflowResult<()> {
+    let receipt = ValidationReceipt {
+        dht_op_hash,
+        validation_result,
+        validator: network.from_agent(),
+    }
+    .sign(env.keystore())
+    .await?;
+    network
+        .send_validation_receipt(author, receipt.try_into()?)
+        .await?;
+    Ok(())
+}
+
 /// Integrate a single DhtOp to the stores based on the
 /// validation status.
 ///
@@ -167,30 +214,33 @@ async fn integrate_single_dht_op(
     op: DhtOp,
     workspace: &mut IntegrateDhtOpsWorkspace,
 ) -> WorkflowResult<Outcome> {
+    // Abandoned ops were abandoned because their dependencies
+    // never arrived so there is no point waiting for them here.
+    if let ValidationStatus::Abandoned = iv.validation_status {
+        update_abandoned_status(&op, &mut workspace.meta)?;
+        return Ok(integrate_abandoned(
+            iv,
+            op,
+            &mut workspace.element_abandoned,
+        )?);
+    }
     if op_dependencies_held(&op, workspace).await? {
         match iv.validation_status {
-            ValidationStatus::Valid => Ok(integrate_data_and_meta(
-                iv,
-                op,
-                &mut workspace.elements,
-                &mut workspace.meta,
-            )?),
+            ValidationStatus::Valid => {
+                update_fork_status(&op, &mut workspace.meta)?;
+                Ok(integrate_data_and_meta(
+                    iv,
+                    op,
+                    &mut workspace.elements,
+                    &mut workspace.meta,
+                )?)
+            }
             ValidationStatus::Rejected => {
                 update_activity_status(&op, &mut workspace.meta)?;
                 update_validation_status(&op, &mut workspace.meta)?;
                 Ok(integrate_data(iv, op, &mut workspace.element_rejected)?)
             }
-            ValidationStatus::Abandoned => {
-                // Throwing away abandoned ops
-                // TODO: keep abandoned ops but remove the entries
-                // and put them in a AbandonedPrefix db
-                let integrated = IntegratedDhtOpsValue {
-                    validation_status: iv.validation_status,
-                    op: iv.op,
-                    when_integrated: Timestamp::now(),
-                };
-                Ok(Outcome::Integrated(integrated))
-            }
+            ValidationStatus::Abandoned => unreachable!("Abandoned ops are integrated above"),
         }
     } else {
         debug!("deferring");
@@ -231,6 +281,36 @@ fn integrate_data<P: PrefixType>(
     Ok(Outcome::Integrated(integrated))
 }
 
+/// Integrate the header of an abandoned op.
+/// The entry is dropped because it was never validated.
+fn integrate_abandoned(
+    iv: IntegrationLimboValue,
+    op: DhtOp,
+    element_store: &mut ElementBuf<AbandonedPrefix>,
+) -> DhtOpConvertResult<Outcome> {
+    let (signature, header, _) = op.into_inner();
+    put_data(signature, header, None, element_store)?;
+    let integrated = IntegratedDhtOpsValue {
+        validation_status: iv.validation_status,
+        op: iv.op,
+        when_integrated: Timestamp::now(),
+    };
+    debug!("integrating abandoned");
+    Ok(Outcome::Integrated(integrated))
+}
+
+/// Abandoned element headers still need to be registered
+/// so the status can be served for a get call.
+fn update_abandoned_status(
+    op: &DhtOp,
+    meta_integrated: &mut impl MetadataBufT,
+) -> WorkflowResult<()> {
+    if let DhtOp::StoreElement(_, h, _) = op {
+        meta_integrated.register_abandoned_element_header(h)?;
+    }
+    Ok(())
+}
+
 /// Update the status of agent activity if an op
 /// is rejected by the agent authority.
 fn update_activity_status(
@@ -248,6 +328,35 @@ fn update_activity_status(
     Ok(())
 }
 
+/// Record a fork in the agent's activity if this valid header is at
+/// the same position on their chain as another valid header we hold.
+/// The earliest header we saw at this position is the first side of the fork.
+fn update_fork_status(op: &DhtOp, meta_integrated: &mut impl MetadataBufT) -> WorkflowResult<()> {
+    if let DhtOp::RegisterAgentActivity(_, h) = &op {
+        let header_hash = HeaderHash::with_data_sync(h);
+        let key = ChainItemKey::AgentStatusSequence(
+            h.author().clone(),
+            ValidationStatus::Valid,
+            h.header_seq(),
+        );
+        let first = fresh_reader!(meta_integrated.env(), |r| {
+            meta_integrated
+                .get_activity(&r, key)?
+                .filter(|thh| Ok(thh.header_hash != header_hash))
+                .min()
+        })?;
+        if let Some(first) = first {
+            let fork = ChainFork {
+                fork_seq: h.header_seq(),
+                first_header: first.header_hash,
+                second_header: header_hash,
+            };
+            meta_integrated.register_activity_status(h.author(), ChainStatus::Forked(fork))?;
+        }
+    }
+    Ok(())
+}
+
 /// Rejected headers still need to be stored in the metadata vault so
 /// they can be served for a get details call.
 fn update_validation_status(
@@ -539,6 +648,8 @@ pub struct IntegrateDhtOpsWorkspace {
     pub meta_pending: MetadataBuf<PendingPrefix>,
     pub element_rejected: ElementBuf<RejectedPrefix>,
     pub meta_rejected: MetadataBuf<RejectedPrefix>,
+    /// Headers of ops whose dependencies never resolved
+    pub element_abandoned: ElementBuf<AbandonedPrefix>,
     /// Ops to disintegrate
     pub to_disintegrate_pending: Vec<DhtOpLight>,
     /// READ ONLY
@@ -562,6 +673,7 @@ impl Workspace for IntegrateDhtOpsWorkspace {
         self.meta_pending.flush_to_txn_ref(writer)?;
         self.element_rejected.flush_to_txn_ref(writer)?;
         self.meta_rejected.flush_to_txn_ref(writer)?;
+        self.element_abandoned.flush_to_txn_ref(writer)?;
         Ok(())
     }
 }
@@ -584,7 +696,9 @@ impl IntegrateDhtOpsWorkspace {
         let meta_pending = MetadataBuf::pending(env.clone())?;
 
         let element_rejected = ElementBuf::rejected(env.clone())?;
-        let meta_rejected = MetadataBuf::rejected(env)?;
+        let meta_rejected = MetadataBuf::rejected(env.clone())?;
+
+        let element_abandoned = ElementBuf::abandoned(env)?;
 
         Ok(Self {
             integration_limbo,
@@ -595,6 +709,7 @@ impl IntegrateDhtOpsWorkspace {
             meta_pending,
             element_rejected,
             meta_rejected,
+            element_abandoned,
             validation_limbo,
             to_disintegrate_pending: Vec::new(),
         })
@@ -612,9 +727,8 @@ impl IntegrateDhtOpsWorkspace {
         Ok(self.integrated_dht_ops.contains(&hash)? || self.integration_limbo.contains(&hash)?)
     }
 
-    /// Create a cascade through the integrated and rejected stores
-    // TODO: Might need to add abandoned here but will need some
-    // thought as abandoned entries are not stored.
+    /// Create a cascade through the integrated and rejected stores.
+    /// Abandoned ops are not included as their entries are not stored.
     pub fn cascade(&self) -> Cascade<'_> {
         let integrated_data = DbPair {
             element: &self.elements,
@@ -221,6 +221,7 @@ impl Db {
                     let value = IntegrationLimboValue {
                         validation_status: ValidationStatus::Valid,
                         op: op.to_light(),
+                        send_receipt: false,
                     };
                     let res = workspace
                         .integration_limbo
@@ -477,6 +478,7 @@ impl Db {
                     let val = IntegrationLimboValue {
                         validation_status: ValidationStatus::Valid,
                         op: op.to_light(),
+                        send_receipt: false,
                     };
                     workspace
                         .integration_limbo
@@ -542,7 +544,9 @@ impl Db {
 async fn call_workflow<'env>(env: EnvironmentWrite) {
     let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
     let (mut qt, _rx) = TriggerSender::new();
-    integrate_dht_ops_workflow(workspace, env.clone().into(), &mut qt)
+    let test_network = test_network(None, None).await;
+    let mut cell_network = test_network.cell_network();
+    integrate_dht_ops_workflow(workspace, env.clone().into(), &mut qt, &mut cell_network)
         .await
         .unwrap();
 }
@@ -846,6 +850,86 @@ async fn test_ops_state() {
     }
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn test_abandoned_ops_keep_header_but_drop_entry() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let env_ref = env.guard();
+    let a = TestData::new().await;
+
+    let header: Header = a.original_header.clone().into();
+    let header_hash = HeaderHash::with_data_sync(&header);
+    let store_entry = DhtOp::StoreEntry(
+        a.signature.clone(),
+        a.original_header.clone(),
+        a.original_entry.clone().into(),
+    );
+    let store_element = DhtOp::StoreElement(
+        a.signature.clone(),
+        header.clone(),
+        Some(a.original_entry.clone().into()),
+    );
+    let ops = vec![store_entry, store_element];
+
+    // Put the ops in the integration limbo as abandoned
+    // with their data in the pending store
+    let pre_state = ops.iter().fold(Vec::new(), add_op_to_judged);
+    Db::set(pre_state, env.clone()).await;
+    {
+        let mut workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        for op in &ops {
+            let op_hash = DhtOpHashed::from_content_sync(op.clone()).into_hash();
+            let val = IntegrationLimboValue {
+                validation_status: ValidationStatus::Abandoned,
+                op: op.to_light(),
+                send_receipt: false,
+            };
+            workspace.integration_limbo.put(op_hash, val).unwrap();
+        }
+        env_ref
+            .with_commit(|writer| workspace.flush_to_txn_ref(writer))
+            .unwrap();
+    }
+
+    call_workflow(env.clone()).await;
+
+    let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    for op in &ops {
+        let op_hash = DhtOpHashed::from_content_sync(op.clone()).into_hash();
+        let integrated = workspace.integrated_dht_ops.get(&op_hash).unwrap().unwrap();
+        assert_eq!(integrated.validation_status, ValidationStatus::Abandoned);
+    }
+
+    // The header is kept but the entry is not
+    let element_abandoned = ElementBuf::abandoned(env.clone().into()).unwrap();
+    assert!(element_abandoned
+        .get_header(&header_hash)
+        .unwrap()
+        .is_some());
+    assert!(element_abandoned
+        .get_entry(&a.original_entry_hash)
+        .unwrap()
+        .is_none());
+
+    // Nothing is added to the integrated stores
+    assert!(workspace
+        .elements
+        .get_header(&header_hash)
+        .unwrap()
+        .is_none());
+    assert!(!workspace
+        .meta
+        .has_valid_registered_store_element(&header_hash)
+        .unwrap());
+
+    // But the status can still be served
+    assert!(workspace
+        .meta
+        .has_any_registered_store_element(&header_hash)
+        .unwrap());
+}
+
 /// Call the produce dht ops workflow
 async fn produce_dht_ops<'env>(env: EnvironmentWrite) {
     let (mut qt, _rx) = TriggerSender::new();
@@ -957,7 +1041,7 @@ async fn get_entry(env: EnvironmentWrite, entry_hash: EntryHash) -> Option<Entry
 
     let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
 
-    let input = GetInput::new((entry_hash.clone().into(), GetOptions));
+    let input = GetInput::new((entry_hash.clone().into(), GetOptions::default()));
 
     let output = {
         let mut host_access = fixt!(ZomeCallHostAccess);
@@ -1344,6 +1428,7 @@ mod slow_tests {
                 uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::Create.into()].into(),
+                rate_limits: Default::default(),
             },
             vec![TestWasm::Create.into()],
         )
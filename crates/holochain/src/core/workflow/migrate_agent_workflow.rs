@@ -0,0 +1,362 @@
+//! Moves an agent from one DNA to another by closing their source chain in
+//! the previous DNA and opening a new one in the next DNA.
+//!
+//! The `migrate_agent` callbacks of both DNAs are run before anything is
+//! written so that any zome on either side can veto the move.
+
+use super::{error::WorkflowResult, CallZomeWorkspace, CallZomeWorkspaceLock};
+use crate::core::{
+    queue_consumer::OneshotWriter,
+    ribosome::{
+        guest_callback::migrate_agent::{
+            MigrateAgentHostAccess, MigrateAgentInvocation, MigrateAgentResult,
+        },
+        RibosomeT,
+    },
+    state::workspace::Workspace,
+};
+use derive_more::Constructor;
+use holochain_types::dna::{DnaDef, DnaDefHashed};
+use holochain_zome_types::header::builder;
+use holochain_zome_types::migrate_agent::MigrateAgent;
+use holochain_zome_types::Header;
+use tracing::*;
+
+#[derive(Constructor, Debug)]
+pub struct MigrateAgentWorkflowArgs<Ribosome: RibosomeT> {
+    /// The DNA the agent is leaving
+    pub prev_dna_def: DnaDef,
+    pub prev_ribosome: Ribosome,
+    /// The DNA the agent is moving to
+    pub next_dna_def: DnaDef,
+    pub next_ribosome: Ribosome,
+}
+
+pub type MigrateAgentWorkspace = CallZomeWorkspace;
+
+/// Run the `migrate_agent` callbacks on both sides of the move and, if
+/// they all pass, write [CloseChain] to the previous chain and [OpenChain]
+/// to the next chain.
+///
+/// The next chain must already have had genesis run on it.
+///
+/// [CloseChain]: holochain_zome_types::header::CloseChain
+/// [OpenChain]: holochain_zome_types::header::OpenChain
+#[instrument(skip(prev_workspace, prev_writer, next_workspace, next_writer))]
+pub async fn migrate_agent_workflow<'env, Ribosome: RibosomeT>(
+    prev_workspace: MigrateAgentWorkspace,
+    prev_writer: OneshotWriter,
+    next_workspace: MigrateAgentWorkspace,
+    next_writer: OneshotWriter,
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<MigrateAgentResult> {
+    let prev_workspace_lock = CallZomeWorkspaceLock::new(prev_workspace);
+    let next_workspace_lock = CallZomeWorkspaceLock::new(next_workspace);
+    let result = migrate_agent_workflow_inner(
+        prev_workspace_lock.clone(),
+        next_workspace_lock.clone(),
+        args,
+    )
+    .await?;
+
+    // A vetoed migration leaves both chains untouched
+    if let MigrateAgentResult::Fail(..) = result {
+        return Ok(result);
+    }
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    // The next chain is opened before the previous one is closed so that
+    // failing in between leaves the agent on an open chain.
+    // Running the workflow again picks up from where it stopped.
+    {
+        let mut guard = next_workspace_lock.write().await;
+        let workspace: &mut CallZomeWorkspace = &mut guard;
+        next_writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+    }
+    {
+        let mut guard = prev_workspace_lock.write().await;
+        let workspace: &mut CallZomeWorkspace = &mut guard;
+        prev_writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+    }
+    Ok(result)
+}
+
+async fn migrate_agent_workflow_inner<'env, Ribosome: RibosomeT>(
+    prev_workspace: CallZomeWorkspaceLock,
+    next_workspace: CallZomeWorkspaceLock,
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<MigrateAgentResult> {
+    let MigrateAgentWorkflowArgs {
+        prev_dna_def,
+        prev_ribosome,
+        next_dna_def,
+        next_ribosome,
+    } = args;
+    let (prev_dna_def, prev_dna_hash) = DnaDefHashed::from_content(prev_dna_def).await.into_inner();
+    let (next_dna_def, next_dna_hash) = DnaDefHashed::from_content(next_dna_def).await.into_inner();
+
+    // An earlier run already closed the previous chain so there is nothing left to write
+    if chain_head_matches(
+        &prev_workspace,
+        |h| matches!(h, Header::CloseChain(close) if close.new_dna_hash == next_dna_hash),
+    )
+    .await?
+    {
+        return Ok(MigrateAgentResult::Pass);
+    }
+
+    // Ask the zomes being left if the agent may go
+    let result = prev_ribosome.run_migrate_agent(
+        MigrateAgentHostAccess::new(prev_workspace.clone()),
+        MigrateAgentInvocation::new(prev_dna_def, MigrateAgent::Close),
+    )?;
+    if let MigrateAgentResult::Fail(..) = result {
+        return Ok(result);
+    }
+
+    // Ask the zomes being joined if the agent may come
+    let result = next_ribosome.run_migrate_agent(
+        MigrateAgentHostAccess::new(next_workspace.clone()),
+        MigrateAgentInvocation::new(next_dna_def, MigrateAgent::Open),
+    )?;
+    if let MigrateAgentResult::Fail(..) = result {
+        return Ok(result);
+    }
+
+    // Point the old chain forward to the new DNA
+    prev_workspace
+        .write()
+        .await
+        .source_chain
+        .put(
+            builder::CloseChain {
+                new_dna_hash: next_dna_hash,
+            },
+            None,
+        )
+        .await?;
+
+    // Point the new chain back to the old DNA unless an earlier run already did
+    if !chain_head_matches(
+        &next_workspace,
+        |h| matches!(h, Header::OpenChain(open) if open.prev_dna_hash == prev_dna_hash),
+    )
+    .await?
+    {
+        next_workspace
+            .write()
+            .await
+            .source_chain
+            .put(builder::OpenChain { prev_dna_hash }, None)
+            .await?;
+    }
+
+    Ok(result)
+}
+
+/// Check the header at the head of a workspace's source chain
+async fn chain_head_matches(
+    workspace: &CallZomeWorkspaceLock,
+    f: impl FnOnce(&Header) -> bool,
+) -> WorkflowResult<bool> {
+    let guard = workspace.read().await;
+    let head = guard.source_chain.chain_head()?.clone();
+    Ok(guard
+        .source_chain
+        .get_header(&head)?
+        .map(|h| f(h.header()))
+        .unwrap_or(false))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::core::ribosome::MockRibosomeT;
+    use crate::core::workflow::fake_genesis;
+    use crate::fixt::DnaDefFixturator;
+    use ::fixt::prelude::*;
+    use fixt::Unpredictable;
+    use holo_hash::HasHash;
+    use holochain_state::test_utils::test_cell_env;
+    use matches::assert_matches;
+
+    async fn genesis_workspace_lock(
+        env: holochain_state::env::EnvironmentWrite,
+    ) -> CallZomeWorkspaceLock {
+        let mut workspace = CallZomeWorkspace::new(env.into()).unwrap();
+        fake_genesis(&mut workspace.source_chain).await.unwrap();
+        CallZomeWorkspaceLock::new(workspace)
+    }
+
+    fn ribosome(result: fn() -> MigrateAgentResult) -> MockRibosomeT {
+        let mut ribosome = MockRibosomeT::new();
+        ribosome
+            .expect_run_migrate_agent()
+            .returning(move |_, _| Ok(result()));
+        ribosome
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn closes_and_opens_chains() {
+        let prev_env = test_cell_env();
+        let next_env = test_cell_env();
+        let prev_workspace = genesis_workspace_lock(prev_env.env()).await;
+        let next_workspace = genesis_workspace_lock(next_env.env()).await;
+
+        let prev_dna_def = DnaDefFixturator::new(Unpredictable).next().unwrap();
+        let next_dna_def = DnaDefFixturator::new(Unpredictable).next().unwrap();
+        let prev_dna_hash = DnaDefHashed::from_content(prev_dna_def.clone())
+            .await
+            .into_hash();
+        let next_dna_hash = DnaDefHashed::from_content(next_dna_def.clone())
+            .await
+            .into_hash();
+
+        let args = MigrateAgentWorkflowArgs {
+            prev_dna_def,
+            prev_ribosome: ribosome(|| MigrateAgentResult::Pass),
+            next_dna_def,
+            next_ribosome: ribosome(|| MigrateAgentResult::Pass),
+        };
+        let result =
+            migrate_agent_workflow_inner(prev_workspace.clone(), next_workspace.clone(), args)
+                .await
+                .unwrap();
+        assert_eq!(result, MigrateAgentResult::Pass);
+
+        // The old chain points forward to the new dna
+        assert_matches!(
+            prev_workspace
+                .read()
+                .await
+                .source_chain
+                .get_at_index(3)
+                .unwrap()
+                .unwrap()
+                .header(),
+            Header::CloseChain(close) if close.new_dna_hash == next_dna_hash
+        );
+        // The new chain points back to the old dna
+        assert_matches!(
+            next_workspace
+                .read()
+                .await
+                .source_chain
+                .get_at_index(3)
+                .unwrap()
+                .unwrap()
+                .header(),
+            Header::OpenChain(open) if open.prev_dna_hash == prev_dna_hash
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn resumes_interrupted_migration() {
+        let prev_env = test_cell_env();
+        let next_env = test_cell_env();
+        let prev_workspace = genesis_workspace_lock(prev_env.env()).await;
+        let next_workspace = genesis_workspace_lock(next_env.env()).await;
+
+        let prev_dna_def = DnaDefFixturator::new(Unpredictable).next().unwrap();
+        let next_dna_def = DnaDefFixturator::new(Unpredictable).next().unwrap();
+        let prev_dna_hash = DnaDefHashed::from_content(prev_dna_def.clone())
+            .await
+            .into_hash();
+        let args = || MigrateAgentWorkflowArgs {
+            prev_dna_def: prev_dna_def.clone(),
+            prev_ribosome: ribosome(|| MigrateAgentResult::Pass),
+            next_dna_def: next_dna_def.clone(),
+            next_ribosome: ribosome(|| MigrateAgentResult::Pass),
+        };
+
+        // The next chain was opened but the previous chain was never closed
+        next_workspace
+            .write()
+            .await
+            .source_chain
+            .put(
+                builder::OpenChain {
+                    prev_dna_hash: prev_dna_hash.clone(),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let result =
+            migrate_agent_workflow_inner(prev_workspace.clone(), next_workspace.clone(), args())
+                .await
+                .unwrap();
+        assert_eq!(result, MigrateAgentResult::Pass);
+        assert_matches!(
+            prev_workspace
+                .read()
+                .await
+                .source_chain
+                .get_at_index(3)
+                .unwrap()
+                .unwrap()
+                .header(),
+            Header::CloseChain(_)
+        );
+
+        // Running again once both chains are written adds nothing
+        let result =
+            migrate_agent_workflow_inner(prev_workspace.clone(), next_workspace.clone(), args())
+                .await
+                .unwrap();
+        assert_eq!(result, MigrateAgentResult::Pass);
+        assert!(prev_workspace
+            .read()
+            .await
+            .source_chain
+            .get_at_index(4)
+            .unwrap()
+            .is_none());
+        assert!(next_workspace
+            .read()
+            .await
+            .source_chain
+            .get_at_index(4)
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn veto_leaves_chains_untouched() {
+        let prev_env = test_cell_env();
+        let next_env = test_cell_env();
+        let prev_workspace = genesis_workspace_lock(prev_env.env()).await;
+        let next_workspace = genesis_workspace_lock(next_env.env()).await;
+
+        let args = MigrateAgentWorkflowArgs {
+            prev_dna_def: DnaDefFixturator::new(Unpredictable).next().unwrap(),
+            prev_ribosome: ribosome(|| MigrateAgentResult::Pass),
+            next_dna_def: DnaDefFixturator::new(Unpredictable).next().unwrap(),
+            next_ribosome: ribosome(|| {
+                MigrateAgentResult::Fail("foo".into(), "not welcome".into())
+            }),
+        };
+        let result =
+            migrate_agent_workflow_inner(prev_workspace.clone(), next_workspace.clone(), args)
+                .await
+                .unwrap();
+        assert_matches!(result, MigrateAgentResult::Fail(..));
+
+        assert!(prev_workspace
+            .read()
+            .await
+            .source_chain
+            .get_at_index(3)
+            .unwrap()
+            .is_none());
+        assert!(next_workspace
+            .read()
+            .await
+            .source_chain
+            .get_at_index(3)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -44,11 +44,7 @@ async fn produce_dht_ops_workflow_inner(
         for op in ops {
             let (op, hash) = DhtOpHashed::from_content_sync(op).into_inner();
             debug!(?hash, ?op);
-            let value = AuthoredDhtOpsValue {
-                op: op.to_light(),
-                receipt_count: 0,
-                last_publish_time: None,
-            };
+            let value = AuthoredDhtOpsValue::from_light(op.to_light());
             workspace.authored_dht_ops.put(hash, value)?;
         }
         // Mark the dht op as complete
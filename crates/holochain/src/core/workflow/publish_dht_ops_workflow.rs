@@ -38,10 +38,9 @@ use std::collections::HashMap;
 use std::time;
 use tracing::*;
 
-/// Default redundancy factor for validation receipts
-// TODO: Pull this from the wasm entry def and only use this if it's missing
+/// Default redundancy factor for validation receipts.
+/// The health check replaces this with the entry def's `required_validations`.
 // TODO: Put a default in the DnaBundle
-// TODO: build zome_types/entry_def map to get the (AppEntryType map to entry def)
 pub const DEFAULT_RECEIPT_BUNDLE_SIZE: u32 = 5;
 
 /// Don't publish a DhtOp more than once during this interval.
@@ -97,7 +96,7 @@ pub async fn publish_dht_ops_workflow_inner(
         .authored()
         .iter(&r)?
         .filter_map(|(k, mut r)| {
-            Ok(if r.receipt_count < DEFAULT_RECEIPT_BUNDLE_SIZE {
+            Ok(if r.receipt_count < r.required_receipt_count {
                 let needs_publish = r
                     .last_publish_time
                     .map(|last| {
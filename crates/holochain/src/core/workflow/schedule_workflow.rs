@@ -0,0 +1,192 @@
+//! # Schedule Workflow
+//!
+//! Calls the zome functions which zomes have asked to be called later via the
+//! `schedule` host function, once they become due.
+//!
+//! Due functions are removed from the schedule (or rescheduled if they repeat)
+//! and that change is committed _before_ any of them are called, so a function
+//! is called at most once each time it becomes due, even if the conductor stops
+//! part way through this workflow.
+
+use super::error::WorkflowResult;
+use crate::conductor::api::CellConductorApiT;
+use crate::core::queue_consumer::OneshotWriter;
+use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::state::{
+    schedule::{ScheduleBuf, ScheduledFn},
+    workspace::{Workspace, WorkspaceResult},
+};
+use holochain_state::{buffer::BufferedStore, fresh_reader, prelude::*};
+use holochain_types::Timestamp;
+use holochain_zome_types::{ExternInput, ZomeCallResponse};
+use tracing::*;
+
+/// Run every scheduled function which is due at `now`.
+/// Returns the time the next function becomes due, if any are scheduled.
+///
+/// `now` is passed in rather than read from the system clock
+/// so the schedule can be driven by a mock clock in tests.
+#[instrument(skip(workspace, writer, conductor_api))]
+pub async fn schedule_workflow<C: CellConductorApiT>(
+    mut workspace: ScheduleWorkspace,
+    writer: OneshotWriter,
+    conductor_api: &C,
+    now: Timestamp,
+) -> WorkflowResult<Option<Timestamp>> {
+    let due = schedule_workflow_inner(&mut workspace, now)?;
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    // commit the workspace
+    let env = workspace.schedules.env().clone();
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+
+    // call the due functions
+    let cell_id = conductor_api.cell_id().clone();
+    for scheduled_fn in due {
+        let zome_name = scheduled_fn.zome_name.clone();
+        let fn_name = scheduled_fn.fn_name.clone();
+        let invocation = ZomeCallInvocation {
+            cell_id: cell_id.clone(),
+            zome_name: scheduled_fn.zome_name,
+            cap: None,
+            fn_name: scheduled_fn.fn_name,
+            payload: ExternInput::new(scheduled_fn.payload),
+            // scheduled functions are always called as the agent that owns the cell
+            provenance: cell_id.agent_pubkey().clone(),
+        };
+        match conductor_api.call_zome(&cell_id, invocation).await {
+            Ok(Ok(ZomeCallResponse::Ok(_))) => (),
+            result => warn!(
+                ?zome_name,
+                ?fn_name,
+                ?result,
+                "Scheduled zome function did not succeed"
+            ),
+        }
+    }
+
+    // the called functions may have scheduled more work
+    let next_due = fresh_reader!(env, |r| ScheduleBuf::new(env.clone())?.next_due(&r))?;
+    Ok(next_due)
+}
+
+fn schedule_workflow_inner(
+    workspace: &mut ScheduleWorkspace,
+    now: Timestamp,
+) -> WorkflowResult<Vec<ScheduledFn>> {
+    let due = fresh_reader!(workspace.schedules.env(), |r| workspace
+        .schedules
+        .due(&r, now))?;
+    for scheduled_fn in due.iter() {
+        workspace.schedules.unschedule(scheduled_fn)?;
+        if let Some(next) = scheduled_fn.next(now) {
+            workspace.schedules.schedule(next)?;
+        }
+    }
+    Ok(due)
+}
+
+/// The workspace for the schedule workflow
+pub struct ScheduleWorkspace {
+    /// The functions waiting to be called
+    pub schedules: ScheduleBuf,
+}
+
+impl ScheduleWorkspace {
+    /// Constructor
+    pub fn new(env: EnvironmentRead) -> WorkspaceResult<Self> {
+        Ok(Self {
+            schedules: ScheduleBuf::new(env)?,
+        })
+    }
+}
+
+impl Workspace for ScheduleWorkspace {
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> WorkspaceResult<()> {
+        self.schedules.flush_to_txn_ref(writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conductor::api::MockCellConductorApi;
+    use crate::core::state::schedule::tests::scheduled_fn;
+    use crate::core::workflow::ZomeCallInvocationResult;
+    use ::fixt::prelude::*;
+    use holochain_serialized_bytes::prelude::*;
+    use holochain_state::{env::WriteManager, test_utils::test_cell_env};
+    use holochain_types::fixt::CellIdFixturator;
+    use holochain_zome_types::ExternOutput;
+    use std::time::Duration;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn calls_due_fns_and_reschedules_repeats() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+
+        {
+            let mut workspace = ScheduleWorkspace::new(env.clone().into()).unwrap();
+            workspace
+                .schedules
+                .schedule(scheduled_fn("once", Timestamp(100, 0), None))
+                .unwrap();
+            workspace
+                .schedules
+                .schedule(scheduled_fn(
+                    "repeat",
+                    Timestamp(100, 0),
+                    Some(Duration::from_secs(60)),
+                ))
+                .unwrap();
+            workspace
+                .schedules
+                .schedule(scheduled_fn("later", Timestamp(1000, 0), None))
+                .unwrap();
+            env.guard()
+                .with_commit(|writer| workspace.flush_to_txn(writer))
+                .unwrap();
+        }
+
+        let cell_id = fixt!(CellId);
+        let mut conductor_api = MockCellConductorApi::new();
+        conductor_api.expect_cell_id().return_const(cell_id.clone());
+        conductor_api
+            .expect_sync_call_zome()
+            .times(2)
+            .returning(move |_, invocation| {
+                assert_eq!(invocation.cap, None);
+                assert_eq!(&invocation.provenance, cell_id.agent_pubkey());
+                let response: ZomeCallInvocationResult = Ok(ZomeCallResponse::Ok(
+                    ExternOutput::new(().try_into().unwrap()),
+                ));
+                Ok(response)
+            });
+
+        // Nothing is due yet
+        let workspace = ScheduleWorkspace::new(env.clone().into()).unwrap();
+        let next_due = schedule_workflow(
+            workspace,
+            env.clone().into(),
+            &conductor_api,
+            Timestamp(50, 0),
+        )
+        .await
+        .unwrap();
+        assert_eq!(next_due, Some(Timestamp(100, 0)));
+
+        // Both fns at 100 are called and the repeating fn is rescheduled
+        let workspace = ScheduleWorkspace::new(env.clone().into()).unwrap();
+        let next_due = schedule_workflow(
+            workspace,
+            env.clone().into(),
+            &conductor_api,
+            Timestamp(110, 0),
+        )
+        .await
+        .unwrap();
+        assert_eq!(next_due, Some(Timestamp(170, 0)));
+    }
+}
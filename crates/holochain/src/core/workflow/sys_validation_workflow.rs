@@ -32,11 +32,12 @@ use holochain_state::{
 };
 use holochain_types::{
     dht_op::DhtOp, header::NewEntryHeaderRef, test_utils::which_agent, validate::ValidationStatus,
-    Entry, Timestamp,
+    warrant::WarrantProof, Entry, Timestamp,
 };
 use holochain_zome_types::{entry_def::EntryVisibility, signature::Signature};
 use holochain_zome_types::{
     header::{CreateLink, Delete, DeleteLink, EntryType, Update},
+    query::ChainFork,
     Header,
 };
 use std::{collections::BinaryHeap, convert::TryFrom, convert::TryInto};
@@ -165,6 +166,7 @@ async fn sys_validation_workflow_inner(
                 let iv = IntegrationLimboValue {
                     op: vlv.op,
                     validation_status: ValidationStatus::Valid,
+                    send_receipt: vlv.send_receipt,
                 };
                 workspace.put_int_limbo(op_hash, iv)?;
             }
@@ -178,17 +180,26 @@ async fn sys_validation_workflow_inner(
                 // We need to be holding the dependency because
                 // we were meant to get a StoreElement or StoreEntry or
                 // RegisterAgentActivity or RegisterAddLink.
-                vlv.status = ValidationLimboStatus::AwaitingSysDeps(missing_dep);
-                workspace.put_val_limbo(op_hash, vlv)?;
+                if vlv.should_abandon() {
+                    workspace.abandon(op_hash, vlv)?;
+                } else {
+                    vlv.status = ValidationLimboStatus::AwaitingSysDeps(missing_dep);
+                    workspace.put_val_limbo(op_hash, vlv)?;
+                }
             }
             Outcome::MissingDhtDep => {
-                vlv.status = ValidationLimboStatus::Pending;
-                workspace.put_val_limbo(op_hash, vlv)?;
+                if vlv.should_abandon() {
+                    workspace.abandon(op_hash, vlv)?;
+                } else {
+                    vlv.status = ValidationLimboStatus::Pending;
+                    workspace.put_val_limbo(op_hash, vlv)?;
+                }
             }
             Outcome::Rejected => {
                 let iv = IntegrationLimboValue {
                     op: vlv.op,
                     validation_status: ValidationStatus::Rejected,
+                    send_receipt: vlv.send_receipt,
                 };
                 workspace.put_int_limbo(op_hash, iv)?;
             }
@@ -207,7 +218,7 @@ async fn validate_op(
     match validate_op_inner(
         op,
         workspace,
-        network,
+        network.clone(),
         conductor_api,
         incoming_dht_ops_sender,
     )
@@ -228,12 +239,60 @@ async fn validate_op(
                 error = ?e,
                 error_msg = %e
             );
-            Ok(handle_failed(e))
+            // A header that isn't signed by its author
+            // is no proof the author did anything wrong
+            let reason = match &e {
+                ValidationOutcome::VerifySignature(_, _) => None,
+                _ => Some(e.to_string()),
+            };
+            let outcome = handle_failed(e);
+            if let (Outcome::Rejected, Some(reason)) = (&outcome, reason) {
+                let header = op.header();
+                let proof = WarrantProof::InvalidHeader {
+                    header_hash: HeaderHash::with_data_sync(&header),
+                    signature: op.signature().clone(),
+                    reason,
+                };
+                let mut network = network;
+                issue_warrant(
+                    proof,
+                    header.author().clone(),
+                    &mut workspace.meta_cache,
+                    conductor_api,
+                    &mut network,
+                )
+                .await;
+            }
+            Ok(outcome)
         }
         Err(e) => Err(e.into()),
     }
 }
 
+/// The proof of a fork from the header with this signature, which is the
+/// fork's second header, if the first header is held and signed by the same author
+async fn chain_fork_proof(
+    fork: ChainFork,
+    signature: &Signature,
+    workspace: &SysValidationWorkspace,
+) -> SysValidationResult<Option<WarrantProof>> {
+    let first = match workspace.element_vault.get_header(&fork.first_header)? {
+        Some(first) => first,
+        None => match workspace.element_pending.get_header(&fork.first_header)? {
+            Some(first) => first,
+            None => return Ok(None),
+        },
+    };
+    if !verify_header_signature(first.signature(), first.header()).await? {
+        return Ok(None);
+    }
+    Ok(Some(WarrantProof::ChainFork {
+        fork_seq: fork.fork_seq,
+        first_header: (fork.first_header, first.signature().clone()),
+        second_header: (fork.second_header, signature.clone()),
+    }))
+}
+
 /// For now errors result in an outcome but in the future
 /// we might find it useful to include the reason something
 /// was rejected etc.
@@ -259,6 +318,10 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
         }
         ValidationOutcome::PrevHeaderError(_) => Rejected,
         ValidationOutcome::PrivateEntry => Rejected,
+        ValidationOutcome::PublishTimeOutOfRange(_) => {
+            unreachable!("Ops dated in the future are dropped before sys validation")
+        }
+        ValidationOutcome::RateLimited(_, _) => Rejected,
         ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected,
         ValidationOutcome::VerifySignature(_, _) => Rejected,
         ValidationOutcome::ZomeId(_) => Rejected,
@@ -303,10 +366,32 @@ async fn validate_op_inner(
             store_element(&header, workspace, network).await?;
             Ok(())
         }
-        DhtOp::RegisterAgentActivity(_, header) => {
-            register_agent_activity(header, workspace, network.clone(), incoming_dht_ops_sender)
-                .await?;
-            store_element(header, workspace, network).await?;
+        DhtOp::RegisterAgentActivity(signature, header) => {
+            register_agent_activity(
+                header,
+                workspace,
+                network.clone(),
+                conductor_api,
+                incoming_dht_ops_sender,
+            )
+            .await?;
+            store_element(header, workspace, network.clone()).await?;
+            // The fork is recorded in the agent's activity when both
+            // headers are integrated as valid, but the signed headers
+            // are already proof enough for a warrant
+            if let Some(fork) = check_chain_rollback(&header, &workspace).await? {
+                if let Some(proof) = chain_fork_proof(fork, signature, &workspace).await? {
+                    let mut network = network;
+                    issue_warrant(
+                        proof,
+                        header.author().clone(),
+                        &mut workspace.meta_cache,
+                        conductor_api,
+                        &mut network,
+                    )
+                    .await;
+                }
+            }
             Ok(())
         }
         DhtOp::RegisterUpdatedContent(_, header, entry) => {
@@ -458,6 +543,7 @@ async fn register_agent_activity(
     header: &Header,
     workspace: &mut SysValidationWorkspace,
     network: HolochainP2pCell,
+    conductor_api: &impl CellConductorApiT,
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
@@ -476,7 +562,12 @@ async fn register_agent_activity(
         )
         .await?;
     }
-    check_chain_rollback(&header, &workspace).await?;
+    // We hold the author's whole chain so this is the only place
+    // the rate limits can be checked deterministically
+    let dna_file = { conductor_api.get_this_dna().await };
+    let dna_file =
+        dna_file.ok_or_else(|| SysValidationError::DnaMissing(conductor_api.cell_id().clone()))?;
+    check_activity_rate(&header, &dna_file.dna().rate_limits, workspace)?;
     Ok(())
 }
 
@@ -785,6 +876,18 @@ impl SysValidationWorkspace {
         Ok(())
     }
 
+    /// Give up waiting on the dependencies of this op and
+    /// send it to integration as abandoned
+    fn abandon(&mut self, hash: DhtOpHash, vlv: ValidationLimboValue) -> WorkflowResult<()> {
+        debug!(msg = "Abandoning op", ?hash, num_tries = vlv.num_tries);
+        let iv = IntegrationLimboValue {
+            op: vlv.op,
+            validation_status: ValidationStatus::Abandoned,
+            send_receipt: vlv.send_receipt,
+        };
+        self.put_int_limbo(hash, iv)
+    }
+
     pub fn network_only_cascade<Network: HolochainP2pCellT + Clone + Send + 'static>(
         &mut self,
         network: Network,
@@ -863,6 +966,7 @@ impl TryFrom<&CallZomeWorkspace> for SysValidationWorkspace {
             meta_integrated,
             element_cache,
             meta_cache,
+            ..
         } = call_zome;
         let mut sys_val = Self::new(call_zome.env().clone())?;
         sys_val.element_authored = source_chain.elements().into();
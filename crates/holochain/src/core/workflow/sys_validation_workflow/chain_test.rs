@@ -8,22 +8,21 @@ use holochain_types::{
     dna::{DnaDef, DnaFile},
 };
 use holochain_wasm_test_utils::TestWasm;
-use holochain_zome_types::test_utils::fake_agent_pubkey_1;
+use holochain_zome_types::{query::ChainStatus, test_utils::fake_agent_pubkey_1};
+use matches::assert_matches;
 
 use super::*;
 use crate::{
-    conductor::ConductorHandle, core::state::source_chain::SourceChain, test_utils::setup_app,
+    conductor::ConductorHandle, core::state::metadata::MetadataBufT,
+    core::state::source_chain::SourceChain, test_utils::setup_app,
     test_utils::wait_for_integration,
 };
 use ::fixt::prelude::*;
 use holochain_zome_types::fixt::*;
 
-/// Unfortunately this test doesn't do anything yet because
-/// failing a chain validation is just a log error so the only way to
-/// verify this works is to run this with logging and check it outputs
-/// use `RUST_LOG=[agent_activity]=warn`
+/// Two valid headers at the same position on a chain are
+/// recorded as a fork once they are both integrated
 #[tokio::test(threaded_scheduler)]
-#[ignore = "TODO: complete when chain validation returns actual error"]
 async fn sys_validation_agent_activity_test() {
     observability::test_run().ok();
 
@@ -33,6 +32,7 @@ async fn sys_validation_agent_activity_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            rate_limits: Default::default(),
         },
         vec![TestWasm::Create.into()],
     )
@@ -126,14 +126,20 @@ async fn run_test(alice_cell_id: CellId, handle: ConductorHandle) {
         sys_validation_trigger.clone(),
         ops,
         None,
+        false,
+        None,
     )
     .await
     .unwrap();
 
     wait_for_integration(&alice_env, 7 + 2, 100, Duration::from_millis(100)).await;
 
-    // Check you don't see any warning output
-    // TODO: When we add invalid chains put a real check here
+    // A valid chain hasn't forked
+    let meta_vault = MetadataBuf::vault(alice_env.clone().into()).unwrap();
+    let status = meta_vault
+        .get_activity_status(alice_cell_id.agent_pubkey())
+        .unwrap();
+    assert!(!matches!(status, Some(ChainStatus::Forked(_))));
 
     // set valid prev header chain
     let last_hash = HeaderHash::with_data_sync(&Header::Create(h2.clone()));
@@ -182,12 +188,21 @@ async fn run_test(alice_cell_id: CellId, handle: ConductorHandle) {
         sys_validation_trigger,
         ops,
         None,
+        false,
+        None,
     )
     .await
     .unwrap();
 
     wait_for_integration(&alice_env, 9 + 2, 100, Duration::from_millis(100)).await;
 
-    // Check you **do** see any warning output
-    // TODO: When we add invalid chains put a real check here
+    // Both headers at position 5 are valid so the chain has forked
+    let meta_vault = MetadataBuf::vault(alice_env.clone().into()).unwrap();
+    let status = meta_vault
+        .get_activity_status(alice_cell_id.agent_pubkey())
+        .unwrap();
+    assert_matches!(
+        status,
+        Some(ChainStatus::Forked(ChainFork { fork_seq: 5, .. }))
+    );
 }
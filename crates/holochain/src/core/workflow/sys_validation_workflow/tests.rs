@@ -35,6 +35,7 @@ async fn sys_validation_workflow_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            rate_limits: Default::default(),
         },
         vec![TestWasm::Create.into()],
     )
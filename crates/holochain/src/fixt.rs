@@ -6,6 +6,8 @@ use crate::conductor::{
     interface::SignalBroadcaster,
 };
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckHostAccess;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckInvocation;
 use crate::core::ribosome::guest_callback::init::InitHostAccess;
 use crate::core::ribosome::guest_callback::init::InitInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess;
@@ -38,6 +40,7 @@ use holo_hash::HeaderHash;
 use holo_hash::WasmHash;
 use holochain_keystore::keystore_actor::KeystoreSender;
 use holochain_p2p::HolochainP2pCellFixturator;
+use holochain_state::env::EnvironmentWrite;
 use holochain_state::test_utils::test_keystore;
 use holochain_types::dna::zome::Zome;
 use holochain_types::dna::DnaFile;
@@ -280,6 +283,26 @@ fixturator!(
     };
 );
 
+fixturator!(
+    EnvironmentWrite;
+    curve Empty {
+        // XXX: As with the CallZomeWorkspaceLock below, this environment's
+        //      temp directory is dropped straight away so it is only fit for
+        //      tests which don't rely on what is written to it
+        holochain_state::test_utils::test_cell_env().env()
+    };
+    curve Unpredictable {
+        EnvironmentWriteFixturator::new(Empty)
+            .next()
+            .unwrap()
+    };
+    curve Predictable {
+        EnvironmentWriteFixturator::new(Empty)
+            .next()
+            .unwrap()
+    };
+);
+
 fixturator!(
     CallZomeWorkspaceLock;
     curve Empty {
@@ -315,7 +338,7 @@ fixturator!(
 
 fixturator!(
     ZomeCallHostAccess;
-    constructor fn new(CallZomeWorkspaceLock, KeystoreSender, HolochainP2pCell, SignalBroadcaster, CellConductorReadHandle, CellId);
+    constructor fn new(CallZomeWorkspaceLock, EnvironmentWrite, KeystoreSender, HolochainP2pCell, SignalBroadcaster, CellConductorReadHandle, CellId);
 );
 
 fixturator!(
@@ -338,6 +361,16 @@ fixturator!(
     constructor fn new(CallZomeWorkspaceLock, KeystoreSender, HolochainP2pCell);
 );
 
+fixturator!(
+    GenesisSelfCheckInvocation;
+    constructor fn new(DnaDef, AgentPubKey, MaybeSerializedBytes);
+);
+
+fixturator!(
+    GenesisSelfCheckHostAccess;
+    constructor fn new();
+);
+
 fixturator!(
     MigrateAgentInvocation;
     constructor fn new(DnaDef, MigrateAgent);
@@ -355,7 +388,7 @@ fixturator!(
 
 fixturator!(
     PostCommitHostAccess;
-    constructor fn new(CallZomeWorkspaceLock, KeystoreSender, HolochainP2pCell);
+    constructor fn new(CallZomeWorkspaceLock, KeystoreSender, HolochainP2pCell, SignalBroadcaster, CellConductorReadHandle, CellId);
 );
 
 fixturator!(
@@ -440,6 +473,7 @@ fixturator!(
         Validate(ValidateHostAccess)
         Init(InitHostAccess)
         EntryDefs(EntryDefsHostAccess)
+        GenesisSelfCheck(GenesisSelfCheckHostAccess)
         MigrateAgent(MigrateAgentHostAccess)
         ValidationPackage(ValidationPackageHostAccess)
         PostCommit(PostCommitHostAccess)
@@ -29,6 +29,7 @@ async fn many_agents_can_reach_consistency_agent_links() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: zomes.clone().into_iter().map(Into::into).collect(),
+            rate_limits: Default::default(),
         },
         zomes.into_iter().map(Into::into),
     )
@@ -96,6 +97,7 @@ async fn many_agents_can_reach_consistency_normal_links() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: zomes.clone().into_iter().map(Into::into).collect(),
+            rate_limits: Default::default(),
         },
         zomes.into_iter().map(Into::into),
     )
@@ -132,6 +132,7 @@ async fn speed_test(n: Option<usize>) -> TestEnvironments {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Anchor.into()].into(),
+            rate_limits: Default::default(),
         },
         vec![TestWasm::Anchor.into()],
     )
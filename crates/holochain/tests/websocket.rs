@@ -462,7 +462,11 @@ async fn emit_signals() {
     let sig2: SerializedBytes = unwrap_to::unwrap_to!(msg2 => WebsocketMessage::Signal).clone();
 
     assert_eq!(
-        Signal::App(cell_id, ().try_into().unwrap()),
+        Signal::App(
+            cell_id,
+            TestWasm::EmitSignal.into(),
+            holochain_zome_types::signal::AppSignal::new(().try_into().unwrap())
+        ),
         Signal::try_from(sig1.clone()).unwrap(),
     );
     assert_eq!(sig1, sig2);
@@ -5,7 +5,7 @@ use holo_hash::*;
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::activity::AgentActivity;
 use holochain_zome_types::{capability::CapSecret, zome::ZomeName};
-use holochain_zome_types::{query::ChainQueryFilter, zome::FunctionName};
+use holochain_zome_types::{query::ChainQueryFilter, warrant::SignedWarrant, zome::FunctionName};
 use std::sync::Arc;
 
 mod types;
@@ -104,6 +104,14 @@ pub trait HolochainP2pCellT {
         to_agent: AgentPubKey,
         receipt: SerializedBytes,
     ) -> actor::HolochainP2pResult<()>;
+
+    /// Publish warrants to the agent activity authorities of the warrantee.
+    async fn publish_warrants(
+        &mut self,
+        agent: AgentPubKey,
+        warrants: Vec<SignedWarrant>,
+        timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<()>;
 }
 
 /// A wrapper around HolochainP2pSender that partially applies the dna_hash / agent_pub_key.
@@ -281,6 +289,24 @@ impl HolochainP2pCellT for HolochainP2pCell {
             )
             .await
     }
+
+    /// Publish warrants to the agent activity authorities of the warrantee.
+    async fn publish_warrants(
+        &mut self,
+        agent: AgentPubKey,
+        warrants: Vec<SignedWarrant>,
+        timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<()> {
+        self.sender
+            .publish_warrants(
+                (*self.dna_hash).clone(),
+                (*self.from_agent).clone(),
+                agent,
+                warrants,
+                timeout_ms,
+            )
+            .await
+    }
 }
 
 pub use kitsune_p2p::dht_arc;
@@ -176,6 +176,26 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving incoming warrants from a remote node
+    fn handle_incoming_publish_warrants(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        from_agent: AgentPubKey,
+        agent: AgentPubKey,
+        warrants: Vec<holochain_zome_types::warrant::SignedWarrant>,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<()> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            evt_sender
+                .publish_warrants(dna_hash, to_agent, from_agent, agent, warrants)
+                .await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     /// receiving an incoming validation receipt from a remote node
     fn handle_incoming_validation_receipt(
         &mut self,
@@ -284,6 +304,30 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         .into())
     }
 
+    /// We need to get previously stored agent info for the agents holding a basis.
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_query_agent_info_signed_near_basis(
+        &mut self,
+        input: kitsune_p2p::event::QueryAgentInfoSignedNearBasisEvt,
+    ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<Vec<AgentInfoSigned>> {
+        let kitsune_p2p::event::QueryAgentInfoSignedNearBasisEvt {
+            space,
+            agent,
+            basis_loc,
+            limit,
+        } = input;
+        let h_space = DnaHash::from_kitsune(&space);
+        let h_agent = AgentPubKey::from_kitsune(&agent);
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            Ok(evt_sender
+                .query_agent_info_signed_near_basis(h_space, h_agent, space, basis_loc, limit)
+                .await?)
+        }
+        .boxed()
+        .into())
+    }
+
     #[tracing::instrument(skip(self, space, to_agent, from_agent, payload), level = "trace")]
     fn handle_call(
         &mut self,
@@ -323,7 +367,8 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             } => self.handle_incoming_get_agent_activity(space, to_agent, agent, query, options),
             // holochain_p2p never publishes via request
             // these only occur on broadcasts
-            crate::wire::WireMessage::Publish { .. } => {
+            crate::wire::WireMessage::Publish { .. }
+            | crate::wire::WireMessage::PublishWarrants { .. } => {
                 Err(HolochainP2pError::invalid_p2p_message(
                     "invalid: publish is a broadcast type, not a request".to_string(),
                 )
@@ -378,6 +423,9 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 dht_hash,
                 ops,
             ),
+            crate::wire::WireMessage::PublishWarrants { agent, warrants } => {
+                self.handle_incoming_publish_warrants(space, to_agent, from_agent, agent, warrants)
+            }
         }
     }
 
@@ -824,4 +872,40 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .boxed()
         .into())
     }
+
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_publish_warrants(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        agent: AgentPubKey,
+        warrants: Vec<holochain_zome_types::warrant::SignedWarrant>,
+        timeout_ms: Option<u64>,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.into_kitsune();
+        let from_agent = from_agent.into_kitsune();
+        // Warrants are held by the agent activity authorities
+        // so the agent key is the basis
+        let agent_hash: AnyDhtHash = agent.clone().into();
+        let basis = agent_hash.to_kitsune();
+
+        let payload = crate::wire::WireMessage::publish_warrants(agent, warrants).encode()?;
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            kitsune_p2p
+                .notify_multi(kitsune_p2p::actor::NotifyMulti {
+                    space,
+                    from_agent,
+                    basis,
+                    remote_agent_count: None, // default best-effort
+                    timeout_ms,
+                    payload,
+                })
+                .await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
 }
@@ -102,6 +102,16 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<()> {
         Err("stub".into())
     }
+    fn handle_publish_warrants(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        agent: AgentPubKey,
+        warrants: Vec<holochain_zome_types::warrant::SignedWarrant>,
+        timeout_ms: Option<u64>,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
 }
 
 /// Spawn a stub network that doesn't respond to any messages.
@@ -304,6 +314,9 @@ mod tests {
                     QueryAgentInfoSigned { respond, .. } => {
                         respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
                     }
+                    QueryAgentInfoSignedNearBasis { respond, .. } => {
+                        respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                    }
                     _ => (),
                 }
             }
@@ -328,6 +341,77 @@ mod tests {
         r_task.await.unwrap();
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn test_publish_warrants_workflow() {
+        use holochain_zome_types::warrant::{SignedWarrant, Warrant, WarrantProof};
+
+        let (dna, a1, a2, a3) = test_setup();
+
+        let (p2p, mut evt) = spawn_holochain_p2p(KitsuneP2pConfig::default())
+            .await
+            .unwrap();
+
+        let warrant = SignedWarrant {
+            warrant: Warrant {
+                proof: WarrantProof::InvalidHeader {
+                    header_hash: newhash!(HeaderHash, 'h'),
+                    signature: vec![0; 64].into(),
+                    reason: "warrant-test".into(),
+                },
+                warrantee: a3.clone(),
+                author: a1.clone(),
+                timestamp: holochain_types::Timestamp::now().into(),
+            },
+            signature: vec![0; 64].into(),
+        };
+
+        let recv_count = Arc::new(std::sync::atomic::AtomicU8::new(0));
+
+        let recv_count_clone = recv_count.clone();
+        let expected = warrant.clone();
+        let r_task = tokio::task::spawn(async move {
+            use tokio::stream::StreamExt;
+            while let Some(evt) = evt.next().await {
+                use crate::types::event::HolochainP2pEvent::*;
+                match evt {
+                    PublishWarrants {
+                        respond, warrants, ..
+                    } => {
+                        assert_eq!(vec![expected.clone()], warrants);
+                        respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                        recv_count_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    }
+                    SignNetworkData { respond, .. } => {
+                        respond.r(Ok(async move { Ok(vec![0; 64].into()) }.boxed().into()));
+                    }
+                    PutAgentInfoSigned { respond, .. } => {
+                        respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                    }
+                    QueryAgentInfoSigned { respond, .. } => {
+                        respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                    }
+                    QueryAgentInfoSignedNearBasis { respond, .. } => {
+                        respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                    }
+                    _ => (),
+                }
+            }
+        });
+
+        p2p.join(dna.clone(), a1.clone()).await.unwrap();
+        p2p.join(dna.clone(), a2.clone()).await.unwrap();
+        p2p.join(dna.clone(), a3.clone()).await.unwrap();
+
+        p2p.publish_warrants(dna, a1, a3, vec![warrant], Some(200))
+            .await
+            .unwrap();
+
+        assert_eq!(3, recv_count.load(std::sync::atomic::Ordering::SeqCst));
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+        r_task.await.unwrap();
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn test_get_workflow() {
         let (dna, a1, a2, _a3) = test_setup();
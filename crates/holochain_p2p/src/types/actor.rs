@@ -2,7 +2,9 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::*;
+use holochain_zome_types::entry::GetStrategy;
 use holochain_zome_types::request::MetadataRequest;
+use holochain_zome_types::warrant::SignedWarrant;
 use holochain_zome_types::zome::FunctionName;
 
 /// Request a validation package.
@@ -23,6 +25,8 @@ pub struct GetValidationPackage {
 /// Fields tagged with `[Network]` are network-level controls.
 /// Fields tagged with `[Remote]` are controls that will be forwarded to the
 /// remote agent processing this `Get` request.
+/// Fields tagged with `[Local]` control how the cascade uses the
+/// data held locally and are not sent.
 pub struct GetOptions {
     /// [Network]
     /// How many remote nodes should we make requests of / aggregate.
@@ -57,6 +61,21 @@ pub struct GetOptions {
     /// Return all live headers even if there is deletes.
     /// Useful for metadata calls.
     pub all_live_headers_with_metadata: bool,
+
+    /// [Local] [Remote]
+    /// Whether the latest metadata is needed or only the content.
+    /// When only the content is needed the cascade returns local data
+    /// without going to the network and the remote-end doesn't
+    /// return any metadata.
+    pub strategy: GetStrategy,
+
+    /// [Local]
+    /// Never go to the network, only use the data held locally.
+    pub local_only: bool,
+
+    /// [Local]
+    /// Follow the update chain and return the latest update.
+    pub follow_updates: bool,
 }
 
 impl Default for GetOptions {
@@ -68,13 +87,23 @@ impl Default for GetOptions {
             race_timeout_ms: None,
             follow_redirects: true,
             all_live_headers_with_metadata: false,
+            strategy: GetStrategy::Latest,
+            local_only: false,
+            follow_updates: false,
         }
     }
 }
 
 impl From<holochain_zome_types::entry::GetOptions> for GetOptions {
-    fn from(_: holochain_zome_types::entry::GetOptions) -> Self {
-        Self::default()
+    fn from(a: holochain_zome_types::entry::GetOptions) -> Self {
+        Self {
+            remote_agent_count: a.remote_agent_count,
+            timeout_ms: a.timeout_ms,
+            strategy: a.strategy,
+            local_only: a.local_only,
+            follow_updates: a.follow_updates,
+            ..Self::default()
+        }
     }
 }
 
@@ -257,6 +286,15 @@ ghost_actor::ghost_chan! {
 
         /// Send a validation receipt to a remote node.
         fn send_validation_receipt(dna_hash: DnaHash, to_agent: AgentPubKey, from_agent: AgentPubKey, receipt: SerializedBytes) -> ();
+
+        /// Publish warrants to the agent activity authorities of the warrantee.
+        fn publish_warrants(
+            dna_hash: DnaHash,
+            from_agent: AgentPubKey,
+            agent: AgentPubKey,
+            warrants: Vec<SignedWarrant>,
+            timeout_ms: Option<u64>,
+        ) -> ();
     }
 }
 
@@ -2,7 +2,9 @@
 //! Module containing incoming events from the HolochainP2p actor.
 
 use crate::*;
-use holochain_zome_types::signature::Signature;
+use holochain_zome_types::{
+    entry::GetStrategy, request::MetadataRequest, signature::Signature, warrant::SignedWarrant,
+};
 use kitsune_p2p::agent_store::AgentInfoSigned;
 
 /// Get options help control how the get is processed at various levels.
@@ -14,6 +16,8 @@ pub struct GetOptions {
     /// Return all live headers even if there is deletes.
     /// Useful for metadata calls.
     pub all_live_headers_with_metadata: bool,
+    /// Only return the content without any deletes or updates.
+    pub content_only: bool,
 }
 
 impl From<&actor::GetOptions> for GetOptions {
@@ -21,17 +25,23 @@ impl From<&actor::GetOptions> for GetOptions {
         Self {
             follow_redirects: a.follow_redirects,
             all_live_headers_with_metadata: a.all_live_headers_with_metadata,
+            content_only: a.strategy == GetStrategy::Content,
         }
     }
 }
 
 /// GetMeta options help control how the get is processed at various levels.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetMetaOptions {}
+pub struct GetMetaOptions {
+    /// Which metadata the remote-end should return.
+    pub metadata_request: MetadataRequest,
+}
 
 impl From<&actor::GetMetaOptions> for GetMetaOptions {
-    fn from(_a: &actor::GetMetaOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetMetaOptions) -> Self {
+        Self {
+            metadata_request: a.metadata_request.clone(),
+        }
     }
 }
 
@@ -80,6 +90,9 @@ ghost_actor::ghost_chan! {
         /// We need to get previously stored agent info.
         fn query_agent_info_signed(dna_hash: DnaHash, to_agent: AgentPubKey, kitsune_space: Arc<kitsune_p2p::KitsuneSpace>, kitsune_agent: Arc<kitsune_p2p::KitsuneAgent>) -> Vec<AgentInfoSigned>;
 
+        /// We need to get previously stored agent info for the agents holding a basis.
+        fn query_agent_info_signed_near_basis(dna_hash: DnaHash, to_agent: AgentPubKey, kitsune_space: Arc<kitsune_p2p::KitsuneSpace>, basis_loc: u32, limit: u32) -> Vec<AgentInfoSigned>;
+
         /// A remote node is attempting to make a remote call on us.
         fn call_remote(
             dna_hash: DnaHash,
@@ -101,6 +114,16 @@ ghost_actor::ghost_chan! {
             ops: Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
         ) -> ();
 
+        /// A remote node is publishing warrants about an agent
+        /// whose activity we claim to be holding.
+        fn publish_warrants(
+            dna_hash: DnaHash,
+            to_agent: AgentPubKey,
+            from_agent: AgentPubKey,
+            agent: AgentPubKey,
+            warrants: Vec<SignedWarrant>,
+        ) -> ();
+
         /// A remote node is requesting a validation package.
         fn get_validation_package(
             // The dna_hash / space_hash context.
@@ -184,6 +207,7 @@ macro_rules! match_p2p_evt {
         match $h {
             HolochainP2pEvent::CallRemote { $i, .. } => { $($t)* }
             HolochainP2pEvent::Publish { $i, .. } => { $($t)* }
+            HolochainP2pEvent::PublishWarrants { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetValidationPackage { $i, .. } => { $($t)* }
             HolochainP2pEvent::Get { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetMeta { $i, .. } => { $($t)* }
@@ -196,6 +220,7 @@ macro_rules! match_p2p_evt {
             HolochainP2pEvent::PutAgentInfoSigned { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetAgentInfoSigned { $i, .. } => { $($t)* }
             HolochainP2pEvent::QueryAgentInfoSigned { $i, .. } => { $($t)* }
+            HolochainP2pEvent::QueryAgentInfoSignedNearBasis { $i, .. } => { $($t)* }
         }
     };
 }
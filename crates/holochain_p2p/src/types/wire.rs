@@ -1,4 +1,5 @@
 use crate::*;
+use holochain_zome_types::warrant::SignedWarrant;
 use holochain_zome_types::zome::FunctionName;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -58,6 +59,10 @@ pub(crate) enum WireMessage {
     GetValidationPackage {
         header_hash: HeaderHash,
     },
+    PublishWarrants {
+        agent: AgentPubKey,
+        warrants: Vec<SignedWarrant>,
+    },
 }
 
 impl WireMessage {
@@ -131,4 +136,8 @@ impl WireMessage {
     pub fn get_validation_package(header_hash: HeaderHash) -> WireMessage {
         Self::GetValidationPackage { header_hash }
     }
+
+    pub fn publish_warrants(agent: AgentPubKey, warrants: Vec<SignedWarrant>) -> WireMessage {
+        Self::PublishWarrants { agent, warrants }
+    }
 }
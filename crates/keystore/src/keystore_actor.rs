@@ -6,6 +6,10 @@ use ghost_actor::dependencies::futures::future::FutureExt;
 use holo_hash::{HOLO_HASH_CORE_LEN, HOLO_HASH_PREFIX_LEN};
 use holochain_zome_types::signature::Sign;
 use holochain_zome_types::signature::Signature;
+use holochain_zome_types::x_salsa20_poly1305::*;
+use lair_keystore_api::internal::crypto_box;
+use std::convert::TryFrom;
+use std::sync::Arc;
 
 /// GhostSender type for the KeystoreApi
 pub type KeystoreSender = ghost_actor::GhostSender<lair_keystore_api::actor::LairClientApi>;
@@ -24,6 +28,18 @@ pub trait KeystoreSenderExt {
 
     /// Generate a signature for a given blob of binary data.
     fn sign(&self, input: Sign) -> KeystoreApiFuture<Signature>;
+
+    /// Generates a new x25519 keypair in the keystore, returning the public key.
+    fn create_x25519_keypair(&self) -> KeystoreApiFuture<X25519PubKey>;
+
+    /// Encrypt some data from the sender to the recipient.
+    /// The private key for the sender must be in the keystore.
+    fn encrypt(&self, input: Encrypt) -> KeystoreApiFuture<XSalsa20Poly1305EncryptedData>;
+
+    /// Decrypt some data sent from the sender to the recipient.
+    /// The private key for the recipient must be in the keystore.
+    /// Returns None if the data could not be authenticated.
+    fn decrypt(&self, input: Decrypt) -> KeystoreApiFuture<Option<Vec<u8>>>;
 }
 
 impl KeystoreSenderExt for KeystoreSender {
@@ -53,4 +69,78 @@ impl KeystoreSenderExt for KeystoreSender {
         .boxed()
         .into()
     }
+
+    fn create_x25519_keypair(&self) -> KeystoreApiFuture<X25519PubKey> {
+        use lair_keystore_api::actor::LairClientApiSender;
+        let fut = self.x25519_new_from_entropy();
+        async move {
+            let pub_key = fut.await?;
+            Ok(X25519PubKey::try_from(AsRef::<[u8]>::as_ref(&pub_key))
+                .map_err(|e| KeystoreError::Other(e.to_string()))?)
+        }
+        .boxed()
+        .into()
+    }
+
+    fn encrypt(&self, input: Encrypt) -> KeystoreApiFuture<XSalsa20Poly1305EncryptedData> {
+        use lair_keystore_api::actor::LairClientApiSender;
+        let Encrypt {
+            sender,
+            recipient,
+            data,
+        } = input;
+        let fut = self.crypto_box_by_pub_key(
+            x25519_pub_key(&sender),
+            x25519_pub_key(&recipient),
+            Arc::new(crypto_box::CryptoBoxData {
+                data: Arc::new(data),
+            }),
+        );
+        async move {
+            let crypto_box::CryptoBoxEncryptedData {
+                nonce,
+                encrypted_data,
+            } = fut.await?;
+            let nonce = XSalsa20Poly1305Nonce::try_from(AsRef::<[u8]>::as_ref(&nonce))
+                .map_err(|e| KeystoreError::Other(e.to_string()))?;
+            Ok(XSalsa20Poly1305EncryptedData::new(
+                nonce,
+                encrypted_data.to_vec(),
+            ))
+        }
+        .boxed()
+        .into()
+    }
+
+    fn decrypt(&self, input: Decrypt) -> KeystoreApiFuture<Option<Vec<u8>>> {
+        use lair_keystore_api::actor::LairClientApiSender;
+        let Decrypt {
+            recipient,
+            sender,
+            encrypted_data,
+        } = input;
+        let mut nonce = [0; XSALSA20_POLY1305_NONCE_BYTES];
+        nonce.copy_from_slice(encrypted_data.as_nonce_ref().as_ref());
+        let fut = self.crypto_box_open_by_pub_key(
+            x25519_pub_key(&recipient),
+            x25519_pub_key(&sender),
+            Arc::new(crypto_box::CryptoBoxEncryptedData {
+                nonce: nonce.into(),
+                encrypted_data: Arc::new(encrypted_data.as_encrypted_data_ref().to_vec()),
+            }),
+        );
+        async move {
+            let data = fut.await?;
+            Ok(data.map(|data| data.data.to_vec()))
+        }
+        .boxed()
+        .into()
+    }
+}
+
+/// Convert our public key type into the one lair expects.
+fn x25519_pub_key(key: &X25519PubKey) -> lair_keystore_api::internal::x25519::X25519PubKey {
+    let mut bytes = [0; X25519_PUB_KEY_BYTES];
+    bytes.copy_from_slice(key.as_ref());
+    bytes.into()
 }
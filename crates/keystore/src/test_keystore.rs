@@ -111,4 +111,52 @@ mod tests {
         .await
         .unwrap();
     }
+    #[tokio::test(threaded_scheduler)]
+    async fn test_test_keystore_encrypt_decrypt() {
+        use holochain_zome_types::x_salsa20_poly1305::*;
+        tokio::task::spawn(async move {
+            let keystore = spawn_test_keystore().await.unwrap();
+            let alice = keystore.create_x25519_keypair().await.unwrap();
+            let bob = keystore.create_x25519_keypair().await.unwrap();
+            let carol = keystore.create_x25519_keypair().await.unwrap();
+            let data = b"encryption test data".to_vec();
+
+            // agent to agent
+            let encrypted = keystore
+                .encrypt(Encrypt::new(alice, bob, data.clone()))
+                .await
+                .unwrap();
+            assert_ne!(data.as_slice(), encrypted.as_encrypted_data_ref());
+            assert_eq!(
+                Some(data.clone()),
+                keystore
+                    .decrypt(Decrypt::new(bob, alice, encrypted.clone()))
+                    .await
+                    .unwrap()
+            );
+            // the wrong sender fails to authenticate
+            assert_eq!(
+                None,
+                keystore
+                    .decrypt(Decrypt::new(bob, carol, encrypted))
+                    .await
+                    .unwrap()
+            );
+
+            // symmetric
+            let encrypted = keystore
+                .encrypt(Encrypt::to_self(carol, data.clone()))
+                .await
+                .unwrap();
+            assert_eq!(
+                Some(data),
+                keystore
+                    .decrypt(Decrypt::from_self(carol, encrypted))
+                    .await
+                    .unwrap()
+            );
+        })
+        .await
+        .unwrap();
+    }
 }
@@ -6,6 +6,7 @@ use crate::{
     KitsuneAgent, KitsuneBinType, KitsuneSignature, KitsuneSpace,
 };
 use fixt::prelude::*;
+use kitsune_p2p_types::dht_arc::DhtArc;
 use url2::url2;
 
 fixturator!(
@@ -48,9 +49,16 @@ fixturator!(
     from SixtyFourBytes;
 );
 
+fixturator!(
+    DhtArc;
+    curve Empty DhtArc::new(0, 0);
+    curve Unpredictable DhtArc::new(fixt!(U32), fixt!(U32));
+    curve Predictable DhtArc::new(fixt!(U32, Predictable), fixt!(U32, Predictable));
+);
+
 fixturator!(
     AgentInfo;
-    constructor fn new(KitsuneSpace, KitsuneAgent, Urls, U64, U64);
+    constructor fn new(KitsuneSpace, KitsuneAgent, Urls, DhtArc, U64, U64);
 );
 
 fixturator!(
@@ -58,28 +66,28 @@ fixturator!(
     curve Empty {
         let mut data = Vec::new();
         kitsune_p2p_types::codec::rmp_encode(&mut data, &fixt!(AgentInfo, Empty)).unwrap();
-        AgentInfoSigned::try_new(
+        AgentInfoSigned::new_unchecked(
             fixt!(KitsuneAgent, Empty),
             fixt!(KitsuneSignature, Empty),
             data,
-        ).unwrap()
+        )
     };
     curve Unpredictable {
         let mut data = Vec::new();
         kitsune_p2p_types::codec::rmp_encode(&mut data, &fixt!(AgentInfo)).unwrap();
-        AgentInfoSigned::try_new(
+        AgentInfoSigned::new_unchecked(
             fixt!(KitsuneAgent),
             fixt!(KitsuneSignature),
             data,
-        ).unwrap()
+        )
     };
     curve Predictable {
         let mut data = Vec::new();
         kitsune_p2p_types::codec::rmp_encode(&mut data, &fixt!(AgentInfo, Predictable)).unwrap();
-        AgentInfoSigned::try_new(
+        AgentInfoSigned::new_unchecked(
             fixt!(KitsuneAgent, Predictable),
             fixt!(KitsuneSignature, Predictable),
             data,
-        ).unwrap()
+        )
     };
 );
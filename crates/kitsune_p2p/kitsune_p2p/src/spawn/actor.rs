@@ -205,6 +205,44 @@ impl KitsuneP2pActor {
                                             .expect("This encoding should never fail");
                                     let _ = write.write_and_close(resp).await;
                                 }
+                                wire::Wire::FetchOpBloom(wire::FetchOpBloom {
+                                    space,
+                                    from_agent,
+                                    to_agent,
+                                    dht_arc,
+                                    since_utc_epoch_s,
+                                    until_utc_epoch_s,
+                                    bloom,
+                                }) => {
+                                    let input = ReqOpBloomEvt::new(
+                                        from_agent,
+                                        to_agent,
+                                        dht_arc,
+                                        since_utc_epoch_s,
+                                        until_utc_epoch_s,
+                                        bloom,
+                                    );
+                                    let (missing_hashes, bloom, agent_hashes) =
+                                        match local_req_op_bloom(&evt_sender, space, input).await {
+                                            Err(err) => {
+                                                let reason = format!("{:?}", err);
+                                                let fail = wire::Wire::failure(reason)
+                                                    .encode_vec()
+                                                    .unwrap();
+                                                let _ = write.write_and_close(fail).await;
+                                                continue;
+                                            }
+                                            Ok(r) => r,
+                                        };
+                                    let resp = wire::Wire::fetch_op_bloom_response(
+                                        missing_hashes,
+                                        bloom,
+                                        agent_hashes,
+                                    )
+                                    .encode_vec()
+                                    .expect("This encoding should never fail");
+                                    let _ = write.write_and_close(resp).await;
+                                }
                                 wire::Wire::FetchOpData(wire::FetchOpData {
                                     space,
                                     from_agent,
@@ -334,7 +372,15 @@ impl KitsuneP2pEventHandler for KitsuneP2pActor {
         &mut self,
         input: crate::event::PutAgentInfoSignedEvt,
     ) -> KitsuneP2pEventHandlerResult<()> {
-        Ok(self.evt_sender.put_agent_info_signed(input))
+        // every agent info is verified before it reaches the store,
+        // whether it came from gossip, a query or the bootstrap service
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            input.agent_info_signed.verify().await?;
+            evt_sender.put_agent_info_signed(input).await
+        }
+        .boxed()
+        .into())
     }
 
     fn handle_get_agent_info_signed(
@@ -351,6 +397,13 @@ impl KitsuneP2pEventHandler for KitsuneP2pActor {
         Ok(self.evt_sender.query_agent_info_signed(input))
     }
 
+    fn handle_query_agent_info_signed_near_basis(
+        &mut self,
+        input: crate::event::QueryAgentInfoSignedNearBasisEvt,
+    ) -> KitsuneP2pEventHandlerResult<Vec<crate::types::agent_store::AgentInfoSigned>> {
+        Ok(self.evt_sender.query_agent_info_signed_near_basis(input))
+    }
+
     fn handle_call(
         &mut self,
         space: Arc<KitsuneSpace>,
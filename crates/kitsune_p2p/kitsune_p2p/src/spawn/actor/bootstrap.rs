@@ -226,6 +226,7 @@ mod tests {
             space,
             agent.clone(),
             urls,
+            fixt!(DhtArc),
             (millis - 100).try_into().unwrap(),
             AGENT_INFO_EXPIRES_AFTER_MS,
         );
@@ -237,6 +238,7 @@ mod tests {
             .unwrap();
         let agent_info_signed =
             AgentInfoSigned::try_new(agent, KitsuneSignature((*signature.0).clone()), data)
+                .await
                 .unwrap();
 
         // Simply hitting the endpoint should be OK.
@@ -311,6 +313,7 @@ mod tests {
                 space.clone(),
                 kitsune_agent.clone(),
                 fixt!(Urls),
+                fixt!(DhtArc),
                 now,
                 AGENT_INFO_EXPIRES_AFTER_MS,
             );
@@ -322,6 +325,7 @@ mod tests {
                 KitsuneSignature((*signature.0).clone()),
                 data,
             )
+            .await
             .unwrap();
 
             super::put(
@@ -120,8 +120,16 @@ pub(crate) fn peer_discover(
                             if agent_infos.is_empty() {
                                 Err("failed to connect".into())
                             } else {
-                                // if we have a result, return it
-                                Ok(agent_infos.remove(0))
+                                // if we have a result, check it is really
+                                // from the agent we asked for and return it
+                                let info = agent_infos.remove(0);
+                                if info.as_agent_ref() != &**to_agent {
+                                    return Err(KitsuneP2pError::InvalidAgentInfo(Arc::new(
+                                        info.into_agent(),
+                                    )));
+                                }
+                                info.verify().await?;
+                                Ok(info)
                             }
                         }
                         _ => KitsuneP2pResult::Err("failed to connect".into()),
@@ -184,6 +192,10 @@ pub(crate) fn peer_discover(
 }
 
 /// attempt to send messages to remote nodes in a staged timeout format
+/// - returns as soon as `target_node_count` results have been accepted
+/// - after stage 1, returns as soon as there are any results
+/// - after stage 2, returns whatever results there are
+/// Any requests still outstanding when this returns are cancelled.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn message_neighborhood<T, F>(
     space: &mut Space,
@@ -191,8 +203,7 @@ pub(crate) fn message_neighborhood<T, F>(
     target_node_count: u8,
     stage_1_timeout_if_any_ms: u64,
     stage_2_timeout_even_if_none_ms: u64,
-    // ignored while full-sync
-    _basis: Arc<KitsuneBasis>,
+    basis: Arc<KitsuneBasis>,
     payload: wire::Wire,
     accept_result_cb: F,
 ) -> MustBoxFuture<'static, Vec<T>>
@@ -207,7 +218,11 @@ where
     let space = space.space.clone();
     let accept_result_cb = Arc::new(accept_result_cb);
     async move {
-        let out = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+        use futures::StreamExt;
+
+        let (res_send, mut res_recv) = futures::channel::mpsc::unbounded();
+        let mut out = Vec::new();
+        let mut abort_handles = Vec::new();
 
         let mut sent_to = HashSet::new();
         let start_time = std::time::Instant::now();
@@ -221,8 +236,13 @@ where
             // as a comprimize attempting to favor readable code
             // we'll check the fetch count / timing after every full
             // iteration before deciding to send more requests.
+            // The wait between iterations is cut short by any result.
+
+            while let Ok(Some(res)) = res_recv.try_next() {
+                out.push(res);
+            }
 
-            let fetched_count = out.lock().await.len();
+            let fetched_count = out.len();
             if fetched_count >= target_node_count as usize {
                 break;
             }
@@ -240,7 +260,7 @@ where
             if let Ok(nodes) = get_5_or_less_non_local_agents_near_basis(
                 space.clone(),
                 from_agent.clone(),
-                _basis.clone(),
+                basis.clone(),
                 i_s.clone(),
                 evt_sender.clone(),
                 bootstrap_service.clone(),
@@ -258,8 +278,8 @@ where
                         let fut = tx.create_channel(url);
                         let mut payload = payload.clone();
                         let accept_result_cb = accept_result_cb.clone();
-                        let out = out.clone();
-                        tokio::task::spawn(async move {
+                        let res_send = res_send.clone();
+                        let (fut, abort_handle) = futures::future::abortable(async move {
                             let (_, mut write, read) = fut.await?;
                             match &mut payload {
                                 wire::Wire::Notify(n) => {
@@ -275,11 +295,14 @@ where
                             let res = read.read_to_end().await;
                             let (_, res) = wire::Wire::decode_ref(&res)?;
                             if let Ok(res) = accept_result_cb(to_agent, res) {
-                                out.lock().await.push(res);
+                                // the receiver is gone if we already returned
+                                let _ = res_send.unbounded_send(res);
                             }
 
                             KitsuneP2pResult::Ok(())
                         });
+                        abort_handles.push(abort_handle);
+                        tokio::task::spawn(fut);
                     }
                 }
             }
@@ -294,12 +317,26 @@ where
             if interval_ms > stage_2_timeout_even_if_none_ms - elapsed_ms {
                 interval_ms = stage_2_timeout_even_if_none_ms - elapsed_ms;
             }
+            // don't sleep through the end of stage 1
+            if elapsed_ms < stage_1_timeout_if_any_ms
+                && interval_ms > stage_1_timeout_if_any_ms - elapsed_ms
+            {
+                interval_ms = stage_1_timeout_if_any_ms - elapsed_ms;
+            }
 
-            tokio::time::delay_for(std::time::Duration::from_millis(interval_ms)).await;
+            let delay = tokio::time::delay_for(std::time::Duration::from_millis(interval_ms));
+            if let futures::future::Either::Left((Some(res), _)) =
+                futures::future::select(res_recv.next(), delay).await
+            {
+                out.push(res);
+            }
         }
 
-        let mut lock = out.lock().await;
-        lock.drain(..).collect()
+        for abort_handle in abort_handles {
+            abort_handle.abort();
+        }
+
+        out
     }
     .boxed()
     .into()
@@ -309,8 +346,7 @@ where
 pub(crate) fn get_5_or_less_non_local_agents_near_basis(
     space: Arc<KitsuneSpace>,
     from_agent: Arc<KitsuneAgent>,
-    // ignored while full-sync
-    _basis: Arc<KitsuneBasis>,
+    basis: Arc<KitsuneBasis>,
     i_s: ghost_actor::GhostSender<SpaceInternal>,
     evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     bootstrap_service: Option<url2::Url2>,
@@ -318,10 +354,14 @@ pub(crate) fn get_5_or_less_non_local_agents_near_basis(
     async move {
         let mut out = HashSet::new();
 
+        // only the agents whose storage arcs hold the basis
         if let Ok(mut list) = evt_sender
-            .query_agent_info_signed(QueryAgentInfoSignedEvt {
+            .query_agent_info_signed_near_basis(QueryAgentInfoSignedNearBasisEvt {
                 space: space.clone(),
                 agent: from_agent.clone(),
+                basis_loc: basis.get_loc(),
+                // grap a few extra incase they happen to be local
+                limit: 16,
             })
             .await
         {
@@ -355,7 +395,14 @@ pub(crate) fn get_5_or_less_non_local_agents_near_basis(
         .await
         {
             for item in list {
-                // TODO - someday some validation here
+                // anyone can put to the bootstrap service
+                if let Err(e) = item.verify().await {
+                    ghost_actor::dependencies::tracing::warn!(
+                        ?e,
+                        "dropping invalid agent info from bootstrap"
+                    );
+                    continue;
+                }
                 if let Ok(info) = AgentInfo::try_from(&item) {
                     if let Ok(is_local) = i_s
                         .is_agent_local(Arc::new(info.as_agent_ref().clone()))
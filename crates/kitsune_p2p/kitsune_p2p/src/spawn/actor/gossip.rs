@@ -1,23 +1,44 @@
-//! This is a temporary quick-hack gossip module for use with the
-//! in-memory / full-sync / non-sharded networking module
+//! Gossip module pairing each local agent with the agents whose arcs
+//! overlap its own and exchanging the ops held in the overlap.
+//! Each round only looks at recently integrated ops, with a periodic
+//! historical sweep of all time for every pair, and reconciles the op
+//! hashes each side holds with bloom filters.
 
-use crate::agent_store::AgentInfoSigned;
+use crate::{agent_store::AgentInfoSigned, op_bloom::OpBloom};
 use crate::{types::actor::KitsuneP2pResult, types::gossip::*, *};
 use ghost_actor::dependencies::{tracing, tracing_futures};
 use kitsune_p2p_types::dht_arc::DhtArc;
-use std::{collections::HashSet, iter::FromIterator, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// How far back a regular round of gossip looks for ops.
+/// Generous so that ops are not missed between rounds.
+const RECENT_WINDOW_S: i64 = 60 * 10;
+
+/// How often each pair of agents gossips all of time
+/// to pick up ops that were missed by the recent windows.
+const HISTORICAL_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 5);
 
 ghost_actor::ghost_chan! {
     /// "Event" requests emitted by the gossip module
     pub chan GossipEvent<crate::KitsuneP2pError> {
-        /// get a list of agents we know about
-        fn list_neighbor_agents() -> Vec<Arc<KitsuneAgent>>;
+        /// get a list of agents we know about and the arcs they hold
+        fn list_neighbor_agents() -> ListNeighborAgents;
 
         /// fetch op list from/to with constraints
         fn req_op_hashes(
             input: ReqOpHashesEvt,
         ) -> OpHashesAgentHashes;
 
+        /// fetch the op hashes missing from our bloom filter
+        /// and a bloom filter of all the op hashes held
+        fn req_op_bloom(
+            input: ReqOpBloomEvt,
+        ) -> MissingOpHashesBloom;
+
         /// fetch op data for op hash list
         fn req_op_data(
             input: ReqOpDataEvt
@@ -58,9 +79,20 @@ async fn gossip_loop(
     }
 }
 
+/// A pair of agents to gossip between, and what to gossip.
+struct GossipPair {
+    from_agent: Arc<KitsuneAgent>,
+    to_agent: Arc<KitsuneAgent>,
+    /// the parts of the dht held by both agents
+    arcs: Vec<DhtArc>,
+    since_utc_epoch_s: i64,
+    until_utc_epoch_s: i64,
+}
+
 struct GossipData {
     evt_send: futures::channel::mpsc::Sender<GossipEvent>,
-    pending_gossip_list: Vec<(Arc<KitsuneAgent>, Arc<KitsuneAgent>)>,
+    pending_gossip_list: Vec<GossipPair>,
+    last_historical_sweep: HashMap<(Arc<KitsuneAgent>, Arc<KitsuneAgent>), Instant>,
 }
 
 impl GossipData {
@@ -68,86 +100,120 @@ impl GossipData {
         Self {
             evt_send,
             pending_gossip_list: Vec::new(),
+            last_historical_sweep: HashMap::new(),
         }
     }
 
     pub async fn take_action(&mut self) -> KitsuneP2pResult<()> {
         if self.pending_gossip_list.is_empty() {
             self.fetch_pending_gossip_list().await?;
-        } else {
-            self.process_next_gossip().await?;
+        } else if let Err(e) = self.process_next_gossip().await {
+            // one unreachable peer shouldn't stop gossip with the rest
+            tracing::warn!(?e, "gossip failed");
         }
         Ok(())
     }
 
     async fn fetch_pending_gossip_list(&mut self) -> KitsuneP2pResult<()> {
-        let list = self.evt_send.list_neighbor_agents().await?;
-        // super naive gossip just processes all combinations
-        // also causes duplication because it runs pairs from both sides
-        tracing::debug!(?list);
-        for a1 in list.iter() {
-            for a2 in list.iter() {
-                // at the very least, avoid gossiping with ourselves
-                if a1 != a2 {
-                    self.pending_gossip_list.push((a1.clone(), a2.clone()));
+        let (local_agents, remote_agents) = self.evt_send.list_neighbor_agents().await?;
+        tracing::debug!(?local_agents, ?remote_agents);
+        let now = Instant::now();
+        let recent_since = epoch_now_s() - RECENT_WINDOW_S;
+        // forget pairs that we no longer gossip between
+        let mut last_historical_sweep = HashMap::new();
+        for (i, (from_agent, from_arc)) in local_agents.iter().enumerate() {
+            // gossip between a pair goes both ways so we only need to
+            // visit each pair of local agents once
+            let others = local_agents.iter().skip(i + 1).chain(remote_agents.iter());
+            for (to_agent, to_arc) in others {
+                let arcs = from_arc.intersect(to_arc);
+                if arcs.is_empty() {
+                    continue;
                 }
+                let pair = (from_agent.clone(), to_agent.clone());
+                let last_sweep = self
+                    .last_historical_sweep
+                    .get(&pair)
+                    .copied()
+                    .filter(|last| now.duration_since(*last) < HISTORICAL_SWEEP_INTERVAL);
+                // newly discovered pairs start with a sweep
+                let since_utc_epoch_s = match last_sweep {
+                    Some(_) => recent_since,
+                    None => i64::MIN,
+                };
+                last_historical_sweep.insert(pair, last_sweep.unwrap_or(now));
+                self.pending_gossip_list.push(GossipPair {
+                    from_agent: from_agent.clone(),
+                    to_agent: to_agent.clone(),
+                    arcs,
+                    since_utc_epoch_s,
+                    until_utc_epoch_s: i64::MAX,
+                });
             }
         }
+        self.last_historical_sweep = last_historical_sweep;
         Ok(())
     }
 
     async fn process_next_gossip(&mut self) -> KitsuneP2pResult<()> {
         // !is_empty() checked above in take_action
-        let (from_agent, to_agent) = self.pending_gossip_list.remove(0);
-
-        // required so from_iters below know the build_hasher type
-        type S = HashSet<Arc<KitsuneOpHash>>;
-        type A = HashSet<(Arc<KitsuneAgent>, u64)>;
-
-        // we'll just fetch all with no constraints for now
-        let (op_hashes_from, agent_info_from) = self
-            .evt_send
-            .req_op_hashes(ReqOpHashesEvt::new(
-                from_agent.clone(), // from not to because we're initiating
-                from_agent.clone(),
-                DhtArc::new(0, u32::MAX),
-                i64::MIN,
-                i64::MAX,
-            ))
-            .await?;
-        let op_hashes_from: S = HashSet::from_iter(op_hashes_from);
-        let agent_info_from: A = HashSet::from_iter(agent_info_from);
-
-        // we'll just fetch all with no constraints for now
-        let (op_hashes_to, agent_info_to) = self
-            .evt_send
-            .req_op_hashes(ReqOpHashesEvt::new(
-                from_agent.clone(),
-                to_agent.clone(),
-                DhtArc::new(0, u32::MAX),
-                i64::MIN,
-                i64::MAX,
-            ))
-            .await?;
-        let op_hashes_to: S = HashSet::from_iter(op_hashes_to);
-        let agent_info_to: A = HashSet::from_iter(agent_info_to);
+        let GossipPair {
+            from_agent,
+            to_agent,
+            arcs,
+            since_utc_epoch_s,
+            until_utc_epoch_s,
+        } = self.pending_gossip_list.remove(0);
 
+        let mut agent_info_from = HashSet::new();
+        let mut agent_info_to = HashSet::new();
         // values that to_agent has, and from_agent needs
-        let from_needs = op_hashes_to
-            .difference(&op_hashes_from)
-            .cloned()
-            .collect::<Vec<_>>();
+        let mut from_needs = HashSet::new();
+        // values that from_agent has, and to_agent needs
+        let mut to_needs = HashSet::new();
+        for dht_arc in arcs {
+            let (op_hashes, agent_info) = self
+                .evt_send
+                .req_op_hashes(ReqOpHashesEvt::new(
+                    from_agent.clone(), // from not to because we're initiating
+                    from_agent.clone(),
+                    dht_arc,
+                    since_utc_epoch_s,
+                    until_utc_epoch_s,
+                ))
+                .await?;
+            agent_info_from.extend(agent_info);
+
+            // rather than both sides listing every op hash they hold
+            // we send a bloom filter of ours and only hear about the
+            // op hashes that are missing from it
+            let (missing_hashes, bloom, agent_info) = self
+                .evt_send
+                .req_op_bloom(ReqOpBloomEvt::new(
+                    from_agent.clone(),
+                    to_agent.clone(),
+                    dht_arc,
+                    since_utc_epoch_s,
+                    until_utc_epoch_s,
+                    OpBloom::new(&op_hashes),
+                ))
+                .await?;
+            agent_info_to.extend(agent_info);
+            from_needs.extend(missing_hashes);
+            to_needs.extend(
+                op_hashes
+                    .into_iter()
+                    .filter(|op_hash| !bloom.contains(op_hash)),
+            );
+        }
+        let from_needs = from_needs.into_iter().collect::<Vec<_>>();
+        let to_needs = to_needs.into_iter().collect::<Vec<_>>();
+
         let from_needs_agents = agent_info_to
             .difference(&agent_info_from)
             .cloned()
             .map(|(ai, _)| ai)
             .collect::<Vec<_>>();
-
-        // values that from_agent has, and to_agent needs
-        let to_needs = op_hashes_from
-            .difference(&op_hashes_to)
-            .cloned()
-            .collect::<Vec<_>>();
         let to_needs_agents = agent_info_from
             .difference(&agent_info_to)
             .cloned()
@@ -210,3 +276,11 @@ impl GossipData {
         Ok(())
     }
 }
+
+/// The current time in seconds since the unix epoch
+fn epoch_now_s() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
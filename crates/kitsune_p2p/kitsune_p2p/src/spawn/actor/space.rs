@@ -1,8 +1,11 @@
-use crate::agent_store::AgentInfoSigned;
+use crate::{agent_store::AgentInfoSigned, op_bloom::OpBloom};
 
 use super::*;
 use ghost_actor::dependencies::{tracing, tracing_futures::Instrument};
-use kitsune_p2p_types::codec::Codec;
+use kitsune_p2p_types::{
+    codec::Codec,
+    dht_arc::{DhtArc, MAX_HALF_LENGTH},
+};
 use std::{collections::HashSet, convert::TryFrom};
 
 /// if the user specifies None or zero (0) for remote_agent_count
@@ -79,8 +82,12 @@ impl ghost_actor::GhostHandler<gossip::GossipEvent> for Space {}
 impl gossip::GossipEventHandler for Space {
     fn handle_list_neighbor_agents(
         &mut self,
-    ) -> gossip::GossipEventHandlerResult<Vec<Arc<KitsuneAgent>>> {
-        // while full-sync this is just a clone of list_by_basis
+    ) -> gossip::GossipEventHandlerResult<ListNeighborAgents> {
+        let local_agents = self
+            .local_joined_agents
+            .iter()
+            .map(|agent| (agent.clone(), local_storage_arc(agent)))
+            .collect::<Vec<_>>();
         let all_agents = self
             .local_joined_agents
             .iter()
@@ -97,14 +104,19 @@ impl gossip::GossipEventHandler for Space {
             None => async { Ok(Vec::new()) }.boxed().into(),
         };
         Ok(async move {
-            let mut peer_store = fut
-                .await?
-                .into_iter()
-                .map(|ai| Arc::new(ai.into_agent()))
-                .filter(|a| !all_agents.contains(a))
-                .collect::<Vec<_>>();
-            peer_store.extend(all_agents);
-            Ok(peer_store)
+            let mut remote_agents = Vec::new();
+            for agent_info_signed in fut.await? {
+                let agent = Arc::new(agent_info_signed.as_agent_ref().clone());
+                if all_agents.contains(&agent) {
+                    continue;
+                }
+                // peers that don't tell us what they hold can't be gossiped with
+                match types::agent_store::AgentInfo::try_from(&agent_info_signed) {
+                    Ok(agent_info) => remote_agents.push((agent, agent_info.storage_arc())),
+                    Err(e) => tracing::warn!(?agent, ?e, "undecodable agent info"),
+                }
+            }
+            Ok((local_agents, remote_agents))
         }
         .boxed()
         .into())
@@ -129,39 +141,65 @@ impl gossip::GossipEventHandler for Space {
             let evt_sender = self.evt_sender.clone();
             let space = self.space.clone();
             Ok(async move {
-                // see if we have an entry for this agent in our agent_store
-                let info = match evt_sender
-                    .get_agent_info_signed(GetAgentInfoSignedEvt {
-                        space: space.clone(),
-                        agent: to_agent.clone(),
-                    })
-                    .await?
-                {
-                    None => return Err(KitsuneP2pError::RoutingAgentError(to_agent)),
-                    Some(i) => i,
-                };
                 let data = wire::Wire::fetch_op_hashes(
-                    space,
+                    space.clone(),
                     from_agent,
-                    to_agent,
+                    to_agent.clone(),
                     dht_arc,
                     since_utc_epoch_s,
                     until_utc_epoch_s,
                 )
                 .encode_vec()?;
-                let info = types::agent_store::AgentInfo::try_from(&info)?;
-                let url = info.as_urls_ref().get(0).unwrap().clone();
-                let (_, mut write, read) = transport_tx.create_channel(url).await?;
-                write.write_and_close(data.to_vec()).await?;
-                let read = read.read_to_end().await;
-                let (_, read) = wire::Wire::decode_ref(&read)?;
-                match read {
-                    wire::Wire::Failure(wire::Failure { reason }) => Err(reason.into()),
+                match remote_req(evt_sender, transport_tx, space, to_agent, data).await? {
                     wire::Wire::FetchOpHashesResponse(wire::FetchOpHashesResponse {
                         hashes,
                         peer_hashes,
                     }) => Ok((hashes, peer_hashes)),
-                    _ => unreachable!(),
+                    _ => Err("unexpected response to fetch_op_hashes".into()),
+                }
+            }
+            .boxed()
+            .into())
+        }
+    }
+
+    fn handle_req_op_bloom(
+        &mut self,
+        input: ReqOpBloomEvt,
+    ) -> gossip::GossipEventHandlerResult<MissingOpHashesBloom> {
+        if self.local_joined_agents.contains(&input.to_agent) {
+            let fut = local_req_op_bloom(&self.evt_sender, self.space.clone(), input);
+            Ok(async move { fut.await }.boxed().into())
+        } else {
+            let ReqOpBloomEvt {
+                to_agent,
+                dht_arc,
+                since_utc_epoch_s,
+                until_utc_epoch_s,
+                from_agent,
+                bloom,
+            } = input;
+            let transport_tx = self.transport.clone();
+            let evt_sender = self.evt_sender.clone();
+            let space = self.space.clone();
+            Ok(async move {
+                let data = wire::Wire::fetch_op_bloom(
+                    space.clone(),
+                    from_agent,
+                    to_agent.clone(),
+                    dht_arc,
+                    since_utc_epoch_s,
+                    until_utc_epoch_s,
+                    bloom,
+                )
+                .encode_vec()?;
+                match remote_req(evt_sender, transport_tx, space, to_agent, data).await? {
+                    wire::Wire::FetchOpBloomResponse(wire::FetchOpBloomResponse {
+                        missing_hashes,
+                        bloom,
+                        peer_hashes,
+                    }) => Ok((missing_hashes, bloom, peer_hashes)),
+                    _ => Err("unexpected response to fetch_op_bloom".into()),
                 }
             }
             .boxed()
@@ -187,28 +225,15 @@ impl gossip::GossipEventHandler for Space {
             let evt_sender = self.evt_sender.clone();
             let space = self.space.clone();
             Ok(async move {
-                // see if we have an entry for this agent in our agent_store
-                let info = match evt_sender
-                    .get_agent_info_signed(GetAgentInfoSignedEvt {
-                        space: space.clone(),
-                        agent: to_agent.clone(),
-                    })
-                    .await?
-                {
-                    None => return Err(KitsuneP2pError::RoutingAgentError(to_agent)),
-                    Some(i) => i,
-                };
-                let data =
-                    wire::Wire::fetch_op_data(space, from_agent, to_agent, op_hashes, peer_hashes)
-                        .encode_vec()?;
-                let info = types::agent_store::AgentInfo::try_from(&info)?;
-                let url = info.as_urls_ref().get(0).unwrap().clone();
-                let (_, mut write, read) = transport_tx.create_channel(url).await?;
-                write.write_and_close(data.to_vec()).await?;
-                let read = read.read_to_end().await;
-                let (_, read) = wire::Wire::decode_ref(&read)?;
-                match read {
-                    wire::Wire::Failure(wire::Failure { reason }) => Err(reason.into()),
+                let data = wire::Wire::fetch_op_data(
+                    space.clone(),
+                    from_agent,
+                    to_agent.clone(),
+                    op_hashes,
+                    peer_hashes,
+                )
+                .encode_vec()?;
+                match remote_req(evt_sender, transport_tx, space, to_agent, data).await? {
                     wire::Wire::FetchOpDataResponse(wire::FetchOpDataResponse {
                         op_data,
                         agent_infos,
@@ -216,7 +241,7 @@ impl gossip::GossipEventHandler for Space {
                         op_data.into_iter().map(|(h, d)| (h, d.into())).collect(),
                         agent_infos,
                     )),
-                    _ => unreachable!(),
+                    _ => Err("unexpected response to fetch_op_data".into()),
                 }
             }
             .boxed()
@@ -276,6 +301,48 @@ impl gossip::GossipEventHandler for Space {
     }
 }
 
+/// The arc of the dht a locally joined agent is holding.
+/// While full-sync every local agent holds the whole dht.
+fn local_storage_arc(agent: &KitsuneAgent) -> DhtArc {
+    DhtArc::new(agent.get_loc(), MAX_HALF_LENGTH)
+}
+
+/// Send a gossip request to a remote agent and read back the response.
+/// A failure response from the remote is returned as an error.
+async fn remote_req(
+    evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+    transport_tx: ghost_actor::GhostSender<TransportListener>,
+    space: Arc<KitsuneSpace>,
+    to_agent: Arc<KitsuneAgent>,
+    data: Vec<u8>,
+) -> KitsuneP2pResult<wire::Wire> {
+    // see if we have an entry for this agent in our agent_store
+    let info = match evt_sender
+        .get_agent_info_signed(GetAgentInfoSignedEvt {
+            space,
+            agent: to_agent.clone(),
+        })
+        .await?
+    {
+        None => return Err(KitsuneP2pError::RoutingAgentError(to_agent)),
+        Some(i) => i,
+    };
+    let info = types::agent_store::AgentInfo::try_from(&info)?;
+    let url = info
+        .as_urls_ref()
+        .get(0)
+        .ok_or_else(|| KitsuneP2pError::from("no url"))?
+        .clone();
+    let (_, mut write, read) = transport_tx.create_channel(url).await?;
+    write.write_and_close(data).await?;
+    let read = read.read_to_end().await;
+    let (_, read) = wire::Wire::decode_ref(&read)?;
+    match read {
+        wire::Wire::Failure(wire::Failure { reason }) => Err(reason.into()),
+        read => Ok(read),
+    }
+}
+
 pub fn local_req_op_hashes(
     evt_sender: &futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     space: Arc<KitsuneSpace>,
@@ -313,6 +380,41 @@ pub fn local_req_op_hashes(
     }
 }
 
+pub fn local_req_op_bloom(
+    evt_sender: &futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+    space: Arc<KitsuneSpace>,
+    input: ReqOpBloomEvt,
+) -> impl std::future::Future<Output = Result<MissingOpHashesBloom, KitsuneP2pError>> {
+    let ReqOpBloomEvt {
+        from_agent,
+        to_agent,
+        dht_arc,
+        since_utc_epoch_s,
+        until_utc_epoch_s,
+        bloom,
+    } = input;
+    let fut = local_req_op_hashes(
+        evt_sender,
+        space,
+        ReqOpHashesEvt::new(
+            from_agent,
+            to_agent,
+            dht_arc,
+            since_utc_epoch_s,
+            until_utc_epoch_s,
+        ),
+    );
+    async move {
+        let (op_hashes, agent_hashes) = fut.await?;
+        let held = OpBloom::new(&op_hashes);
+        let missing_hashes = op_hashes
+            .into_iter()
+            .filter(|op_hash| !bloom.contains(op_hash))
+            .collect();
+        Ok((missing_hashes, held, agent_hashes))
+    }
+}
+
 pub fn local_req_op_data(
     evt_sender: &futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     space: Arc<KitsuneSpace>,
@@ -395,6 +497,7 @@ impl SpaceInternalHandler for Space {
                     (*space).clone(),
                     (*agent).clone(),
                     urls.clone(),
+                    local_storage_arc(&agent),
                     crate::spawn::actor::bootstrap::now_once(None).await?,
                     AGENT_INFO_EXPIRES_AFTER_MS,
                 );
@@ -406,11 +509,20 @@ impl SpaceInternalHandler for Space {
                     data: Arc::new(data.clone()),
                 };
                 let sig = evt_sender.sign_network_data(sign_req).await?;
-                let agent_info_signed = crate::types::agent_store::AgentInfoSigned::try_new(
+                let agent_info_signed = match crate::types::agent_store::AgentInfoSigned::try_new(
                     (*agent).clone(),
                     sig.clone(),
                     data,
-                )?;
+                )
+                .await
+                {
+                    Ok(agent_info_signed) => agent_info_signed,
+                    // no other node would accept this so don't publish it
+                    Err(e) => {
+                        tracing::error!(?e, "signature on our own agent info is invalid");
+                        continue;
+                    }
+                };
                 tracing::debug!(?agent_info, ?sig);
                 evt_sender
                     .put_agent_info_signed(PutAgentInfoSignedEvt {
@@ -644,14 +756,19 @@ impl Space {
             basis,
             remote_agent_count,
             timeout_ms,
-            //as_race,
-            //race_timeout_ms,
+            as_race,
+            race_timeout_ms,
             payload,
-            ..
         } = input;
         let remote_agent_count = remote_agent_count.unwrap();
         let timeout_ms = timeout_ms.unwrap();
-        let stage_1_timeout_ms = timeout_ms / 2;
+        // when racing, return the remote results we have as soon as
+        // the race timeout is up, rather than waiting for more
+        let stage_1_timeout_ms = if as_race {
+            race_timeout_ms.unwrap()
+        } else {
+            timeout_ms / 2
+        };
 
         // as an optimization - request to all local joins
         // but don't count that toward our request total
@@ -694,8 +811,11 @@ impl Space {
         );
 
         Ok(async move {
-            let mut out: Vec<actor::RpcMultiResponse> = futures::future::join_all(local_all)
-                .await
+            // don't hold the remote requests up behind the local ones
+            let (local_all, mut remote) =
+                futures::future::join(futures::future::join_all(local_all), remote_fut).await;
+
+            let mut out: Vec<actor::RpcMultiResponse> = local_all
                 .into_iter()
                 .filter_map(|(r, a)| {
                     if let Ok(r) = r {
@@ -709,7 +829,7 @@ impl Space {
                 })
                 .collect();
 
-            out.append(&mut remote_fut.await);
+            out.append(&mut remote);
 
             Ok(out)
         }
@@ -67,6 +67,46 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn test_transport_multi_coms_race() -> Result<(), KitsuneP2pError> {
+        observability::test_run().ok();
+        let (harness, _evt) = spawn_test_harness_mem().await?;
+
+        let space = harness.add_space().await?;
+        let (a1, p2p1) = harness.add_direct_agent("one".into()).await?;
+        let (_a2, _p2p2) = harness.add_direct_agent("two".into()).await?;
+        let (_a3, _p2p3) = harness.add_direct_agent("tre".into()).await?;
+
+        // needed until we have some way of bootstrapping
+        harness.magic_peer_info_exchange().await?;
+
+        let start = std::time::Instant::now();
+        let res = p2p1
+            .rpc_multi(actor::RpcMulti {
+                space: space,
+                from_agent: a1.clone(),
+                // this is just a dummy value right now
+                basis: TestVal::test_val(),
+                // every remote agent, so the race ends once they've all responded
+                remote_agent_count: Some(2),
+                timeout_ms: Some(5000),
+                as_race: true,
+                // long enough for every remote to make it in
+                race_timeout_ms: Some(4000),
+                payload: b"test-multi-request".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        // the race returned on the count, before the race timeout
+        assert!(start.elapsed() < std::time::Duration::from_millis(4000));
+        // the local agent and both remotes
+        assert_eq!(3, res.len());
+
+        harness.ghost_actor_shutdown().await?;
+        Ok(())
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn test_transport_notify_coms() -> Result<(), KitsuneP2pError> {
         observability::test_run().ok();
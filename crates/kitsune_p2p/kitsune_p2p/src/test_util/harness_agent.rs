@@ -1,4 +1,5 @@
 use super::*;
+use std::convert::TryFrom;
 
 ghost_actor::ghost_chan! {
     /// controller for test harness agent actor
@@ -157,6 +158,33 @@ impl KitsuneP2pEventHandler for AgentHarness {
         Ok(async move { Ok(out) }.boxed().into())
     }
 
+    fn handle_query_agent_info_signed_near_basis(
+        &mut self,
+        input: QueryAgentInfoSignedNearBasisEvt,
+    ) -> KitsuneP2pEventHandlerResult<Vec<crate::types::agent_store::AgentInfoSigned>> {
+        let mut out = self
+            .agent_store
+            .values()
+            .filter_map(|a| {
+                let arc = AgentInfo::try_from(&**a).ok()?.storage_arc();
+                if arc.contains(input.basis_loc) {
+                    Some((arc, (**a).clone()))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        out.sort_by_key(|(arc, _)| {
+            kitsune_p2p_types::dht_arc::shortest_arc_distance(arc.center_loc, input.basis_loc)
+        });
+        let out = out
+            .into_iter()
+            .take(input.limit as usize)
+            .map(|(_, a)| a)
+            .collect();
+        Ok(async move { Ok(out) }.boxed().into())
+    }
+
     fn handle_call(
         &mut self,
         space: Arc<super::KitsuneSpace>,
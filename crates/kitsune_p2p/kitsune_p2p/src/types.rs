@@ -16,6 +16,10 @@ pub enum KitsuneP2pError {
     #[error("Routing Agent Error: {0:?}")]
     RoutingAgentError(Arc<KitsuneAgent>),
 
+    /// InvalidAgentInfo
+    #[error("Invalid Agent Info: {0:?}")]
+    InvalidAgentInfo(Arc<KitsuneAgent>),
+
     /// DecodingError
     #[error("Decoding Error: {0}")]
     DecodingError(Arc<String>),
@@ -219,6 +223,7 @@ pub mod actor;
 pub mod agent_store;
 pub mod event;
 pub mod gossip;
+pub mod op_bloom;
 pub(crate) mod wire;
 
 pub use kitsune_p2p_types::dht_arc;
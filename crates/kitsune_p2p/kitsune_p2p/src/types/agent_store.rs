@@ -1,9 +1,14 @@
 //! Data structures to be stored in the agent/peer database.
 
 use crate::types::KitsuneAgent;
+use crate::types::KitsuneBinType;
 use crate::types::KitsuneP2pError;
 use crate::types::KitsuneSignature;
 use crate::types::KitsuneSpace;
+use kitsune_p2p_types::dht_arc::{DhtArc, MAX_HALF_LENGTH};
+use lair_keystore_api::actor::{SignEd25519PubKey, SignEd25519Signature};
+use std::convert::TryFrom;
+use std::sync::Arc;
 use url2::Url2;
 
 /// A list of Urls.
@@ -33,18 +38,56 @@ pub struct AgentInfoSigned {
 
 impl AgentInfoSigned {
     /// Build a new AgentInfoSigned struct given a valid signature of the AgentInfo.
-    // @todo fail this if the signature does not verify against the agent info.
-    // It should not be possible to express a signed agent info type  with no valid signature.
-    pub fn try_new(
+    /// Fails with InvalidAgentInfo if the signature does not verify.
+    pub async fn try_new(
         agent: KitsuneAgent,
         signature: KitsuneSignature,
         agent_info: Vec<u8>,
     ) -> Result<Self, KitsuneP2pError> {
-        Ok(Self {
+        let agent_info_signed = Self::new_unchecked(agent, signature, agent_info);
+        agent_info_signed.verify().await?;
+        Ok(agent_info_signed)
+    }
+
+    /// Build a new AgentInfoSigned struct without checking the signature.
+    /// Only for fixturators, anything else must go through try_new.
+    pub(crate) fn new_unchecked(
+        agent: KitsuneAgent,
+        signature: KitsuneSignature,
+        agent_info: Vec<u8>,
+    ) -> Self {
+        Self {
             agent,
             signature,
             agent_info,
-        })
+        }
+    }
+
+    /// Check that the agent info is about the agent that signed it, and that
+    /// the signature is valid for the agent's key.
+    /// The key is the first 32 bytes of the agent, any trailing bytes (e.g.
+    /// the 4 location bytes holochain appends) are not part of the key.
+    /// Anything received from another node or the bootstrap service can be
+    /// forged so must be verified before it is used or stored.
+    pub async fn verify(&self) -> Result<(), KitsuneP2pError> {
+        let invalid = || KitsuneP2pError::InvalidAgentInfo(Arc::new(self.agent.clone()));
+        match AgentInfo::try_from(self) {
+            Ok(agent_info) if agent_info.agent == self.agent => (),
+            _ => return Err(invalid()),
+        }
+        let pub_key: SignEd25519PubKey = match self.agent.get_bytes().get(..32) {
+            Some(key) => key.to_vec().into(),
+            None => return Err(invalid()),
+        };
+        let signature: SignEd25519Signature = self.signature.0.clone().into();
+        match pub_key
+            .verify(Arc::new(self.agent_info.clone()), signature)
+            .await
+        {
+            Ok(true) => Ok(()),
+            // a malformed key or signature is just as invalid
+            _ => Err(invalid()),
+        }
     }
 
     /// Thin wrapper around AsRef for KitsuneSignature.
@@ -79,6 +122,11 @@ pub struct AgentInfo {
     agent: KitsuneAgent,
     // List of urls the agent can be reached at, in the agent's own preference order.
     urls: Urls,
+    // The arc of the dht the agent is holding ops for.
+    // Agent infos signed before the arc was added are treated as holding everything.
+    #[as_ref(ignore)]
+    #[serde(default = "full_storage_arc")]
+    storage_arc: DhtArc,
     // The unix ms timestamp that the agent info was signed at, according to the agent's own clock.
     #[as_ref(ignore)]
     signed_at_ms: u64,
@@ -87,6 +135,10 @@ pub struct AgentInfo {
     expires_after_ms: u64,
 }
 
+fn full_storage_arc() -> DhtArc {
+    DhtArc::new(0, MAX_HALF_LENGTH)
+}
+
 impl std::convert::TryFrom<&AgentInfoSigned> for AgentInfo {
     type Error = KitsuneP2pError;
     fn try_from(agent_info_signed: &AgentInfoSigned) -> Result<Self, Self::Error> {
@@ -102,6 +154,7 @@ impl AgentInfo {
         space: KitsuneSpace,
         agent: KitsuneAgent,
         urls: Urls,
+        storage_arc: DhtArc,
         signed_at_ms: u64,
         expires_after_ms: u64,
     ) -> Self {
@@ -109,6 +162,7 @@ impl AgentInfo {
             space,
             agent,
             urls,
+            storage_arc,
             signed_at_ms,
             expires_after_ms,
         }
@@ -137,6 +191,11 @@ impl AgentInfo {
         self.as_ref()
     }
 
+    /// Accessor for storage_arc.
+    pub fn storage_arc(&self) -> DhtArc {
+        self.storage_arc
+    }
+
     /// Accessor for signed_at_ms.
     pub fn signed_at_ms(&self) -> u64 {
         self.signed_at_ms
@@ -159,3 +218,114 @@ impl From<AgentInfo> for KitsuneAgent {
         ai.agent
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixt::*;
+    use fixt::prelude::*;
+    use lair_keystore_api::internal::sign_ed25519::sign_ed25519_keypair_new_from_entropy;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn agent_info_signatures_are_verified() {
+        let keypair = sign_ed25519_keypair_new_from_entropy().await.unwrap();
+        let agent = KitsuneAgent::new((*keypair.pub_key.0).clone());
+        let sign = |agent: KitsuneAgent| {
+            let keypair = keypair.clone();
+            async move {
+                let agent_info = AgentInfo::new(
+                    fixt!(KitsuneSpace),
+                    agent,
+                    fixt!(Urls),
+                    fixt!(DhtArc),
+                    fixt!(U64),
+                    fixt!(U64),
+                );
+                let mut data = Vec::new();
+                kitsune_p2p_types::codec::rmp_encode(&mut data, &agent_info).unwrap();
+                let signature = keypair.sign(Arc::new(data.clone())).await.unwrap();
+                (KitsuneSignature((*signature.0).clone()), data)
+            }
+        };
+
+        let (signature, data) = sign(agent.clone()).await;
+        let agent_info_signed = AgentInfoSigned::try_new(agent.clone(), signature.clone(), data)
+            .await
+            .unwrap();
+        agent_info_signed.verify().await.unwrap();
+
+        // The signed data can't be changed
+        let mut data = agent_info_signed.as_agent_info_ref().to_vec();
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert!(matches!(
+            AgentInfoSigned::try_new(agent.clone(), signature, data).await,
+            Err(KitsuneP2pError::InvalidAgentInfo(_))
+        ));
+
+        // An agent can't sign info about someone else
+        let (signature, data) = sign(fixt!(KitsuneAgent)).await;
+        assert!(matches!(
+            AgentInfoSigned::try_new(agent, signature, data).await,
+            Err(KitsuneP2pError::InvalidAgentInfo(_))
+        ));
+
+        // Nor can the signature be forged
+        assert!(matches!(
+            fixt!(AgentInfoSigned).verify().await,
+            Err(KitsuneP2pError::InvalidAgentInfo(_))
+        ));
+    }
+
+    #[test]
+    fn agent_info_without_storage_arc_holds_everything() {
+        #[derive(serde::Serialize)]
+        struct AgentInfoWithoutArc {
+            space: KitsuneSpace,
+            agent: KitsuneAgent,
+            urls: Urls,
+            signed_at_ms: u64,
+            expires_after_ms: u64,
+        }
+        let mut data = Vec::new();
+        kitsune_p2p_types::codec::rmp_encode(
+            &mut data,
+            AgentInfoWithoutArc {
+                space: fixt!(KitsuneSpace),
+                agent: fixt!(KitsuneAgent),
+                urls: fixt!(Urls),
+                signed_at_ms: fixt!(U64),
+                expires_after_ms: fixt!(U64),
+            },
+        )
+        .unwrap();
+        let agent_info: AgentInfo = kitsune_p2p_types::codec::rmp_decode(&mut &*data).unwrap();
+        assert_eq!(agent_info.storage_arc(), DhtArc::new(0, MAX_HALF_LENGTH));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn agent_info_signatures_are_verified_for_36_byte_agents() {
+        // Holochain agents are the 32 byte key followed by 4 location bytes.
+        let keypair = sign_ed25519_keypair_new_from_entropy().await.unwrap();
+        let mut bytes = (*keypair.pub_key.0).clone();
+        bytes.extend_from_slice(&[1, 2, 3, 4]);
+        let agent = KitsuneAgent::new(bytes);
+
+        let agent_info = AgentInfo::new(
+            fixt!(KitsuneSpace),
+            agent.clone(),
+            fixt!(Urls),
+            fixt!(DhtArc),
+            fixt!(U64),
+            fixt!(U64),
+        );
+        let mut data = Vec::new();
+        kitsune_p2p_types::codec::rmp_encode(&mut data, &agent_info).unwrap();
+        let signature = keypair.sign(Arc::new(data.clone())).await.unwrap();
+        let signature = KitsuneSignature((*signature.0).clone());
+
+        AgentInfoSigned::try_new(agent, signature, data)
+            .await
+            .unwrap();
+    }
+}
@@ -69,6 +69,20 @@ pub struct QueryAgentInfoSignedEvt {
     pub agent: Arc<super::KitsuneAgent>,
 }
 
+#[derive(Debug)]
+/// Get agent info as previously signed and put,
+/// for the agents whose storage arcs cover a basis location.
+pub struct QueryAgentInfoSignedNearBasisEvt {
+    /// The "space" context.
+    pub space: Arc<super::KitsuneSpace>,
+    /// The "agent" context.
+    pub agent: Arc<super::KitsuneAgent>,
+    /// The location of the basis on the dht.
+    pub basis_loc: u32,
+    /// The most agent infos to return, nearest to the basis first.
+    pub limit: u32,
+}
+
 ghost_actor::ghost_chan! {
     /// The KitsuneP2pEvent stream allows handling events generated from the
     /// KitsuneP2p actor.
@@ -82,6 +96,9 @@ ghost_actor::ghost_chan! {
         /// We need to get previously stored agent info.
         fn query_agent_info_signed(input: QueryAgentInfoSignedEvt) -> Vec<crate::types::agent_store::AgentInfoSigned>;
 
+        /// We need to get previously stored agent info for the agents holding a basis.
+        fn query_agent_info_signed_near_basis(input: QueryAgentInfoSignedNearBasisEvt) -> Vec<crate::types::agent_store::AgentInfoSigned>;
+
         /// We are receiving a request from a remote node.
         fn call(space: Arc<super::KitsuneSpace>, to_agent: Arc<super::KitsuneAgent>, from_agent: Arc<super::KitsuneAgent>, payload: Vec<u8>) -> Vec<u8>;
 
@@ -2,7 +2,7 @@
 
 use kitsune_p2p_types::dht_arc::DhtArc;
 
-use crate::agent_store::AgentInfoSigned;
+use crate::{agent_store::AgentInfoSigned, op_bloom::OpBloom};
 
 use super::*;
 
@@ -23,6 +23,26 @@ pub struct ReqOpHashesEvt {
     pub until_utc_epoch_s: i64,
 }
 
+#[derive(Debug, derive_more::Constructor)]
+/// Request the dht op hashes an agent holds that are missing
+/// from a bloom filter of our own, and a bloom filter of theirs.
+/// Peers that are mostly in sync send far less than the full
+/// hash lists of ReqOpHashesEvt.
+pub struct ReqOpBloomEvt {
+    /// Agent Requesting the ops.
+    pub from_agent: Arc<KitsuneAgent>,
+    /// The agent you are requesting ops from.
+    pub to_agent: Arc<KitsuneAgent>,
+    /// The arc on the dht that you want ops from.
+    pub dht_arc: DhtArc,
+    /// Get ops from this time.
+    pub since_utc_epoch_s: i64,
+    /// Get ops till this time.
+    pub until_utc_epoch_s: i64,
+    /// Bloom filter of the op hashes the requesting agent holds.
+    pub bloom: OpBloom,
+}
+
 #[derive(Debug, derive_more::Constructor)]
 /// Request dht ops from an agent.
 pub struct ReqOpDataEvt {
@@ -38,5 +58,18 @@ pub struct ReqOpDataEvt {
 
 /// Dht op and agent hashes that the agent has information on.
 pub type OpHashesAgentHashes = (Vec<Arc<KitsuneOpHash>>, Vec<(Arc<KitsuneAgent>, u64)>);
+/// Dht op hashes missing from a bloom filter, a bloom filter of all the
+/// op hashes held and the agent hashes the agent has information on.
+pub type MissingOpHashesBloom = (
+    Vec<Arc<KitsuneOpHash>>,
+    OpBloom,
+    Vec<(Arc<KitsuneAgent>, u64)>,
+);
 /// The Dht op data and agent store information
 pub type OpDataAgentInfo = (Vec<(Arc<KitsuneOpHash>, Vec<u8>)>, Vec<AgentInfoSigned>);
+/// Agents to gossip with and the arcs they hold.
+/// Our own local agents first then the remote peers we know about.
+pub type ListNeighborAgents = (
+    Vec<(Arc<KitsuneAgent>, DhtArc)>,
+    Vec<(Arc<KitsuneAgent>, DhtArc)>,
+);
@@ -0,0 +1,117 @@
+//! Bloom filters of op hashes for gossip reconciliation.
+//!
+//! Peers that are mostly in sync can swap a small filter of the op hashes
+//! they hold instead of the full hash list, and only list the hashes the
+//! other side is missing.
+
+use crate::KitsuneOpHash;
+
+/// Bits in the filter for each op hash.
+/// With [HASH_COUNT] hashes this is a false positive rate of around 1%.
+const BITS_PER_OP: usize = 10;
+
+/// Number of bit positions set for each op hash.
+const HASH_COUNT: u64 = 7;
+
+/// The smallest filter in bits, so an empty filter is still valid.
+const MIN_BITS: usize = 64;
+
+/// A bloom filter of op hashes.
+/// Every filter is built with a random seed, so an op hash that is a false
+/// positive in one round of gossip is unlikely to be one in the next.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OpBloom {
+    seed: u64,
+    #[serde(with = "serde_bytes")]
+    bits: Vec<u8>,
+}
+
+impl OpBloom {
+    /// Build a filter holding these op hashes.
+    pub fn new<I>(op_hashes: I) -> Self
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        I::Item: AsRef<KitsuneOpHash>,
+    {
+        let op_hashes = op_hashes.into_iter();
+        let len = std::cmp::max(op_hashes.len() * BITS_PER_OP, MIN_BITS);
+        let mut bloom = Self {
+            seed: rand::random(),
+            bits: vec![0; (len + 7) / 8],
+        };
+        for op_hash in op_hashes {
+            for i in bloom.indexes(op_hash.as_ref()) {
+                bloom.bits[i / 8] |= 1 << (i % 8);
+            }
+        }
+        bloom
+    }
+
+    /// Check if an op hash might be in the filter.
+    /// False means the op hash is definitely not in the filter.
+    pub fn contains(&self, op_hash: &KitsuneOpHash) -> bool {
+        // a filter from the wire with no bits can't hold anything
+        !self.bits.is_empty()
+            && self
+                .indexes(op_hash)
+                .all(|i| self.bits[i / 8] & (1 << (i % 8)) != 0)
+    }
+
+    /// The bit positions for an op hash using double hashing.
+    fn indexes(&self, op_hash: &KitsuneOpHash) -> impl Iterator<Item = usize> {
+        let len = self.bits.len() as u64 * 8;
+        let h1 = fnv1a(self.seed, &op_hash.0);
+        // odd so that it steps through every position
+        let h2 = fnv1a(h1, &op_hash.0) | 1;
+        (0..HASH_COUNT).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize)
+    }
+}
+
+/// 64 bit FNV-1a hash of some bytes starting from a seeded offset.
+/// This must give the same result on every node so std hashers can't be used.
+fn fnv1a(seed: u64, bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET ^ seed, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KitsuneBinType;
+    use std::sync::Arc;
+
+    fn op_hash(i: u32) -> Arc<KitsuneOpHash> {
+        let mut bytes = vec![0; 32];
+        bytes[..4].copy_from_slice(&i.to_le_bytes());
+        Arc::new(KitsuneOpHash::new(bytes))
+    }
+
+    #[test]
+    fn bloom_holds_its_op_hashes() {
+        let held = (0..1000).map(op_hash).collect::<Vec<_>>();
+        let bloom = OpBloom::new(&held);
+        assert!(held.iter().all(|h| bloom.contains(h)));
+
+        let false_positives = (1000..11000)
+            .map(op_hash)
+            .filter(|h| bloom.contains(h))
+            .count();
+        // around 1% are expected
+        assert!(false_positives < 300, "{} false positives", false_positives);
+    }
+
+    #[test]
+    fn empty_bloom_holds_nothing() {
+        let bloom = OpBloom::new(Vec::<Arc<KitsuneOpHash>>::new());
+        assert!(!bloom.contains(&op_hash(0)));
+        let bloom = OpBloom {
+            seed: 0,
+            bits: Vec::new(),
+        };
+        assert!(!bloom.contains(&op_hash(0)));
+    }
+}
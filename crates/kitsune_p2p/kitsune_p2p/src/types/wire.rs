@@ -1,6 +1,6 @@
 //! KitsuneP2p Wire Protocol Encoding Decoding
 
-use crate::{agent_store::AgentInfoSigned, types::*};
+use crate::{agent_store::AgentInfoSigned, op_bloom::OpBloom, types::*};
 use derive_more::*;
 use kitsune_p2p_types::dht_arc::DhtArc;
 use std::sync::Arc;
@@ -75,6 +75,26 @@ kitsune_p2p_types::write_codec_enum! {
             agent_infos.1: Vec<AgentInfoSigned>,
         },
 
+        /// Fetch the DhtOp hashes held with Constraints that are
+        /// missing from a bloom filter of the requester's op hashes
+        FetchOpBloom(0x35) {
+            space.0: Arc<KitsuneSpace>,
+            from_agent.1: Arc<KitsuneAgent>,
+            to_agent.2: Arc<KitsuneAgent>,
+            dht_arc.3: DhtArc,
+            since_utc_epoch_s.4: i64,
+            until_utc_epoch_s.5: i64,
+            bloom.6: OpBloom,
+        },
+
+        /// Missing hashes and a bloom filter of all held hashes
+        /// in response to FetchOpBloom
+        FetchOpBloomResponse(0x36) {
+            missing_hashes.0: Vec<Arc<KitsuneOpHash>>,
+            bloom.1: OpBloom,
+            peer_hashes.2: Vec<(Arc<KitsuneAgent>, u64)>,
+        },
+
         /// Query Agent data from a remote node
         AgentInfoQuery(0x40) {
             space.0: Arc<KitsuneSpace>,
@@ -8,7 +8,9 @@ use std::{
     ops::{Bound, RangeBounds},
 };
 
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, From, Into)]
+#[derive(
+    Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash, From, Into,
+)]
 /// Type for representing a location that can wrap around
 /// a u32 dht arc
 pub struct DhtLocation(pub Wrapping<u32>);
@@ -20,7 +22,7 @@ pub struct DhtLocation(pub Wrapping<u32>);
 /// 1 more is added to represent the middle point of an odd length array
 pub const MAX_HALF_LENGTH: u32 = (u32::MAX / 2) + 1 + 1;
 
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash)]
 /// Represents how much of a dht arc is held
 /// center_loc is where the hash is.
 /// The center_loc is the center of the arc
@@ -101,8 +103,61 @@ impl DhtArc {
             }
         }
     }
+
+    /// Get the arcs that are held by both this arc and another.
+    /// Two arcs that each wrap most of the way around the dht
+    /// can overlap at both of their ends so there may be two.
+    /// An arc always holds an odd number of locations so an
+    /// overlap of an even length is grown by one location.
+    pub fn intersect(&self, other: &DhtArc) -> Vec<DhtArc> {
+        if self.half_length == 0 || other.half_length == 0 {
+            return Vec::new();
+        }
+        if self.half_length == MAX_HALF_LENGTH {
+            return vec![*other];
+        }
+        if other.half_length == MAX_HALF_LENGTH {
+            return vec![*self];
+        }
+        let (start, len) = self.start_len();
+        let (other_start, other_len) = other.start_len();
+        // Measure the other arc from the start of this one
+        let offset = (Wrapping(other_start) - Wrapping(start)).0 as u64;
+        let mut overlaps = Vec::with_capacity(2);
+        // The part of the other arc that wraps back around to our start
+        if offset + other_len > FULL_LENGTH {
+            overlaps.push((0, std::cmp::min(offset + other_len - FULL_LENGTH, len)));
+        }
+        if offset < len {
+            overlaps.push((offset, std::cmp::min(offset + other_len, len)));
+        }
+        // The other arc covers all of this one
+        if overlaps.len() == 2 && overlaps[0].1 >= overlaps[1].0 {
+            return vec![*self];
+        }
+        overlaps
+            .into_iter()
+            .map(|(s, e)| Self::from_start_len((Wrapping(start) + Wrapping(s as u32)).0, e - s))
+            .collect()
+    }
+
+    /// The first location and number of locations held by
+    /// an arc that is neither empty nor full
+    fn start_len(&self) -> (u32, u64) {
+        let start = (self.center_loc.0 - Wrapping(self.half_length - 1)).0;
+        (start, self.half_length as u64 * 2 - 1)
+    }
+
+    /// The smallest arc holding `len` locations from `start`
+    fn from_start_len(start: u32, len: u64) -> Self {
+        let half = (len / 2) as u32;
+        Self::new((Wrapping(start) + Wrapping(half)).0, half + 1)
+    }
 }
 
+/// The number of locations on the dht
+const FULL_LENGTH: u64 = u32::MAX as u64 + 1;
+
 impl From<u32> for DhtLocation {
     fn from(a: u32) -> Self {
         Self(Wrapping(a))
@@ -116,7 +171,7 @@ impl From<DhtLocation> for u32 {
 }
 
 /// Finds the shortest distance between two points on a circle
-fn shortest_arc_distance<A: Into<DhtLocation>, B: Into<DhtLocation>>(a: A, b: B) -> u32 {
+pub fn shortest_arc_distance<A: Into<DhtLocation>, B: Into<DhtLocation>>(a: A, b: B) -> u32 {
     // Turn into wrapped u32s
     let a = a.into().0;
     let b = b.into().0;
@@ -318,4 +373,77 @@ mod tests {
         );
         check_bounds_full(0, MAX_HALF_LENGTH, half, half - 1);
     }
+
+    #[test]
+    fn test_arc_intersect() {
+        let quarter = (u32::MAX as f64 / 4.0).round() as u32;
+        let half = (u32::MAX as f64 / 2.0).round() as u32;
+        let eighth = (u32::MAX as f64 / 8.0).round() as u32;
+
+        // Nothing is held in common with an empty arc
+        assert!(DhtArc::new(0, 0).intersect(&DhtArc::new(0, 10)).is_empty());
+        assert!(DhtArc::new(0, 10).intersect(&DhtArc::new(0, 0)).is_empty());
+
+        // Arcs that don't touch
+        assert!(DhtArc::new(0, 10)
+            .intersect(&DhtArc::new(20, 10))
+            .is_empty());
+        assert!(DhtArc::new(0, quarter)
+            .intersect(&DhtArc::new(half, quarter))
+            .is_empty());
+
+        // A full arc holds everything the other arc holds
+        let arc = DhtArc::new(quarter, 10);
+        assert_eq!(DhtArc::new(0, MAX_HALF_LENGTH).intersect(&arc), vec![arc]);
+        assert_eq!(arc.intersect(&DhtArc::new(0, MAX_HALF_LENGTH)), vec![arc]);
+
+        // An arc inside another
+        assert_eq!(
+            DhtArc::new(0, quarter).intersect(&DhtArc::new(5, 3)),
+            vec![DhtArc::new(5, 3)]
+        );
+        assert_eq!(
+            DhtArc::new(5, 3).intersect(&DhtArc::new(0, quarter)),
+            vec![DhtArc::new(5, 3)]
+        );
+
+        // Overlapping at one end
+        // 0..=20 and 10..=30 hold 10..=20
+        assert_eq!(
+            DhtArc::new(10, 11).intersect(&DhtArc::new(20, 11)),
+            vec![DhtArc::new(15, 6)]
+        );
+        // Overlapping across zero
+        // -10..=10 and 0..=20 hold 0..=10
+        assert_eq!(
+            DhtArc::new(0, 11).intersect(&DhtArc::new(10, 11)),
+            vec![DhtArc::new(5, 6)]
+        );
+        assert_eq!(
+            DhtArc::new(10, 11).intersect(&DhtArc::new(0, 11)),
+            vec![DhtArc::new(5, 6)]
+        );
+        // An even length overlap is grown by one location
+        // 0..=20 and 11..=31 hold 11..=20 which becomes 11..=21
+        assert_eq!(
+            DhtArc::new(10, 11).intersect(&DhtArc::new(21, 11)),
+            vec![DhtArc::new(16, 6)]
+        );
+
+        // Arcs that overlap at both ends
+        let a = DhtArc::new(0, quarter + eighth);
+        let b = DhtArc::new(half, quarter + eighth);
+        let overlaps = a.intersect(&b);
+        assert_eq!(overlaps.len(), 2);
+        for loc in vec![quarter, half + quarter] {
+            assert!(a.contains(loc) && b.contains(loc));
+            assert!(overlaps.iter().any(|arc| arc.contains(loc)));
+        }
+        for loc in vec![0, half] {
+            assert!(!overlaps.iter().any(|arc| arc.contains(loc)));
+        }
+        for arc in b.intersect(&a) {
+            assert!(overlaps.contains(&arc));
+        }
+    }
 }
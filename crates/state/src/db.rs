@@ -63,6 +63,12 @@ pub enum DbName {
     ValidationLimbo,
     /// KVV store to accumulate validation receipts for a published EntryHash
     ValidationReceipts,
+    /// KV store of zome functions scheduled to be called later, keyed by when they are due
+    ScheduledFns,
+    /// KV store of the number of times each use-limited CapGrant has been used
+    CapGrantUses,
+    /// KV store of the x25519 public keys created by the agent of a Cell
+    X25519Keys,
     /// Single store for all known agents on the network
     Agent,
 }
@@ -94,6 +100,9 @@ impl DbName {
             IntegrationLimbo => Single,
             ValidationLimbo => Single,
             ValidationReceipts => Multi,
+            ScheduledFns => Single,
+            CapGrantUses => Single,
+            X25519Keys => Single,
             Agent => Single,
         }
     }
@@ -165,6 +174,12 @@ lazy_static! {
     pub static ref VALIDATION_LIMBO: DbKey<SingleStore> = DbKey::new(DbName::ValidationLimbo);
     /// The key to access the ValidationReceipts database
     pub static ref VALIDATION_RECEIPTS: DbKey<MultiStore> = DbKey::new(DbName::ValidationReceipts);
+    /// The key to access the ScheduledFns database
+    pub static ref SCHEDULED_FNS: DbKey<SingleStore> = DbKey::new(DbName::ScheduledFns);
+    /// The key to access the CapGrantUses database
+    pub static ref CAP_GRANT_USES: DbKey<SingleStore> = DbKey::new(DbName::CapGrantUses);
+    /// The key to access the X25519Keys database
+    pub static ref X25519_KEYS: DbKey<SingleStore> = DbKey::new(DbName::X25519Keys);
     /// The key to access the Agent database
     pub static ref AGENT: DbKey<SingleStore> = DbKey::new(DbName::Agent);
 }
@@ -225,6 +240,9 @@ fn register_databases(env: &Rkv, kind: &EnvironmentKind, um: &mut DbMap) -> Data
             register_db(env, um, &*INTEGRATION_LIMBO)?;
             register_db(env, um, &*VALIDATION_LIMBO)?;
             register_db(env, um, &*VALIDATION_RECEIPTS)?;
+            register_db(env, um, &*SCHEDULED_FNS)?;
+            register_db(env, um, &*CAP_GRANT_USES)?;
+            register_db(env, um, &*X25519_KEYS)?;
         }
         EnvironmentKind::Conductor => {
             register_db(env, um, &*CONDUCTOR_STATE)?;
@@ -21,6 +21,7 @@ pub enum TestWasm {
     EmitSignal,
     HashEntry,
     Foo,
+    GenesisSelfCheck,
     HashPath,
     HdkExtern,
     Imports,
@@ -47,6 +48,7 @@ pub enum TestWasm {
     ValidationPackageFail,
     ValidationPackageSuccess,
     WhoAmI,
+    XSalsa20Poly1305,
     ZomeInfo,
 }
 
@@ -65,6 +67,7 @@ impl From<TestWasm> for ZomeName {
             TestWasm::EmitSignal => "emit_signal",
             TestWasm::HashEntry => "hash_entry",
             TestWasm::Foo => "foo",
+            TestWasm::GenesisSelfCheck => "genesis_self_check",
             TestWasm::HashPath => "hash_path",
             TestWasm::HdkExtern => "hdk_extern",
             TestWasm::Imports => "imports",
@@ -91,6 +94,7 @@ impl From<TestWasm> for ZomeName {
             TestWasm::ValidationPackageFail => "validation_package_fail",
             TestWasm::ValidationPackageSuccess => "validation_package_success",
             TestWasm::WhoAmI => "whoami",
+            TestWasm::XSalsa20Poly1305 => "x_salsa20_poly1305",
             TestWasm::ZomeInfo => "zome_info",
         })
     }
@@ -123,6 +127,9 @@ impl From<TestWasm> for DnaWasm {
                 get_code("wasm32-unknown-unknown/release/test_wasm_hash_entry.wasm")
             }
             TestWasm::Foo => get_code("wasm32-unknown-unknown/release/test_wasm_foo.wasm"),
+            TestWasm::GenesisSelfCheck => {
+                get_code("wasm32-unknown-unknown/release/test_wasm_genesis_self_check.wasm")
+            }
             TestWasm::HashPath => {
                 get_code("wasm32-unknown-unknown/release/test_wasm_hash_path.wasm")
             }
@@ -189,6 +196,9 @@ impl From<TestWasm> for DnaWasm {
                 get_code("wasm32-unknown-unknown/release/test_wasm_validation_package_success.wasm")
             }
             TestWasm::WhoAmI => get_code("wasm32-unknown-unknown/release/test_wasm_whoami.wasm"),
+            TestWasm::XSalsa20Poly1305 => {
+                get_code("wasm32-unknown-unknown/release/test_wasm_x_salsa20_poly1305.wasm")
+            }
             TestWasm::ZomeInfo => {
                 get_code("wasm32-unknown-unknown/release/test_wasm_zome_info.wasm")
             }
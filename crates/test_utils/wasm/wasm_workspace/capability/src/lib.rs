@@ -14,6 +14,8 @@ fn init(_: ()) -> ExternResult<InitCallbackResult> {
         // empty access converts to unrestricted
         access: ().into(),
         functions,
+        curry_payloads: CurryPayloads::default(),
+        limits: CapLimits::default(),
     })?;
 
     Ok(InitCallbackResult::Pass)
@@ -32,6 +34,8 @@ fn cap_grant_entry(secret: CapSecret) -> ExternResult<CapGrantEntry> {
         tag: "".into(),
         access: secret.into(),
         functions,
+        curry_payloads: CurryPayloads::default(),
+        limits: CapLimits::default(),
     })
 }
 
@@ -51,9 +55,24 @@ pub fn delete_cap_grant(header_hash: HeaderHash) -> ExternResult<HeaderHash> {
     Ok(hdk3::prelude::delete_cap_grant(header_hash)?)
 }
 
+#[hdk_extern]
+fn cap_grants(_: ()) -> ExternResult<CapabilityGrantsOutput> {
+    Ok(CapabilityGrantsOutput::new(capability_grants(None)?))
+}
+
+#[hdk_extern]
+fn cap_claims(_: ()) -> ExternResult<CapabilityClaimsOutput> {
+    Ok(CapabilityClaimsOutput::new(capability_claims(None, None)?))
+}
+
+#[hdk_extern]
+fn cap_info(_: ()) -> ExternResult<CapInfo> {
+    Ok(capability_info()?)
+}
+
 #[hdk_extern]
 fn get_entry(header_hash: HeaderHash) -> ExternResult<GetOutput> {
-    Ok(GetOutput::new(get(header_hash, GetOptions)?))
+    Ok(GetOutput::new(get(header_hash, GetOptions::default())?))
 }
 
 /// accepts a cap claim and commits it to the local chain
@@ -104,6 +123,8 @@ fn send_assigned_cap_claim(agent: AgentPubKey) -> ExternResult<()> {
         access: (secret, agent.clone()).into(),
         functions,
         tag: tag.clone(),
+        curry_payloads: CurryPayloads::default(),
+        limits: CapLimits::default(),
     })?;
 
     // send the assigned cap token
@@ -48,7 +48,7 @@ fn create_post(post: Post) -> ExternResult<HeaderHash> {
 
 #[hdk_extern]
 fn get_entry(_: ()) -> ExternResult<GetOutput> {
-    Ok(GetOutput::new(get(hash_entry(&post())?, GetOptions)?))
+    Ok(GetOutput::new(get(hash_entry(&post())?, GetOptions::default())?))
 }
 
 #[hdk_extern]
@@ -90,6 +90,8 @@ fn init(_: ()) -> ExternResult<InitCallbackResult> {
         // empty access converts to unrestricted
         access: ().into(),
         functions,
+        curry_payloads: CurryPayloads::default(),
+        limits: CapLimits::default(),
     })?;
 
     Ok(InitCallbackResult::Pass)
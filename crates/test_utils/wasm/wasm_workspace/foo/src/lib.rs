@@ -12,6 +12,8 @@ fn init(_: ()) -> ExternResult<InitCallbackResult> {
         // empty access converts to unrestricted
         access: ().into(),
         functions,
+        curry_payloads: CurryPayloads::default(),
+        limits: CapLimits::default(),
     })?;
 
     Ok(InitCallbackResult::Pass)
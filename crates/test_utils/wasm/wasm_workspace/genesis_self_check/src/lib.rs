@@ -0,0 +1,9 @@
+use hdk3::prelude::*;
+
+#[hdk_extern]
+fn genesis_self_check(data: GenesisSelfCheckData) -> ExternResult<GenesisSelfCheckCallbackResult> {
+    Ok(match data.membrane_proof {
+        Some(_) => GenesisSelfCheckCallbackResult::Valid,
+        None => GenesisSelfCheckCallbackResult::Invalid("no membrane proof".into()),
+    })
+}
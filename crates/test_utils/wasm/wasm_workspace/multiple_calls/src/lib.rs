@@ -21,7 +21,7 @@ fn create_entry_multiple(n: TestInt) -> ExternResult<()> {
 fn get_entry_multiple(n: TestInt) -> ExternResult<TestBytes> {
     let mut bytes = vec![];
     'test_loop: for i in 0..n.0 {
-        match get(hash_entry(&Val(i))?, GetOptions)? {
+        match get(hash_entry(&Val(i))?, GetOptions::default())? {
             Some(element) => {
                 match element.entry().to_app_option::<Val>()? {
                     Some(v) => bytes.append(&mut v.0.to_le_bytes().to_vec()),
@@ -23,7 +23,7 @@ fn create_entry(_: ()) -> ExternResult<HeaderHash> {
 
 #[hdk_extern]
 fn get_entry(_: ()) -> ExternResult<GetOutput> {
-    Ok(GetOutput::new(get(hash_entry(&post())?, GetOptions)?))
+    Ok(GetOutput::new(get(hash_entry(&post())?, GetOptions::default())?))
 }
 
 #[hdk_extern]
@@ -0,0 +1,22 @@
+use hdk3::prelude::*;
+
+#[hdk_extern]
+fn create_x25519_keypair(_: ()) -> ExternResult<CreateX25519KeypairOutput> {
+    Ok(CreateX25519KeypairOutput::new(
+        hdk3::prelude::create_x25519_keypair()?,
+    ))
+}
+
+#[hdk_extern]
+fn encrypt(input: EncryptInput) -> ExternResult<EncryptOutput> {
+    Ok(EncryptOutput::new(hdk3::prelude::encrypt(
+        input.into_inner(),
+    )?))
+}
+
+#[hdk_extern]
+fn decrypt(input: DecryptInput) -> ExternResult<DecryptOutput> {
+    Ok(DecryptOutput::new(
+        hdk3::prelude::decrypt(input.into_inner())?.map(Bytes::from),
+    ))
+}
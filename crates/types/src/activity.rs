@@ -6,6 +6,7 @@ pub use holochain_zome_types::query::ChainStatus;
 use holochain_zome_types::{
     element::{Element, SignedHeaderHashed},
     query::HighestObserved,
+    warrant::SignedWarrant,
 };
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -23,6 +24,9 @@ pub struct AgentActivity<T = SignedHeaderHashed> {
     /// The highest chain header that has
     /// been observed by this authority.
     pub highest_observed: Option<HighestObserved>,
+    /// Warrants issued against this agent
+    /// by the validators of their activity.
+    pub warrants: Vec<SignedWarrant>,
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -59,7 +63,7 @@ impl From<AgentActivity<Element>> for holochain_zome_types::query::AgentActivity
             rejected_activity,
             status: a.status,
             highest_observed: a.highest_observed,
-            warrants: Vec::with_capacity(0),
+            warrants: a.warrants,
         }
     }
 }
@@ -211,6 +211,21 @@ impl DhtOp {
         }
     }
 
+    /// Get the entry from this op, if it carries one
+    pub fn entry(&self) -> Option<&Entry> {
+        match self {
+            DhtOp::StoreElement(_, _, e)
+            | DhtOp::RegisterUpdatedContent(_, _, e)
+            | DhtOp::RegisterUpdatedElement(_, _, e) => e.as_ref().map(|e| &**e),
+            DhtOp::StoreEntry(_, _, e) => Some(e),
+            DhtOp::RegisterAgentActivity(_, _)
+            | DhtOp::RegisterDeletedBy(_, _)
+            | DhtOp::RegisterDeletedEntryHeader(_, _)
+            | DhtOp::RegisterAddLink(_, _)
+            | DhtOp::RegisterRemoveLink(_, _) => None,
+        }
+    }
+
     /// Get the header from this op
     /// This requires cloning and converting the header
     /// as some ops don't hold the Header type
@@ -45,6 +45,13 @@ pub struct DnaDef {
 
     /// An array of zomes associated with your holochain application.
     pub zomes: Zomes,
+
+    /// How quickly each author may publish to this DNA's DHT.
+    /// This is part of the DNA's hash so changing the limits makes a new DNA.
+    /// The default, unlimited, limits aren't serialized so DNAs without
+    /// limits keep the hash they had before this field was added.
+    #[serde(default, skip_serializing_if = "PublishRateLimits::is_default")]
+    pub rate_limits: PublishRateLimits,
 }
 
 impl DnaDef {
@@ -63,6 +70,52 @@ impl DnaDef {
     }
 }
 
+/// Limits on how quickly a single author may publish to the DHT.
+///
+/// Authors check their own commits against these limits,
+/// authorities drop ops from authors that look to be over them
+/// and agent activity authorities reject headers that take their
+/// author's chain over `max_headers`.
+/// A limit of `None` is not enforced.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, SerializedBytes)]
+pub struct PublishRateLimits {
+    /// The length of the sliding window the limits apply to, in milliseconds.
+    pub window_ms: u64,
+    /// The most headers an author may publish within one window.
+    pub max_headers: Option<u32>,
+    /// The most bytes of app entry data an author may publish within one window.
+    pub max_bytes: Option<u64>,
+}
+
+impl PublishRateLimits {
+    /// Are any limits set
+    pub fn is_limited(&self) -> bool {
+        self.max_headers.is_some() || self.max_bytes.is_some()
+    }
+
+    /// Are these the default limits
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The start of the window that ends at this time.
+    /// Headers after this time are counted.
+    pub fn window_start(&self, end: Timestamp) -> Timestamp {
+        let end: chrono::DateTime<chrono::Utc> = end.into();
+        (end - chrono::Duration::milliseconds(self.window_ms as i64)).into()
+    }
+}
+
+impl Default for PublishRateLimits {
+    fn default() -> Self {
+        Self {
+            window_ms: 60_000,
+            max_headers: None,
+            max_bytes: None,
+        }
+    }
+}
+
 /// A DnaDef paired with its DnaHash
 pub type DnaDefHashed = HoloHashed<DnaDef>;
 
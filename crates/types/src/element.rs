@@ -126,7 +126,9 @@ impl<'a> ElementGroup<'a> {
                     wire.header
                         .into_header(entry_type.clone(), entry_hash.clone()),
                 )),
-                ValidationStatus::Abandoned => todo!(),
+                // Abandoned headers were never judged so
+                // they don't belong with the entry
+                ValidationStatus::Abandoned => (),
             }
         }
 
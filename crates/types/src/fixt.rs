@@ -16,6 +16,7 @@ use holo_hash::AgentPubKey;
 use holo_hash::EntryHash;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_zome_types::capability::CapGrant;
+use holochain_zome_types::capability::CapLimits;
 use holochain_zome_types::capability::CapSecret;
 use holochain_zome_types::capability::CurryPayloads;
 use holochain_zome_types::capability::GrantedFunction;
@@ -178,7 +179,9 @@ fixturator!(
                     granted_functions.insert(GrantedFunctionFixturator::new(Empty).next().unwrap());
                 }
                 granted_functions
-            }, // CurryPayloadsFixturator::new(Empty).next().unwrap(),
+            },
+            CurryPayloadsFixturator::new(Empty).next().unwrap(),
+            CapLimits::default(),
         )
     },
     {
@@ -199,7 +202,8 @@ fixturator!(
                 }
                 granted_functions
             },
-            // CurryPayloadsFixturator::new(Unpredictable).next().unwrap(),
+            CurryPayloadsFixturator::new(Unpredictable).next().unwrap(),
+            CapLimits::default(),
         )
     },
     {
@@ -218,7 +222,8 @@ fixturator!(
                 }
                 granted_functions
             },
-            // CurryPayloadsFixturator::new(Predictable).next().unwrap(),
+            CurryPayloadsFixturator::new(Predictable).next().unwrap(),
+            CapLimits::default(),
         )
     }
 );
@@ -458,6 +463,7 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limits: Default::default(),
     };
 
     curve Unpredictable DnaDef {
@@ -473,6 +479,7 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Unpredictable, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limits: Default::default(),
     };
 
     curve Predictable DnaDef {
@@ -488,6 +495,7 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Predictable, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limits: Default::default(),
     };
 );
 
@@ -5,6 +5,7 @@ use crate::Timestamp;
 use holo_hash::HeaderHash;
 use holochain_serialized_bytes::prelude::*;
 pub use holochain_zome_types::metadata::EntryDhtStatus;
+use holochain_zome_types::validate::ValidationStatus;
 use std::collections::BTreeSet;
 
 /// Timestamp of when the header was created with the headers hash.
@@ -18,18 +19,30 @@ pub struct TimedHeaderHash {
 
 /// Metadata returned from a GetMeta request.
 /// The Ord derive on TimedHeaderHash means each set is ordered by time.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, SerializedBytes)]
 pub struct MetadataSet {
     /// Headers that created or updated an entry.
     /// These are the headers that show the entry exists.
     pub headers: BTreeSet<TimedHeaderHash>,
-    // TODO: Implement after validation
-    /// Placeholder
+    /// Headers that created or updated an entry
+    /// but were rejected by validation.
     pub invalid_headers: BTreeSet<TimedHeaderHash>,
-    /// Deletes on a header
+    /// Deletes on a header or an entry's headers
     pub deletes: BTreeSet<TimedHeaderHash>,
     /// Updates on a header or entry
     pub updates: BTreeSet<TimedHeaderHash>,
+    /// [CreateLink] headers on an entry as a base.
+    ///
+    /// [CreateLink]: holochain_zome_types::header::CreateLink
+    pub links: BTreeSet<TimedHeaderHash>,
+    /// [DeleteLink] headers on a [CreateLink] header.
+    ///
+    /// [CreateLink]: holochain_zome_types::header::CreateLink
+    /// [DeleteLink]: holochain_zome_types::header::DeleteLink
+    pub link_removes: BTreeSet<TimedHeaderHash>,
+    /// The validation status of a header.
+    /// There is more than one status if the validators disagree.
+    pub validation_status: BTreeSet<ValidationStatus>,
     /// The status of an entry from an authority.
     /// This is simply a faster way of determining if
     /// there are any live headers on an entry.
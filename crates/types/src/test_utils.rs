@@ -44,6 +44,7 @@ pub fn fake_dna_zomes(uuid: &str, zomes: Vec<(ZomeName, DnaWasm)>) -> DnaFile {
             .unwrap(),
         uuid: uuid.to_string(),
         zomes: Vec::new(),
+        rate_limits: Default::default(),
     };
     tokio_safe_block_on::tokio_safe_block_forever_on(async move {
         let mut wasm_code = Vec::new();
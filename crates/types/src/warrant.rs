@@ -0,0 +1,92 @@
+//! Helpers for creating and checking [Warrant]s
+
+use crate::prelude::*;
+use holochain_keystore::KeystoreError;
+use holochain_zome_types::header::Header;
+pub use holochain_zome_types::warrant::*;
+
+/// Extension trait to keep zome types minimal
+#[async_trait::async_trait]
+pub trait SignedWarrantExt {
+    /// Create a warrant about the warrantee and sign it as the author
+    async fn new(
+        keystore: &KeystoreSender,
+        proof: WarrantProof,
+        warrantee: AgentPubKey,
+        author: AgentPubKey,
+    ) -> Result<SignedWarrant, KeystoreError>;
+    /// Check the warrant was signed by its author
+    async fn validate(&self) -> Result<(), KeystoreError>;
+    /// Check the proof holds up against the headers it is about.
+    /// `headers` are the headers of [WarrantProof::signed_headers], in the
+    /// same order, as found by the caller.
+    /// This only shows the warrantee signed the headers, so for
+    /// [WarrantProof::InvalidHeader] the caller must only pass a header
+    /// its own validation rejected.
+    async fn verify_proof(&self, headers: &[Header]) -> Result<bool, KeystoreError>;
+}
+
+#[async_trait::async_trait]
+impl SignedWarrantExt for SignedWarrant {
+    async fn new(
+        keystore: &KeystoreSender,
+        proof: WarrantProof,
+        warrantee: AgentPubKey,
+        author: AgentPubKey,
+    ) -> Result<Self, KeystoreError> {
+        let warrant = Warrant {
+            proof,
+            warrantee,
+            author,
+            timestamp: Timestamp::now().into(),
+        };
+        let signature = warrant.author.sign(keystore, warrant.clone()).await?;
+        Ok(Self { warrant, signature })
+    }
+
+    async fn validate(&self) -> Result<(), KeystoreError> {
+        if !self
+            .warrant
+            .author
+            .verify_signature(&self.signature, self.warrant.clone())
+            .await?
+        {
+            return Err(KeystoreError::InvalidSignature(
+                self.signature.clone(),
+                format!("warrant {:?}", self.warrant),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn verify_proof(&self, headers: &[Header]) -> Result<bool, KeystoreError> {
+        let warrantee = &self.warrant.warrantee;
+        let signed_headers = self.warrant.proof.signed_headers();
+        if headers.len() != signed_headers.len() {
+            return Ok(false);
+        }
+        // Every header must be the warrantee's and signed by them
+        for (header, (header_hash, signature)) in headers.iter().zip(signed_headers) {
+            if header.author() != warrantee || &HeaderHash::with_data_sync(header) != header_hash {
+                return Ok(false);
+            }
+            if !warrantee.verify_signature(signature, header).await? {
+                return Ok(false);
+            }
+        }
+        // A fork is two different headers at the same position
+        if let WarrantProof::ChainFork {
+            fork_seq,
+            first_header,
+            second_header,
+        } = &self.warrant.proof
+        {
+            if first_header.0 == second_header.0
+                || headers.iter().any(|h| h.header_seq() != *fork_seq)
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
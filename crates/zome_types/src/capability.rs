@@ -26,7 +26,9 @@
 
 mod claim;
 mod grant;
+mod info;
 mod secret;
 pub use claim::*;
 pub use grant::*;
+pub use info::*;
 pub use secret::*;
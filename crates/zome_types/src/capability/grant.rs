@@ -1,8 +1,9 @@
 use super::CapSecret;
+use crate::timestamp::Timestamp;
 use crate::zome::FunctionName;
 use crate::zome::ZomeName;
 use holo_hash::*;
-use holochain_serialized_bytes::SerializedBytes;
+use holochain_serialized_bytes::{SerializedBytes, SerializedBytesError, UnsafeBytes};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashSet};
 
@@ -37,9 +38,55 @@ impl From<holo_hash::AgentPubKey> for CapGrant {
 }
 
 #[derive(Default, PartialEq, Eq, Debug, Clone, serde::Serialize, serde::Deserialize)]
-/// @todo Ability to forcibly curry payloads into functions that are called with a claim.
+/// Fixed arguments that are forcibly curried into the payloads of functions called under a grant.
+///
+/// This allows a grant to be narrower than the functions it covers, e.g. a grant to
+/// `read_channel` that can only ever read one channel.
 pub struct CurryPayloads(pub BTreeMap<GrantedFunction, SerializedBytes>);
 
+impl CurryPayloads {
+    /// Merge the payload curried for a function into the payload it is being called with.
+    ///
+    /// If both payloads are maps (i.e. structs) the curried fields are set on the call payload,
+    /// overwriting any the caller provided. Otherwise the curried payload replaces the call
+    /// payload entirely. Functions with nothing curried are passed the call payload unchanged.
+    pub fn curry(
+        &self,
+        function: &GrantedFunction,
+        payload: SerializedBytes,
+    ) -> Result<SerializedBytes, SerializedBytesError> {
+        let curried = match self.0.get(function) {
+            Some(curried) => curried,
+            None => return Ok(payload),
+        };
+        let curried = decode_value(curried.bytes())?;
+        match (decode_value(payload.bytes())?, curried) {
+            (rmpv::Value::Map(mut fields), rmpv::Value::Map(curried_fields)) => {
+                for (key, value) in curried_fields {
+                    match fields.iter_mut().find(|(k, _)| k == &key) {
+                        Some(field) => field.1 = value,
+                        None => fields.push((key, value)),
+                    }
+                }
+                encode_value(&rmpv::Value::Map(fields))
+            }
+            (_, curried) => encode_value(&curried),
+        }
+    }
+}
+
+fn decode_value(bytes: &[u8]) -> Result<rmpv::Value, SerializedBytesError> {
+    rmpv::decode::read_value(&mut &bytes[..])
+        .map_err(|e| SerializedBytesError::FromBytes(e.to_string()))
+}
+
+fn encode_value(value: &rmpv::Value) -> Result<SerializedBytes, SerializedBytesError> {
+    let mut bytes = Vec::new();
+    rmpv::encode::write_value(&mut bytes, value)
+        .map_err(|e| SerializedBytesError::ToBytes(e.to_string()))?;
+    Ok(UnsafeBytes::from(bytes).into())
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 /// The entry for the ZomeCall capability grant.
 /// This data is committed to the callee's source chain as a private entry.
@@ -53,8 +100,12 @@ pub struct ZomeCallCapGrant {
     pub access: CapAccess,
     /// Set of functions to which this capability grants ZomeCall access
     pub functions: GrantedFunctions,
-    // @todo the payloads to curry to the functions
-    // pub curry_payloads: CurryPayloads,
+    /// Arguments fixed by the grantor for any of the functions
+    #[serde(default)]
+    pub curry_payloads: CurryPayloads,
+    /// How long and how many times the grant can be used
+    #[serde(default)]
+    pub limits: CapLimits,
 }
 
 impl ZomeCallCapGrant {
@@ -63,17 +114,55 @@ impl ZomeCallCapGrant {
         tag: String,
         access: CapAccess,
         functions: GrantedFunctions,
-        // @todo curry_payloads: CurryPayloads,
+        curry_payloads: CurryPayloads,
+        limits: CapLimits,
     ) -> Self {
         Self {
             tag,
             access,
             functions,
-            // @todo curry_payloads,
+            curry_payloads,
+            limits,
         }
     }
 }
 
+/// Limits on a grant beyond who may use it.
+///
+/// The default is a grant that never expires and can be used any number of times.
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CapLimits {
+    /// The grant can't be used at or after this time
+    pub expires_at: Option<Timestamp>,
+    /// The number of zome calls the grant can be used for.
+    /// Uses are counted locally by the grantor's conductor.
+    pub max_uses: Option<u32>,
+}
+
+impl CapLimits {
+    /// Limit a grant to be used before a time
+    pub fn expires_at(expires_at: Timestamp) -> Self {
+        Self {
+            expires_at: Some(expires_at),
+            ..Default::default()
+        }
+    }
+
+    /// Limit a grant to a number of uses
+    pub fn max_uses(max_uses: u32) -> Self {
+        Self {
+            max_uses: Some(max_uses),
+            ..Default::default()
+        }
+    }
+
+    /// Are the limits still met at `now` by a grant that has been used `uses` times?
+    pub fn allow(&self, now: &Timestamp, uses: u32) -> bool {
+        self.expires_at.map_or(true, |expires_at| now < &expires_at)
+            && self.max_uses.map_or(true, |max_uses| uses < max_uses)
+    }
+}
+
 impl From<ZomeCallCapGrant> for CapGrant {
     /// Create a new ZomeCall capability grant
     fn from(zccg: ZomeCallCapGrant) -> Self {
@@ -85,21 +174,31 @@ impl CapGrant {
     /// Given a grant, is it valid in isolation?
     /// In a world of CRUD, some new entry might update or delete an existing one, but we can check
     /// if a grant is valid in a standalone way.
+    ///
+    /// `check_time` is when the grant is being used and `check_uses` is the number of times it has
+    /// already been used, which are compared against the `CapLimits` of the grant.
     pub fn is_valid(
         &self,
         check_function: &GrantedFunction,
         check_agent: &AgentPubKey,
         check_secret: Option<&CapSecret>,
+        check_time: &Timestamp,
+        check_uses: u32,
     ) -> bool {
         match self {
             // Grant is always valid if the author matches the check agent.
             CapGrant::ChainAuthor(author) => author == check_agent,
             // Otherwise we need to do more work…
             CapGrant::RemoteAgent(ZomeCallCapGrant {
-                access, functions, ..
+                access,
+                functions,
+                limits,
+                ..
             }) => {
                 // The checked function needs to be in the grant…
                 functions.contains(check_function)
+                // The grant can't have expired or been used up…
+                && limits.allow(check_time, check_uses)
                 // The agent needs to be valid…
                 && match access {
                     // The grant is assigned so the agent needs to match…
@@ -174,3 +273,119 @@ impl From<(CapSecret, AgentPubKey)> for CapAccess {
 pub type GrantedFunction = (ZomeName, FunctionName);
 /// A collection of zome/function pairs
 pub type GrantedFunctions = HashSet<GrantedFunction>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fake_agent_pub_key;
+    use holochain_serialized_bytes::prelude::*;
+    use std::convert::TryInto;
+
+    #[derive(Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+    struct Read {
+        channel: String,
+        limit: u32,
+    }
+
+    #[derive(Serialize, Deserialize, SerializedBytes)]
+    struct Channel {
+        channel: String,
+    }
+
+    fn read() -> GrantedFunction {
+        ("chat".into(), "read".into())
+    }
+
+    fn curry_channel() -> CurryPayloads {
+        let mut payloads = BTreeMap::new();
+        payloads.insert(
+            read(),
+            Channel {
+                channel: "x".into(),
+            }
+            .try_into()
+            .unwrap(),
+        );
+        CurryPayloads(payloads)
+    }
+
+    #[test]
+    fn curried_fields_overwrite_the_call_payload() {
+        let payload: SerializedBytes = Read {
+            channel: "y".into(),
+            limit: 10,
+        }
+        .try_into()
+        .unwrap();
+        let curried: Read = curry_channel()
+            .curry(&read(), payload)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            curried,
+            Read {
+                channel: "x".into(),
+                limit: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn functions_without_a_curried_payload_are_untouched() {
+        let payload: SerializedBytes = Read {
+            channel: "y".into(),
+            limit: 10,
+        }
+        .try_into()
+        .unwrap();
+        let other: GrantedFunction = ("chat".into(), "write".into());
+        assert_eq!(
+            curry_channel().curry(&other, payload.clone()).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn non_map_payloads_are_replaced() {
+        let payload: SerializedBytes = ().try_into().unwrap();
+        let curried: Channel = curry_channel()
+            .curry(&read(), payload)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(curried.channel, "x");
+    }
+    #[test]
+    fn limits_are_checked_for_remote_agents() {
+        let author = fake_agent_pub_key(0);
+        let caller = fake_agent_pub_key(1);
+        let mut functions = HashSet::new();
+        functions.insert(read());
+        let grant = |limits| -> CapGrant {
+            ZomeCallCapGrant::new(
+                "".into(),
+                CapAccess::Unrestricted,
+                functions.clone(),
+                CurryPayloads::default(),
+                limits,
+            )
+            .into()
+        };
+        let now = Timestamp(100, 0);
+        let is_valid = |grant: &CapGrant, uses| grant.is_valid(&read(), &caller, None, &now, uses);
+
+        assert!(is_valid(&grant(CapLimits::default()), 1000));
+        assert!(is_valid(
+            &grant(CapLimits::expires_at(Timestamp(101, 0))),
+            0
+        ));
+        assert!(!is_valid(&grant(CapLimits::expires_at(now)), 0));
+        assert!(is_valid(&grant(CapLimits::max_uses(2)), 1));
+        assert!(!is_valid(&grant(CapLimits::max_uses(2)), 2));
+
+        // the author is never limited
+        let author_grant = CapGrant::ChainAuthor(author.clone());
+        assert!(author_grant.is_valid(&read(), &author, None, &now, 1000));
+    }
+}
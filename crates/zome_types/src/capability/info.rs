@@ -0,0 +1,21 @@
+use super::CapGrant;
+use holo_hash::*;
+use holochain_serialized_bytes::prelude::*;
+
+/// The capability that a zome call is running under.
+/// Lets the callee see who made the call and which grant allowed it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, SerializedBytes)]
+pub struct CapInfo {
+    /// The grant that authorized the call.
+    /// This is `CapGrant::ChainAuthor` when the chain author called their own zome.
+    pub grant: CapGrant,
+    /// The agent who made the call.
+    pub provenance: AgentPubKey,
+}
+
+impl CapInfo {
+    /// Constructor
+    pub fn new(grant: CapGrant, provenance: AgentPubKey) -> Self {
+        Self { grant, provenance }
+    }
+}
@@ -29,9 +29,83 @@ pub type CapGrantEntry = ZomeCallCapGrant;
 /// The data type written to the source chain to denote a capability claim
 pub type CapClaimEntry = CapClaim;
 
+/// Options for `get` and `get_details`
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
-/// @todo make some options for get
-pub struct GetOptions;
+pub struct GetOptions {
+    /// How much the network should be relied on.
+    pub strategy: GetStrategy,
+    /// Only look in the local databases and never go to the network.
+    pub local_only: bool,
+    /// Follow the live updates to the end of the update chain
+    /// and return the latest version instead of the requested one.
+    /// When the chain forks the oldest update is followed.
+    pub follow_updates: bool,
+    /// Timeout to await responses from the network.
+    /// Set to `None` for a default "best-effort".
+    pub timeout_ms: Option<u64>,
+    /// How many remote agents should be asked.
+    /// Set to `None` for a default "best-effort".
+    pub remote_agent_count: Option<u8>,
+}
+
+impl GetOptions {
+    /// Fetch the latest metadata from the network
+    /// so that deletes and updates are taken into account.
+    pub fn latest() -> Self {
+        Self {
+            strategy: GetStrategy::Latest,
+            ..Default::default()
+        }
+    }
+
+    /// Only go to the network if the content isn't held locally.
+    pub fn content() -> Self {
+        Self {
+            strategy: GetStrategy::Content,
+            ..Default::default()
+        }
+    }
+
+    /// Only look at the data this agent already holds.
+    pub fn local() -> Self {
+        Self {
+            local_only: true,
+            ..Default::default()
+        }
+    }
+
+    /// Get the latest update instead of the requested data.
+    pub fn follow_updates() -> Self {
+        Self {
+            follow_updates: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for GetOptions {
+    fn default() -> Self {
+        Self {
+            strategy: GetStrategy::Latest,
+            local_only: false,
+            follow_updates: false,
+            timeout_ms: None,
+            remote_agent_count: None,
+        }
+    }
+}
+
+/// How a get should use the network
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum GetStrategy {
+    /// Always ask the authorities for their latest metadata
+    /// and return the data as it currently stands on the DHT.
+    Latest,
+    /// Return the content if it is held locally
+    /// and only go to the network if it isn't.
+    /// Deletes and updates that haven't reached this agent are missed.
+    Content,
+}
 
 /// Structure holding the entry portion of a chain element.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, SerializedBytes)]
@@ -0,0 +1,43 @@
+//! Types for the genesis self check callback.
+//!
+//! The callback lets an app check the membrane proof an agent is joining
+//! with before its source chain is written. Authorities run the same check
+//! when validating an agent's `AgentValidationPkg`.
+
+use crate::zome_io::ExternOutput;
+use crate::CallbackResult;
+use holo_hash::AgentPubKey;
+use holochain_serialized_bytes::prelude::*;
+
+/// The data passed to the genesis self check callback.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct GenesisSelfCheckData {
+    /// The agent that is joining the network.
+    pub agent_key: AgentPubKey,
+    /// The proof the agent is joining with, if any.
+    pub membrane_proof: Option<SerializedBytes>,
+}
+
+/// The result of a single zome's genesis self check.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub enum GenesisSelfCheckCallbackResult {
+    /// The membrane proof is acceptable.
+    Valid,
+    /// The membrane proof is not acceptable, with a reason.
+    Invalid(String),
+}
+
+impl From<ExternOutput> for GenesisSelfCheckCallbackResult {
+    fn from(guest_output: ExternOutput) -> Self {
+        match guest_output.into_inner().try_into() {
+            Ok(v) => v,
+            Err(e) => Self::Invalid(format!("{:?}", e)),
+        }
+    }
+}
+
+impl CallbackResult for GenesisSelfCheckCallbackResult {
+    fn is_definitive(&self) -> bool {
+        matches!(self, GenesisSelfCheckCallbackResult::Invalid(_))
+    }
+}
@@ -25,6 +25,7 @@ pub mod element;
 pub mod entry;
 #[allow(missing_docs)]
 pub mod entry_def;
+pub mod genesis;
 #[allow(missing_docs)]
 pub mod header;
 #[allow(missing_docs)]
@@ -38,6 +39,8 @@ pub mod migrate_agent;
 pub mod post_commit;
 pub mod query;
 pub mod request;
+pub mod schedule;
+pub mod signal;
 pub mod signature;
 pub mod timestamp;
 #[allow(missing_docs)]
@@ -45,6 +48,7 @@ pub mod validate;
 #[allow(missing_docs)]
 pub mod validate_link;
 pub mod warrant;
+pub mod x_salsa20_poly1305;
 #[allow(missing_docs)]
 pub mod zome;
 #[allow(missing_docs)]
@@ -2,7 +2,7 @@
 
 use crate::{
     header::{EntryType, Header, HeaderType},
-    warrant::Warrant,
+    warrant::SignedWarrant,
 };
 use holo_hash::HeaderHash;
 pub use holochain_serialized_bytes::prelude::*;
@@ -37,9 +37,9 @@ pub struct AgentActivity {
     /// The highest chain header that has
     /// been observed by this authority.
     pub highest_observed: Option<HighestObserved>,
-    /// Warrants about this AgentActivity.
-    /// Placeholder for future.
-    pub warrants: Vec<Warrant>,
+    /// Warrants issued against this agent
+    /// by the validators of their activity.
+    pub warrants: Vec<SignedWarrant>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -96,7 +96,9 @@ pub struct ChainHead {
     pub hash: HeaderHash,
 }
 
-#[derive(Clone, Debug, Hash, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 /// The chain has been forked by these two headers
 pub struct ChainFork {
     /// The point where the chain has forked.
@@ -8,13 +8,19 @@ pub struct MetadataRequest {
     /// Get all the headers on an entry.
     /// Invalid request on a header.
     pub all_valid_headers: bool,
-    // TODO: Implement after validation
-    /// Placeholder
+    /// Get all the rejected headers on an entry.
+    /// Invalid request on a header.
     pub all_invalid_headers: bool,
-    /// Get all the deletes on a header
+    /// Get all the deletes on a header or an entry
     pub all_deletes: bool,
     /// Get all the updates on an entry or header
     pub all_updates: bool,
+    /// Get all the links on an entry
+    /// or all the link removes on a link add header
+    pub all_links: bool,
+    /// Get the validation status of a header.
+    /// Invalid request on an entry.
+    pub validation_status: bool,
     /// Placeholder
     pub follow_redirects: bool,
     /// Request the status of an entry.
@@ -30,6 +36,8 @@ impl Default for MetadataRequest {
             all_invalid_headers: false,
             all_deletes: true,
             all_updates: true,
+            all_links: false,
+            validation_status: true,
             follow_redirects: false,
             entry_dht_status: false,
         }
@@ -0,0 +1,54 @@
+//! Types for asking the conductor to call a zome function at a later time.
+
+use crate::zome::FunctionName;
+use core::time::Duration;
+use holochain_serialized_bytes::prelude::*;
+
+/// A request from a zome to have one of its own functions called by the
+/// conductor after some delay, and optionally again at a fixed interval.
+///
+/// The function is always called in the zome that scheduled it, as the agent
+/// that owns the cell, with the same host access as any other zome call.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct Schedule {
+    /// The function in the scheduling zome to call.
+    pub fn_name: FunctionName,
+    /// The payload passed to the function when it is called.
+    pub payload: SerializedBytes,
+    /// How long to wait before the first call.
+    pub delay: Duration,
+    /// If set, the function is scheduled again with this interval after every call.
+    /// Intervals shorter than a second are treated as one second.
+    pub repeat: Option<Duration>,
+}
+
+impl Schedule {
+    /// Call `fn_name` once after `delay` has elapsed.
+    pub fn once<F: Into<FunctionName>>(
+        fn_name: F,
+        payload: SerializedBytes,
+        delay: Duration,
+    ) -> Self {
+        Self {
+            fn_name: fn_name.into(),
+            payload,
+            delay,
+            repeat: None,
+        }
+    }
+
+    /// Call `fn_name` after `delay` has elapsed and then every `interval` after that.
+    pub fn repeating<F: Into<FunctionName>>(
+        fn_name: F,
+        payload: SerializedBytes,
+        delay: Duration,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            fn_name: fn_name.into(),
+            payload,
+            delay,
+            repeat: Some(interval),
+        }
+    }
+}
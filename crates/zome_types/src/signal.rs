@@ -0,0 +1,33 @@
+//! App-defined signals, emitted by zomes out across the app interfaces.
+
+use holochain_serialized_bytes::prelude::*;
+
+/// A signal emitted by a zome via `emit_signal`.
+///
+/// The optional `signal_type` is a discriminator that clients can use to
+/// filter signals and to decide what type to deserialize the data into.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct AppSignal {
+    /// A name for the kind of signal this is, chosen by the zome.
+    pub signal_type: Option<String>,
+    /// The signal's payload.
+    pub data: SerializedBytes,
+}
+
+impl AppSignal {
+    /// A signal with no type discriminator.
+    pub fn new(data: SerializedBytes) -> Self {
+        Self {
+            signal_type: None,
+            data,
+        }
+    }
+
+    /// A signal with a type discriminator.
+    pub fn typed<S: Into<String>>(signal_type: S, data: SerializedBytes) -> Self {
+        Self {
+            signal_type: Some(signal_type.into()),
+            data,
+        }
+    }
+}
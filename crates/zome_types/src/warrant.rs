@@ -1,6 +1,109 @@
 //! Types for warrants
+//!
+//! A warrant is a signed claim by a validator that an agent has done
+//! something invalid. It carries enough information for anyone holding it
+//! to check the claim for themselves.
+use crate::{signature::Signature, timestamp::Timestamp};
+use holo_hash::{AgentPubKey, HeaderHash};
 pub use holochain_serialized_bytes::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-/// Placeholder for warrant type
-pub struct Warrant;
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    SerializedBytes,
+)]
+/// A claim by an author that the warrantee has broken the rules
+pub struct Warrant {
+    /// The evidence of the invalid behaviour.
+    pub proof: WarrantProof,
+    /// The agent the warrant is about.
+    pub warrantee: AgentPubKey,
+    /// The validator that found the invalid behaviour.
+    pub author: AgentPubKey,
+    /// When the warrant was created.
+    pub timestamp: Timestamp,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    SerializedBytes,
+)]
+/// The evidence backing up a [Warrant]
+pub enum WarrantProof {
+    /// A header authored by the warrantee failed validation.
+    InvalidHeader {
+        /// The header that was rejected.
+        header_hash: HeaderHash,
+        /// The warrantee's signature of the header.
+        signature: Signature,
+        /// Why the header was rejected.
+        reason: String,
+    },
+    /// The warrantee has more than one header at the same
+    /// position on their source chain.
+    ChainFork {
+        /// The position where the chain has forked.
+        fork_seq: u32,
+        /// The first header at this position and the warrantee's signature of it.
+        first_header: (HeaderHash, Signature),
+        /// The second header at this position and the warrantee's signature of it.
+        second_header: (HeaderHash, Signature),
+    },
+}
+
+impl WarrantProof {
+    /// The headers the proof is about, each with the warrantee's signature of it.
+    /// Checking the proof starts with finding these headers.
+    pub fn signed_headers(&self) -> Vec<(&HeaderHash, &Signature)> {
+        match self {
+            WarrantProof::InvalidHeader {
+                header_hash,
+                signature,
+                ..
+            } => vec![(header_hash, signature)],
+            WarrantProof::ChainFork {
+                first_header,
+                second_header,
+                ..
+            } => vec![
+                (&first_header.0, &first_header.1),
+                (&second_header.0, &second_header.1),
+            ],
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    SerializedBytes,
+)]
+/// A [Warrant] signed by its author
+pub struct SignedWarrant {
+    /// The warrant that was signed.
+    pub warrant: Warrant,
+    /// The author's signature of the warrant.
+    pub signature: Signature,
+}
@@ -0,0 +1,174 @@
+//! Types for authenticated encryption with x25519 keys held in the keystore.
+//!
+//! Data is boxed with the libsodium compatible `crypto_box` construction,
+//! i.e. an x25519 key exchange followed by xsalsa20poly1305.
+//! The private keys never leave the keystore, zomes only ever see public keys.
+
+use holochain_serialized_bytes::prelude::*;
+
+/// Length of an x25519 public key in bytes.
+pub const X25519_PUB_KEY_BYTES: usize = 32;
+
+/// Length of an xsalsa20poly1305 nonce in bytes.
+pub const XSALSA20_POLY1305_NONCE_BYTES: usize = 24;
+
+/// The public half of an x25519 keypair held in the keystore.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, SerializedBytes)]
+pub struct X25519PubKey([u8; X25519_PUB_KEY_BYTES]);
+
+impl From<[u8; X25519_PUB_KEY_BYTES]> for X25519PubKey {
+    fn from(bytes: [u8; X25519_PUB_KEY_BYTES]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for X25519PubKey {
+    type Error = SecurityError;
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != X25519_PUB_KEY_BYTES {
+            return Err(SecurityError::BadLength(X25519_PUB_KEY_BYTES, bytes.len()));
+        }
+        let mut key = [0; X25519_PUB_KEY_BYTES];
+        key.copy_from_slice(bytes);
+        Ok(Self(key))
+    }
+}
+
+impl AsRef<[u8]> for X25519PubKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// The nonce that was used to encrypt some data.
+/// Nonces are generated by the keystore and are not secret.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct XSalsa20Poly1305Nonce([u8; XSALSA20_POLY1305_NONCE_BYTES]);
+
+impl From<[u8; XSALSA20_POLY1305_NONCE_BYTES]> for XSalsa20Poly1305Nonce {
+    fn from(bytes: [u8; XSALSA20_POLY1305_NONCE_BYTES]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for XSalsa20Poly1305Nonce {
+    type Error = SecurityError;
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != XSALSA20_POLY1305_NONCE_BYTES {
+            return Err(SecurityError::BadLength(
+                XSALSA20_POLY1305_NONCE_BYTES,
+                bytes.len(),
+            ));
+        }
+        let mut nonce = [0; XSALSA20_POLY1305_NONCE_BYTES];
+        nonce.copy_from_slice(bytes);
+        Ok(Self(nonce))
+    }
+}
+
+impl AsRef<[u8]> for XSalsa20Poly1305Nonce {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Encrypted data along with the nonce needed to decrypt it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct XSalsa20Poly1305EncryptedData {
+    nonce: XSalsa20Poly1305Nonce,
+    #[serde(with = "serde_bytes")]
+    encrypted_data: Vec<u8>,
+}
+
+impl XSalsa20Poly1305EncryptedData {
+    /// Constructor
+    pub fn new(nonce: XSalsa20Poly1305Nonce, encrypted_data: Vec<u8>) -> Self {
+        Self {
+            nonce,
+            encrypted_data,
+        }
+    }
+
+    /// nonce getter
+    pub fn as_nonce_ref(&self) -> &XSalsa20Poly1305Nonce {
+        &self.nonce
+    }
+
+    /// encrypted data getter
+    pub fn as_encrypted_data_ref(&self) -> &[u8] {
+        &self.encrypted_data
+    }
+}
+
+/// Input structure for encrypting some data from one x25519 key to another.
+///
+/// The sender's private key must be in the keystore doing the encryption.
+/// Using the same key as sender and recipient gives authenticated symmetric
+/// encryption that only the holder of that key can decrypt.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct Encrypt {
+    /// The key of the sender, whose private key is in the local keystore.
+    pub sender: X25519PubKey,
+    /// The key of the recipient, who will be able to decrypt the data.
+    pub recipient: X25519PubKey,
+    /// The data to encrypt.
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+impl Encrypt {
+    /// Encrypt `data` from `sender` to `recipient`.
+    pub fn new(sender: X25519PubKey, recipient: X25519PubKey, data: Vec<u8>) -> Self {
+        Self {
+            sender,
+            recipient,
+            data,
+        }
+    }
+
+    /// Encrypt `data` so that only the holder of `key` can decrypt it.
+    pub fn to_self(key: X25519PubKey, data: Vec<u8>) -> Self {
+        Self::new(key, key, data)
+    }
+}
+
+/// Input structure for decrypting some data sent from one x25519 key to another.
+///
+/// The recipient's private key must be in the keystore doing the decryption.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct Decrypt {
+    /// The key of the recipient, whose private key is in the local keystore.
+    pub recipient: X25519PubKey,
+    /// The key of the sender, which the data will be authenticated against.
+    pub sender: X25519PubKey,
+    /// The data to decrypt.
+    pub encrypted_data: XSalsa20Poly1305EncryptedData,
+}
+
+impl Decrypt {
+    /// Decrypt `encrypted_data` sent from `sender` to `recipient`.
+    pub fn new(
+        recipient: X25519PubKey,
+        sender: X25519PubKey,
+        encrypted_data: XSalsa20Poly1305EncryptedData,
+    ) -> Self {
+        Self {
+            recipient,
+            sender,
+            encrypted_data,
+        }
+    }
+
+    /// Decrypt `encrypted_data` which was encrypted with [Encrypt::to_self].
+    pub fn from_self(key: X25519PubKey, encrypted_data: XSalsa20Poly1305EncryptedData) -> Self {
+        Self::new(key, key, encrypted_data)
+    }
+}
+
+/// Key material of the wrong size.
+#[derive(Debug, thiserror::Error)]
+pub enum SecurityError {
+    /// Expected one length of bytes but got another.
+    #[error("Expected {0} bytes but got {1}")]
+    BadLength(usize, usize),
+}
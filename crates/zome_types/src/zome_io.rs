@@ -38,27 +38,32 @@ wasm_io_types!(
     pub struct AgentInfoOutput(agent_info::AgentInfo);
     pub struct CallInput(call::Call);
     pub struct CallOutput(ZomeCallResponse);
-    // @todo List all the local capability claims.
-    pub struct CapabilityClaimsInput(());
-    pub struct CapabilityClaimsOutput(());
-    // @todo List all the local capability grants.
-    pub struct CapabilityGrantsInput(());
-    pub struct CapabilityGrantsOutput(());
-    // @todo Get the capability for the current zome call.
+    // List the local capability claims, optionally filtered by grantor and tag.
+    pub struct CapabilityClaimsInput((Option<holo_hash::AgentPubKey>, Option<String>));
+    pub struct CapabilityClaimsOutput(Vec<capability::CapClaim>);
+    // List the live local capability grants and their header hashes, optionally filtered by tag.
+    pub struct CapabilityGrantsInput(Option<String>);
+    pub struct CapabilityGrantsOutput(Vec<(holo_hash::HeaderHash, capability::ZomeCallCapGrant)>);
+    // Get the grant that authorized the current zome call and the agent who made it.
     pub struct CapabilityInfoInput(());
-    pub struct CapabilityInfoOutput(());
+    pub struct CapabilityInfoOutput(capability::CapInfo);
     // The EntryDefId determines how a create is handled on the host side.
     // CapGrant and CapClaim are handled natively.
     // App entries are referenced by entry defs then SerializedBytes stuffed into an Entry::App.
     pub struct CreateInput((entry_def::EntryDefId, entry::Entry));
     // Header hash of the newly created element.
     pub struct CreateOutput(holo_hash::HeaderHash);
-    // @todo
-    pub struct DecryptInput(());
-    pub struct DecryptOutput(());
-    // @todo
-    pub struct EncryptInput(());
-    pub struct EncryptOutput(());
+    // Create a new x25519 keypair in the keystore and return the public key.
+    pub struct CreateX25519KeypairInput(());
+    pub struct CreateX25519KeypairOutput(x_salsa20_poly1305::X25519PubKey);
+    // Attempt to have the keystore decrypt some data.
+    // None if the data could not be authenticated against the sender.
+    pub struct DecryptInput(x_salsa20_poly1305::Decrypt);
+    pub struct DecryptOutput(Option<bytes::Bytes>);
+    // Attempt to have the keystore encrypt some data.
+    // The sender pubkey in the input needs to be found in the keystore for this to work.
+    pub struct EncryptInput(x_salsa20_poly1305::Encrypt);
+    pub struct EncryptOutput(x_salsa20_poly1305::XSalsa20Poly1305EncryptedData);
     // @todo
     pub struct ShowEnvInput(());
     pub struct ShowEnvOutput(());
@@ -86,15 +91,15 @@ wasm_io_types!(
     pub struct SignOutput(crate::signature::Signature);
     pub struct VerifySignatureInput(crate::signature::VerifySignature);
     pub struct VerifySignatureOutput(bool);
-    // @todo
-    pub struct ScheduleInput(core::time::Duration);
+    // Ask the conductor to call a function in this zome later, possibly repeatedly.
+    pub struct ScheduleInput(schedule::Schedule);
     pub struct ScheduleOutput(());
     // Same as CreateInput but also takes the HeaderHash of the updated element.
     pub struct UpdateInput((entry_def::EntryDefId, entry::Entry, holo_hash::HeaderHash));
     // Header hash of the newly committed element.
     pub struct UpdateOutput(holo_hash::HeaderHash);
     // Emit a Signal::App to subscribers on the interface
-    pub struct EmitSignalInput(SerializedBytes);
+    pub struct EmitSignalInput(signal::AppSignal);
     pub struct EmitSignalOutput(());
     // @todo
     pub struct DeleteInput(holo_hash::HeaderHash);